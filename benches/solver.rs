@@ -0,0 +1,58 @@
+//! Times the solver on a word list the size of a full dictionary, run with `cargo bench`.
+//! criterion isn't a dependency, so this is a plain `harness = false` bench timing with
+//! `Instant`.
+
+// The solver and scoring modules are compiled in on their own, without the rest of the bot or
+// the test harness their tests run in
+#![allow(dead_code, unused_imports)]
+
+#[path = "../src/scoring.rs"]
+mod scoring;
+#[path = "../src/solver.rs"]
+mod solver;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Words in a large dictionary
+const WORDS: usize = 50_000;
+/// Longest `candidates` and `best_guess` should take on `WORDS` words
+const TARGET: Duration = Duration::from_millis(50);
+const RUNS: u32 = 20;
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(121);
+    let words: Vec<String> = (0..WORDS)
+        .map(|_| (0..5).map(|_| rng.gen_range('a'..='z')).collect())
+        .collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let answer = words[0];
+    let guesses: Vec<(String, String)> = ["crane", "pilot"]
+        .iter()
+        .map(|guess| {
+            let colors = scoring::to_emoji(&scoring::score_guess(guess, answer));
+            (colors, guess.to_string())
+        })
+        .collect();
+
+    report("candidates", || {
+        solver::candidates(black_box(&guesses), black_box(&words)).len()
+    });
+    report("best_guess", || {
+        solver::best_guess(black_box(&words)).map(str::len)
+    });
+}
+
+/// Runs `f` `RUNS` times, printing how long it took on average
+fn report<T>(name: &str, mut f: impl FnMut() -> T) {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        black_box(f());
+    }
+    let average = start.elapsed() / RUNS;
+    let verdict = if average <= TARGET { "ok" } else { "SLOW" };
+    println!("{name} on {WORDS} words: {average:?} ({verdict}, target {TARGET:?})");
+}
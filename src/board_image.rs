@@ -0,0 +1,112 @@
+//! Renders the emoji board as a PNG, for crisper sharing than emoji text
+
+use std::io::BufWriter;
+
+/// Side length of a single tile, in pixels
+const TILE_SIZE: u32 = 48;
+/// Space between tiles and around the board, in pixels
+const GAP: u32 = 6;
+
+const BACKGROUND: [u8; 3] = [0x12, 0x12, 0x13];
+const CORRECT: [u8; 3] = [0x53, 0x8d, 0x4e];
+const INCORRECT: [u8; 3] = [0xb5, 0x9f, 0x3b];
+const MISSING: [u8; 3] = [0x3a, 0x3a, 0x3c];
+
+/// Draws one row of tiles per guess, colored from the guess' emoji representation.
+///
+/// Rows may have different lengths; the image is as wide as the longest one.
+pub fn render_board_png(guesses: &[(String, String)]) -> Vec<u8> {
+    let rows: Vec<Vec<[u8; 3]>> = guesses
+        .iter()
+        .map(|(emoji, _)| emoji.chars().filter_map(tile_color).collect())
+        .collect();
+    let columns = rows.iter().map(Vec::len).max().unwrap_or_default() as u32;
+
+    let width = GAP + columns * (TILE_SIZE + GAP);
+    let height = GAP + rows.len() as u32 * (TILE_SIZE + GAP);
+
+    let mut pixels = Vec::with_capacity((width * height * 3) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let color = tile_at(x, y).and_then(|(column, row)| {
+                rows.get(row as usize)
+                    .and_then(|tiles| tiles.get(column as usize))
+            });
+            pixels.extend_from_slice(color.unwrap_or(&BACKGROUND));
+        }
+    }
+
+    let mut png = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(BufWriter::new(&mut png), width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder
+            .write_header()
+            .expect("failed to write PNG header to memory");
+        writer
+            .write_image_data(&pixels)
+            .expect("failed to write PNG data to memory");
+    }
+    png
+}
+
+fn tile_color(emoji: char) -> Option<[u8; 3]> {
+    match emoji {
+        '🟩' => Some(CORRECT),
+        '🟨' => Some(INCORRECT),
+        '⬛' => Some(MISSING),
+        _ => None,
+    }
+}
+
+/// Returns the (column, row) of the tile covering a pixel, or `None` if the pixel is in a gap
+fn tile_at(x: u32, y: u32) -> Option<(u32, u32)> {
+    let in_tile = |offset: u32| offset >= GAP && (offset - GAP) % (TILE_SIZE + GAP) < TILE_SIZE;
+    if in_tile(x) && in_tile(y) {
+        Some(((x - GAP) / (TILE_SIZE + GAP), (y - GAP) / (TILE_SIZE + GAP)))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(png: &[u8]) -> (png::OutputInfo, Vec<u8>) {
+        let mut reader = png::Decoder::new(png).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        pixels.truncate(info.buffer_size());
+        (info, pixels)
+    }
+
+    /// Color of the pixel in the middle of a tile
+    fn tile_pixel(info: &png::OutputInfo, pixels: &[u8], column: u32, row: u32) -> [u8; 3] {
+        let x = GAP + column * (TILE_SIZE + GAP) + TILE_SIZE / 2;
+        let y = GAP + row * (TILE_SIZE + GAP) + TILE_SIZE / 2;
+        let offset = ((y * info.width + x) * 3) as usize;
+        pixels[offset..offset + 3].try_into().unwrap()
+    }
+
+    #[test]
+    fn board_decodes_as_png() {
+        let guesses = [
+            ("⬛🟨⬛⬛🟩".to_string(), "slate".to_string()),
+            ("🟩🟩🟩🟩🟩".to_string(), "crane".to_string()),
+        ];
+        let png = render_board_png(&guesses);
+        assert!(!png.is_empty());
+
+        let (info, pixels) = decode(&png);
+        assert_eq!(info.color_type, png::ColorType::Rgb);
+        assert_eq!(info.width, GAP + 5 * (TILE_SIZE + GAP));
+        assert_eq!(info.height, GAP + 2 * (TILE_SIZE + GAP));
+        assert_eq!(tile_pixel(&info, &pixels, 0, 0), MISSING);
+        assert_eq!(tile_pixel(&info, &pixels, 1, 0), INCORRECT);
+        assert_eq!(tile_pixel(&info, &pixels, 4, 0), CORRECT);
+        assert_eq!(tile_pixel(&info, &pixels, 2, 1), CORRECT);
+        assert_eq!(pixels[..3], BACKGROUND);
+    }
+}
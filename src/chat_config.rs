@@ -0,0 +1,108 @@
+//! Settings picked by each chat, persisted to `chat_config.json` in the assets directory
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+use crate::persist;
+
+/// Settings of every chat that changed one from its default
+static CHAT_CONFIGS: OnceCell<RwLock<ChatConfigs>> = OnceCell::new();
+/// Flag to indicate to our worker thread that a chat's settings have been updated
+static DIRTY_CHAT_CONFIGS: AtomicBool = AtomicBool::new(false);
+
+const CHAT_CONFIG_FILE: &str = "chat_config.json";
+/// Version of the chat config file format
+const CHAT_CONFIG_VERSION: u32 = 1;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ChatConfigs {
+    version: u32,
+    /// Settings by Telegram chat id
+    chats: BTreeMap<i64, ChatConfig>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatConfig {
+    /// Whether guesses have to be dictionary words, or any word of the right length is accepted
+    pub require_dictionary: bool,
+    /// Language replies to players are sent in, one of `i18n::LOCALES`
+    pub locale: String,
+    /// Category answers are drawn from, `None` for every game word
+    pub pool: Option<String>,
+    /// Whether the answer of a lost group game is sent to its players privately, so onlookers
+    /// can still try the word
+    pub private_answer: bool,
+}
+
+impl Default for ChatConfig {
+    fn default() -> Self {
+        Self {
+            require_dictionary: true,
+            locale: crate::i18n::DEFAULT_LOCALE.into(),
+            pool: None,
+            private_answer: false,
+        }
+    }
+}
+
+pub fn load(assets_dir: &Path) {
+    let configs = persist::load_json(&assets_dir.join(CHAT_CONFIG_FILE))
+        .expect("could not read chat config file")
+        .unwrap_or_else(|| ChatConfigs {
+            version: CHAT_CONFIG_VERSION,
+            ..Default::default()
+        });
+
+    CHAT_CONFIGS
+        .set(RwLock::new(configs))
+        .expect("CHAT_CONFIGS already initialized");
+}
+
+/// Settings of `chat_id`, or the defaults if it never changed them
+pub fn get(chat_id: i64) -> ChatConfig {
+    let configs = CHAT_CONFIGS.get().expect("CHAT_CONFIGS is not initialized");
+    let configs = configs.read().expect("failed to lock CHAT_CONFIGS");
+    configs.chats.get(&chat_id).cloned().unwrap_or_default()
+}
+
+/// Changes the settings of `chat_id` with `f`
+pub fn update(chat_id: i64, f: impl FnOnce(&mut ChatConfig)) {
+    let configs = CHAT_CONFIGS.get().expect("CHAT_CONFIGS is not initialized");
+    let mut configs = configs.write().expect("failed to lock CHAT_CONFIGS");
+
+    f(configs.chats.entry(chat_id).or_default());
+    DIRTY_CHAT_CONFIGS.store(true, Ordering::Relaxed);
+}
+
+/// Forgets the settings of `chat_id`, going back to the defaults
+pub fn remove(chat_id: i64) {
+    let configs = CHAT_CONFIGS.get().expect("CHAT_CONFIGS is not initialized");
+    let mut configs = configs.write().expect("failed to lock CHAT_CONFIGS");
+
+    if configs.chats.remove(&chat_id).is_some() {
+        DIRTY_CHAT_CONFIGS.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Writes the chat settings to disk if they changed since the last save
+pub fn save_if_dirty(assets_dir: &Path) -> io::Result<()> {
+    if !DIRTY_CHAT_CONFIGS.swap(false, Ordering::Relaxed) {
+        return Ok(());
+    }
+    // A failed save leaves the changes to the next one
+    save(assets_dir).inspect_err(|_| DIRTY_CHAT_CONFIGS.store(true, Ordering::Relaxed))
+}
+
+fn save(assets_dir: &Path) -> io::Result<()> {
+    let configs = CHAT_CONFIGS.get().expect("CHAT_CONFIGS is not initialized");
+    let configs = configs.read().expect("failed to lock CHAT_CONFIGS");
+    persist::save_json(&assets_dir.join(CHAT_CONFIG_FILE), &*configs)
+}
@@ -0,0 +1,131 @@
+//! Extra words a chat added for its own games only, persisted to `chat_<id>_words.txt` files in
+//! the assets directory
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, LineWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+use once_cell::sync::OnceCell;
+
+/// Words of each chat that added some, by chat id
+static CHAT_WORDS: OnceCell<RwLock<HashMap<i64, BTreeSet<String>>>> = OnceCell::new();
+/// Flag to indicate to our worker thread that a chat's words have been updated
+static DIRTY_CHAT_WORDS: AtomicBool = AtomicBool::new(false);
+
+fn file_name(chat_id: i64) -> String {
+    format!("chat_{chat_id}_words.txt")
+}
+
+/// Chat id of a `chat_<id>_words.txt` file
+fn parse_file_name(file_name: &str) -> Option<i64> {
+    file_name
+        .strip_prefix("chat_")?
+        .strip_suffix("_words.txt")?
+        .parse()
+        .ok()
+}
+
+pub fn load(assets_dir: &Path) {
+    let mut chat_words = HashMap::new();
+    for entry in fs::read_dir(assets_dir).expect("could not read assets directory") {
+        let path = entry.expect("could not read assets directory").path();
+        let Some(chat_id) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(parse_file_name)
+        else {
+            continue;
+        };
+
+        let file = File::open(&path).expect("could not open chat word file");
+        let words: BTreeSet<String> = BufReader::new(file)
+            .lines()
+            .map(|line| line.expect("could not parse line"))
+            .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+            .map(|line| line.trim().to_lowercase())
+            .collect();
+        log::info!("Loaded {} words of chat {chat_id}", words.len());
+        chat_words.insert(chat_id, words);
+    }
+
+    CHAT_WORDS
+        .set(RwLock::new(chat_words))
+        .expect("CHAT_WORDS already initialized");
+}
+
+/// Words `chat_id` added, empty if it has none
+pub fn words(chat_id: i64) -> BTreeSet<String> {
+    let chat_words = CHAT_WORDS.get().expect("CHAT_WORDS is not initialized");
+    let chat_words = chat_words.read().expect("failed to lock CHAT_WORDS");
+    chat_words.get(&chat_id).cloned().unwrap_or_default()
+}
+
+pub fn contains(chat_id: i64, word: &str) -> bool {
+    let chat_words = CHAT_WORDS.get().expect("CHAT_WORDS is not initialized");
+    let chat_words = chat_words.read().expect("failed to lock CHAT_WORDS");
+    chat_words
+        .get(&chat_id)
+        .is_some_and(|words| words.contains(word))
+}
+
+/// Adds `words` to the chat's list, returning the ones that weren't in it yet
+pub fn add<'a>(chat_id: i64, words: &[&'a str]) -> Vec<&'a str> {
+    let chat_words = CHAT_WORDS.get().expect("CHAT_WORDS is not initialized");
+    let mut chat_words = chat_words.write().expect("failed to lock CHAT_WORDS");
+    let chat_words = chat_words.entry(chat_id).or_default();
+
+    let added: Vec<&str> = words
+        .iter()
+        .copied()
+        .filter(|word| chat_words.insert(word.to_string()))
+        .collect();
+    if !added.is_empty() {
+        DIRTY_CHAT_WORDS.store(true, Ordering::Relaxed);
+    }
+    added
+}
+
+/// Removes `words` from the chat's list, returning the ones that were in it
+pub fn remove<'a>(chat_id: i64, words: &[&'a str]) -> Vec<&'a str> {
+    let chat_words = CHAT_WORDS.get().expect("CHAT_WORDS is not initialized");
+    let mut chat_words = chat_words.write().expect("failed to lock CHAT_WORDS");
+    let Some(chat_words) = chat_words.get_mut(&chat_id) else {
+        return Vec::new();
+    };
+
+    let removed: Vec<&str> = words
+        .iter()
+        .copied()
+        .filter(|word| chat_words.remove(*word))
+        .collect();
+    if !removed.is_empty() {
+        DIRTY_CHAT_WORDS.store(true, Ordering::Relaxed);
+    }
+    removed
+}
+
+/// Writes every chat's words to disk if any of them changed since the last save
+pub fn save_if_dirty(assets_dir: &Path) -> io::Result<()> {
+    if !DIRTY_CHAT_WORDS.swap(false, Ordering::Relaxed) {
+        return Ok(());
+    }
+    // A failed save leaves the changes to the next one
+    save(assets_dir).inspect_err(|_| DIRTY_CHAT_WORDS.store(true, Ordering::Relaxed))
+}
+
+fn save(assets_dir: &Path) -> io::Result<()> {
+    let chat_words = CHAT_WORDS.get().expect("CHAT_WORDS is not initialized");
+    let chat_words = chat_words.read().expect("failed to lock CHAT_WORDS");
+    for (chat_id, words) in &*chat_words {
+        let file = File::create(assets_dir.join(file_name(*chat_id)))?;
+        let mut file = LineWriter::new(file);
+        writeln!(file, "{}", crate::WORD_FILE_HEADER)?;
+        for word in words {
+            writeln!(file, "{word}")?;
+        }
+    }
+    Ok(())
+}
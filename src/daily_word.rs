@@ -0,0 +1,199 @@
+//! Where the answer of the daily game comes from: the deterministic pick from the game words, or
+//! an external source set with the `DAILY_WORD_URL` env var
+
+use std::env;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use once_cell::sync::Lazy;
+
+/// Source of the daily answer, an external one if `DAILY_WORD_URL` is set
+static DAILY_SOURCE: Lazy<DailySource> = Lazy::new(|| match env::var("DAILY_WORD_URL") {
+    Ok(url) if !url.is_empty() => {
+        log::info!("Fetching daily words from {url}");
+        DailySource::Url(UrlDailyWord::new(url, LocalDailyWord))
+    }
+    _ => DailySource::Local(LocalDailyWord),
+});
+
+/// How long the external source has to answer before the word is picked locally instead
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Picks the answer of the daily game
+pub trait DailyWordProvider {
+    /// The answer on `date`, `None` if there's no word to pick it from
+    async fn word_for(&self, date: NaiveDate) -> Option<String>;
+}
+
+/// The daily source picked by the env vars
+pub enum DailySource {
+    Local(LocalDailyWord),
+    Url(UrlDailyWord<LocalDailyWord>),
+}
+
+impl DailyWordProvider for DailySource {
+    async fn word_for(&self, date: NaiveDate) -> Option<String> {
+        match self {
+            DailySource::Local(provider) => provider.word_for(date).await,
+            DailySource::Url(provider) => provider.word_for(date).await,
+        }
+    }
+}
+
+/// The daily source the bot was configured with
+pub fn source() -> &'static DailySource {
+    &DAILY_SOURCE
+}
+
+/// The deterministic pick from the game words, see `crate::local_daily_word`
+pub struct LocalDailyWord;
+
+impl DailyWordProvider for LocalDailyWord {
+    async fn word_for(&self, date: NaiveDate) -> Option<String> {
+        crate::local_daily_word(date)
+    }
+}
+
+/// Fetches the answer from a URL, where `{date}` is replaced with the date as `YYYY-MM-DD`. The
+/// response is either the word as plain text, or JSON with it in a `solution` or `word` field,
+/// like NYT's. When the fetch fails or doesn't give a word, `fallback` picks it instead.
+pub struct UrlDailyWord<F> {
+    url: String,
+    client: reqwest::Client,
+    fallback: F,
+}
+
+impl<F> UrlDailyWord<F> {
+    pub fn new(url: String, fallback: F) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .expect("could not build HTTP client");
+        Self {
+            url,
+            client,
+            fallback,
+        }
+    }
+
+    async fn fetch(&self, date: NaiveDate) -> reqwest::Result<String> {
+        let url = self
+            .url
+            .replace("{date}", &date.format("%Y-%m-%d").to_string());
+        self.client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await
+    }
+}
+
+impl<F: DailyWordProvider> DailyWordProvider for UrlDailyWord<F> {
+    async fn word_for(&self, date: NaiveDate) -> Option<String> {
+        match self.fetch(date).await {
+            Ok(body) => match parse_word(&body) {
+                Some(word) => return Some(word),
+                None => log::warn!(
+                    "{} did not answer with a word, picking one locally",
+                    self.url
+                ),
+            },
+            Err(err) => log::warn!("Could not fetch the daily word, picking one locally: {err}"),
+        }
+        self.fallback.word_for(date).await
+    }
+}
+
+/// The word in `body`, either as plain text or in the `solution` or `word` field of a JSON object
+fn parse_word(body: &str) -> Option<String> {
+    let body = body.trim();
+    let word = match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(json) => ["solution", "word"]
+            .iter()
+            .find_map(|field| json.get(field)?.as_str())?
+            .trim()
+            .to_lowercase(),
+        Err(_) => body.to_lowercase(),
+    };
+    // Anything else couldn't be guessed
+    (!word.is_empty() && word.chars().all(char::is_alphabetic)).then_some(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Always picks the same word
+    struct FixedWord(Option<&'static str>);
+
+    impl DailyWordProvider for FixedWord {
+        async fn word_for(&self, _date: NaiveDate) -> Option<String> {
+            self.0.map(String::from)
+        }
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+    }
+
+    /// Answers a single request with `body`, returning the URL to send it to and the request
+    fn serve_once(body: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/daily/{{date}}.json",
+            listener.local_addr().unwrap()
+        );
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let read = stream.read(&mut request).unwrap();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8_lossy(&request[..read]).into_owned()
+        });
+        (url, server)
+    }
+
+    #[tokio::test]
+    async fn fetched_word_is_used() {
+        let (url, server) = serve_once(r#"{"id": 1, "solution": "Slate"}"#);
+        let provider = UrlDailyWord::new(url, FixedWord(Some("crane")));
+        assert_eq!(provider.word_for(date()).await.as_deref(), Some("slate"));
+        let request = server.join().unwrap();
+        assert!(
+            request.starts_with("GET /daily/2024-03-01.json "),
+            "{request}"
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_fetch_falls_back() {
+        // Nothing listens on port 1, so the connection is refused
+        let provider =
+            UrlDailyWord::new("http://127.0.0.1:1/{date}".into(), FixedWord(Some("crane")));
+        assert_eq!(provider.word_for(date()).await.as_deref(), Some("crane"));
+
+        let (url, server) = serve_once("<html>not a word</html>");
+        let provider = UrlDailyWord::new(url, FixedWord(None));
+        assert_eq!(provider.word_for(date()).await, None);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn words_are_parsed_from_text_or_json() {
+        assert_eq!(parse_word("Crane\n").as_deref(), Some("crane"));
+        assert_eq!(parse_word(r#"{"word": "crane"}"#).as_deref(), Some("crane"));
+        assert_eq!(parse_word(r#"{"solution": 5}"#), None);
+        assert_eq!(parse_word("two words"), None);
+        assert_eq!(parse_word(""), None);
+    }
+}
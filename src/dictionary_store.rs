@@ -0,0 +1,105 @@
+//! Where the word lists are read from and saved to, so the word sets don't depend on the disk
+
+#[cfg(test)]
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+#[cfg(test)]
+use std::sync::{Arc, RwLock};
+
+/// Storage of the word list files, by file name
+pub trait DictionaryStore: Send + Sync {
+    /// Contents of the word list `name`, or `None` if there's no such list
+    fn load(&self, name: &str) -> io::Result<Option<String>>;
+    /// Replaces the contents of the word list `name`
+    fn save(&self, name: &str, contents: &str) -> io::Result<()>;
+}
+
+/// Word lists kept as files in a directory, like the assets directory
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+}
+
+impl DictionaryStore for FileStore {
+    fn load(&self, name: &str) -> io::Result<Option<String>> {
+        match fs::read_to_string(self.dir.join(name)) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// A crash mid-save leaves the old list intact instead of a partial one
+    fn save(&self, name: &str, contents: &str) -> io::Result<()> {
+        crate::persist::replace_file(&self.dir.join(name), contents.as_bytes())
+    }
+}
+
+/// Word lists kept in memory only, letting tests start from known word sets without files.
+/// Clones share their lists, so a test can look at what was saved to the one it handed out.
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    files: Arc<RwLock<HashMap<String, String>>>,
+}
+
+#[cfg(test)]
+impl MemoryStore {
+    pub fn new(files: HashMap<String, String>) -> Self {
+        Self {
+            files: Arc::new(RwLock::new(files)),
+        }
+    }
+}
+
+#[cfg(test)]
+impl DictionaryStore for MemoryStore {
+    fn load(&self, name: &str) -> io::Result<Option<String>> {
+        let files = self.files.read().expect("failed to lock MemoryStore");
+        Ok(files.get(name).cloned())
+    }
+
+    fn save(&self, name: &str, contents: &str) -> io::Result<()> {
+        let mut files = self.files.write().expect("failed to lock MemoryStore");
+        files.insert(name.to_string(), contents.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_store_round_trip() {
+        let store = MemoryStore::default();
+        assert_eq!(store.load("words.txt").unwrap(), None);
+        store.save("words.txt", "crane\nslate\n").unwrap();
+        assert_eq!(
+            store.load("words.txt").unwrap().as_deref(),
+            Some("crane\nslate\n")
+        );
+        // Clones see the same lists
+        assert!(store.clone().load("words.txt").unwrap().is_some());
+    }
+
+    #[test]
+    fn file_store_replaces_list() {
+        let dir = std::env::temp_dir().join(format!("telebot-store-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let store = FileStore::new(dir.clone());
+        store.save("words.txt", "crane\n").unwrap();
+        store.save("words.txt", "slate\n").unwrap();
+        assert_eq!(store.load("words.txt").unwrap().as_deref(), Some("slate\n"));
+        assert!(!dir.join("words.txt.tmp").exists());
+        assert_eq!(store.load("missing.txt").unwrap(), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+//! A stand-in for the Telegram bot API that records every request, so tests can run the dialogue
+//! handlers against a real `Bot` and check what they sent
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use serde_json::{json, Value};
+use teloxide::prelude::*;
+
+/// A request the bot made, by method name like `SendMessage` with its JSON parameters
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub method: String,
+    pub params: Value,
+}
+
+/// Fake API server running on a local port for as long as the process does
+pub struct FakeTelegram {
+    url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+    /// Whether every request is answered with an error, like a chat the bot was removed from
+    failing: Arc<AtomicBool>,
+}
+
+impl FakeTelegram {
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("could not bind fake Telegram");
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let failing = Arc::new(AtomicBool::new(false));
+        let recorded = requests.clone();
+        let fails = failing.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let recorded = recorded.clone();
+                let fails = fails.clone();
+                thread::spawn(move || serve(stream, &recorded, &fails));
+            }
+        });
+        Self {
+            url,
+            requests,
+            failing,
+        }
+    }
+
+    /// A bot sending its requests here
+    pub fn bot(&self) -> AutoSend<Bot> {
+        // A proxy from the environment couldn't reach the local port
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        Bot::with_client("1234:fake", client)
+            .set_api_url(reqwest::Url::parse(&self.url).unwrap())
+            .auto_send()
+    }
+
+    /// Makes every following request fail, or succeed again
+    pub fn fail_requests(&self, failing: bool) {
+        self.failing.store(failing, Ordering::Relaxed);
+    }
+
+    /// The requests made since the last call
+    pub fn take_requests(&self) -> Vec<Request> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+}
+
+/// Answers the requests sent over `stream` until the bot closes it
+fn serve(stream: TcpStream, recorded: &Mutex<Vec<Request>>, failing: &AtomicBool) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut stream = stream;
+    loop {
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+            return;
+        }
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+
+        // The path is `/bot<token>/<method>`
+        let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+        let method = path.rsplit('/').next().unwrap_or_default().to_string();
+        let params = serde_json::from_slice(&body).unwrap_or(Value::Null);
+        let body = if failing.load(Ordering::Relaxed) {
+            // Answered with 200 anyway, teloxide waits before reading a server error
+            json!({ "ok": false, "error_code": 400, "description": "Bad Request: chat not found" })
+        } else {
+            json!({ "ok": true, "result": response(&method, &params) })
+        };
+        recorded.lock().unwrap().push(Request { method, params });
+
+        let body = body.to_string();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        if stream.write_all(response.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+/// What Telegram would answer `method` with: the sent message for sends and edits, `true` for
+/// anything else
+fn response(method: &str, params: &Value) -> Value {
+    let method = method.to_lowercase();
+    if !method.starts_with("send") && !method.starts_with("edit") {
+        return Value::Bool(true);
+    }
+    json!({
+        "message_id": 1,
+        "date": 0,
+        "chat": { "id": params["chat_id"], "type": "private", "first_name": "Player" },
+        "text": params["text"].as_str().unwrap_or_default(),
+    })
+}
@@ -0,0 +1,1011 @@
+//! Replies to players, in each language a chat can pick with `/config language`. Operators can
+//! replace any of them in `messages.json` in the assets directory, as
+//! `{"<locale>": {"<message_key>": "<template>"}}`.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+
+/// Messages replaced by the operator, by locale
+static OVERRIDES: OnceCell<HashMap<String, HashMap<MsgKey, String>>> = OnceCell::new();
+
+const OVERRIDES_FILE: &str = "messages.json";
+
+/// Languages with a full set of messages
+pub const LOCALES: [&str; 3] = ["en", "es", "ru"];
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Every message sent to players. Each locale's table matches on all of them, so one can't be
+/// added without translating it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MsgKey {
+    GameStarted,
+    StrictGameStarted,
+    DailyStarted,
+    GameRunning,
+    GameEnded,
+    NoHintsLeft,
+    HintsDisabled,
+    HelpersDisabledDaily,
+    HelpersDisabledRanked,
+    MustFitClues,
+    NoValidWords,
+    HintUsesLastGuess,
+    Hint,
+    AllLettersKnown,
+    ClueUsesLastGuess,
+    Clue,
+    NoClue,
+    NoGuessesYet,
+    GuessesLeft,
+    AnswerLength,
+    WrongLength,
+    LettersOnly,
+    NotInDictionary,
+    NotAnAnswer,
+    Absent,
+    OnTrack,
+    ToughSpot,
+    Win,
+    Loss,
+    AnswerSentPrivately,
+    PrivateAnswer,
+    Lucky,
+    HintsUsed,
+    NothingToShare,
+    ShareAfterGame,
+    Streak,
+    InvalidGuess,
+    UnknownCommand,
+    NoAnswers,
+    NoAnswersOfLength,
+    InvalidMaxGuesses,
+    FeedbackDisabled,
+    FeedbackTooSoon,
+    FeedbackSent,
+    Maintenance,
+    ClueUsed,
+    RemoveWordUsage,
+    Help,
+    Welcome,
+    WordCheckLength,
+    WordCheckAnswer,
+    WordCheckGuess,
+    WordCheckUnknown,
+    FeedbackUsage,
+    OptedOut,
+    OptedIn,
+    StatsOff,
+    UserStats,
+    NoLeaderboard,
+    Leaderboard,
+    LeaderboardEntry,
+    SeasonEnds,
+    NoSeasonWins,
+    WinsEntry,
+    LastSeasonWinner,
+    WeekGames,
+    WeekTopPlayers,
+    WeekHardestWord,
+    UnknownModes,
+    DefaultModesCleared,
+    DefaultModesSet,
+    ModesList,
+    ModeStrict,
+    ModeMono,
+    ModeLetters,
+    ModeChaos,
+    ModeAnimate,
+    ModeMixed,
+    ModeCoach,
+    ModeRanked,
+    PresetsList,
+    PresetWithHints,
+    PresetWithoutHints,
+    PresetFitsClues,
+    GuessesOption,
+    ConfigSettings,
+    ConfigAdminsOnly,
+    LanguageSet,
+    LanguageUsage,
+    ConfigUsage,
+    DictionaryOn,
+    DictionaryOff,
+    PrivateAnswerOn,
+    PrivateAnswerOff,
+    UnknownSetting,
+    NoChatWords,
+    ChatWords,
+    ChatWordsAdminsOnly,
+    ChatWordsUsage,
+    WordsAdded,
+    WordsSkipped,
+    WordsRemoved,
+    NewAnswers,
+    AlreadyPresent,
+    WordBlocked,
+    WrongLengthWords,
+    WordCounts,
+    PoolShown,
+    PoolAll,
+    PoolSet,
+    UnknownPool,
+    TutorialIntro,
+    TutorialStart,
+    TutorialColors,
+    TutorialClose,
+    TutorialFinish,
+    TutorialDone,
+    TutorialEnded,
+    TutorialWrongLength,
+    TutorialReminder,
+    AdminsOnly,
+    EditsIgnored,
+    DictStats,
+    WordUsage,
+    HardestWords,
+    HardestWordsEntry,
+    NoHardestWords,
+    SelfTestRunning,
+    SelfTestFailed,
+    SelfTestNoAnswers,
+    SelfTestReport,
+    SelfTestUnsolved,
+    NoGamesThisWeek,
+    ScoreUsage,
+    ScoreLength,
+    ScoreLetter,
+    ScoreCorrect,
+    ScoreIncorrect,
+    ScoreMissing,
+    SetAnswerUsage,
+    AnswerBlocked,
+    AnswerNotSpellable,
+    AnswerSet,
+    MaintenanceOn,
+    MaintenanceOff,
+    MaintenanceUsage,
+    SavePaused,
+    SaveResumed,
+    ClearStatsUsage,
+    ClearUserConfirm,
+    ClearAllConfirm,
+    NothingToConfirm,
+    StatsCleared,
+    StatsBackupFailed,
+    DiffGameWords,
+    DiffDictWords,
+    DiffSummary,
+    DiffAdded,
+    DiffRemoved,
+    DiffNoBase,
+    DiffUnreadable,
+    Error,
+}
+
+/// Reads the operator's replacement messages, if there are any
+pub fn load_overrides(assets_dir: &Path) {
+    let path = assets_dir.join(OVERRIDES_FILE);
+    let overrides = if path.exists() {
+        // A typo in the operator's file shouldn't keep the bot from starting
+        read_overrides(&path).unwrap_or_else(|err| {
+            log::warn!(
+                "Ignoring {}, using the built-in messages: {err}",
+                path.display()
+            );
+            HashMap::new()
+        })
+    } else {
+        HashMap::new()
+    };
+
+    OVERRIDES
+        .set(overrides)
+        .expect("OVERRIDES already initialized");
+}
+
+fn read_overrides(path: &Path) -> Result<HashMap<String, HashMap<MsgKey, String>>, Box<dyn Error>> {
+    let file = File::open(path)?;
+    Ok(serde_json::from_reader(BufReader::new(file))?)
+}
+
+/// Renders the message for `key` in `locale`, falling back to English for unknown locales.
+/// `args` fill in the message's `{name}` placeholders.
+pub fn msg(locale: &str, key: MsgKey, args: &[(&str, &str)]) -> String {
+    let replaced = OVERRIDES
+        .get()
+        .and_then(|overrides| overrides.get(locale)?.get(&key));
+    let template = match (replaced, locale) {
+        (Some(template), _) => template.as_str(),
+        (None, "es") => spanish(key),
+        (None, "ru") => russian(key),
+        (None, _) => english(key),
+    };
+    crate::render_template(template, args)
+}
+
+/// Letters guesses can be made of in `locale`, which has to match the language of the word lists
+pub fn alphabet(locale: &str) -> &'static str {
+    match locale {
+        "es" => "abcdefghijklmnñopqrstuvwxyzáéíóúü",
+        "ru" => "абвгдеёжзийклмнопрстуфхцчшщъыьэюя",
+        _ => "abcdefghijklmnopqrstuvwxyz",
+    }
+}
+
+/// Rows of the keyboard layout used in `locale`, as shown by /keyboard
+pub fn keyboard_layout(locale: &str) -> &'static [&'static str] {
+    match locale {
+        "es" => &["qwertyuiop", "asdfghjklñ", "zxcvbnm"],
+        "ru" => &["йцукенгшщзхъ", "фывапролджэ", "ячсмитьбюё"],
+        _ => &["qwertyuiop", "asdfghjkl", "zxcvbnm"],
+    }
+}
+
+fn english(key: MsgKey) -> &'static str {
+    match key {
+        MsgKey::GameStarted => "Wordle game started - /guess any {length} letter word",
+        MsgKey::StrictGameStarted => {
+            "Strict wordle game started - /guess any {length} letter answer word"
+        }
+        MsgKey::DailyStarted => "Daily wordle #{number} started - /guess any {length} letter word",
+        MsgKey::GameRunning => "A game is already running — /guess to play or /exit to end",
+        MsgKey::GameEnded => "Ending game. Word was {answer}",
+        MsgKey::NoHintsLeft => "No hints left",
+        MsgKey::HintsDisabled => "Hints are off in this game",
+        MsgKey::HelpersDisabledDaily => "Helpers are disabled in daily mode",
+        MsgKey::HelpersDisabledRanked => "Helpers are disabled in ranked games",
+        MsgKey::MustFitClues => "{word} doesn't fit the clues so far, hard mode guesses have to",
+        MsgKey::NoValidWords => "no valid words remain given your guesses — /exit to end",
+        MsgKey::HintUsesLastGuess => "A hint would use up your last guess",
+        MsgKey::Hint => "Position {position} is {letter}. {tries}/{max}",
+        MsgKey::AllLettersKnown => "You already know every letter",
+        MsgKey::ClueUsesLastGuess => "A clue would use up your last guess",
+        MsgKey::Clue => "Clue: {clue}",
+        MsgKey::NoClue => "No clue available",
+        MsgKey::NoGuessesYet => "No guesses yet",
+        MsgKey::GuessesLeft => "Guesses left: {left}",
+        MsgKey::AnswerLength => "The answer has {length} letters",
+        MsgKey::WrongLength => "You sent '{guess}' ({length}), need {expected} letter words",
+        MsgKey::LettersOnly => "Letters only, please",
+        MsgKey::NotInDictionary => "{word} is not in the dictionary. /addword?",
+        MsgKey::NotAnAnswer => "{word} is a word, but not in the curated answer pool",
+        MsgKey::Absent => "Absent: {letters}",
+        MsgKey::OnTrack => "On track 👍 {count} words left",
+        MsgKey::ToughSpot => "Tough spot 😬 {count} words left",
+        MsgKey::Win => "You won. {tries}/{max}",
+        MsgKey::Loss => "You lost. {tries}/{max}. Cringe.\nAnswer was {answer}",
+        MsgKey::AnswerSentPrivately => "The answer was sent to the players privately",
+        MsgKey::PrivateAnswer => "The answer of your game in {chat} was {answer}",
+        MsgKey::Lucky => "Lucky! 🍀",
+        MsgKey::HintsUsed => "Helped by {count} hints, which don't count towards streaks",
+        MsgKey::NothingToShare => "No game was finished here yet, play one with /wordle",
+        MsgKey::ShareAfterGame => "The result can be shared with /share once the game is over",
+        MsgKey::Streak => "{flames} {count} in a row!",
+        MsgKey::InvalidGuess => "Invalid guess",
+        MsgKey::UnknownCommand => "Unknown command, try /help",
+        MsgKey::NoAnswers => "There are no words to play with right now",
+        MsgKey::NoAnswersOfLength => "There are no {length} letter words to play with",
+        MsgKey::InvalidMaxGuesses => "Games can have from 1 to {limit} guesses, as in guesses=8",
+        MsgKey::FeedbackDisabled => "Feedback is disabled for this bot",
+        MsgKey::FeedbackTooSoon => "You've sent feedback recently, please try again later",
+        MsgKey::FeedbackSent => "Thanks, feedback sent",
+        MsgKey::Maintenance => "Bot is under maintenance, try later",
+        MsgKey::ClueUsed => "Clue: {clue}. {tries}/{max}",
+        MsgKey::RemoveWordUsage => "Usage: /removeword <WORD> [..WORD2]",
+        MsgKey::Help => "\
+/wordle [MODE..] [LENGTH] [guesses=N] [WORD] - start a game, see /modes. A WORD is played as \
+the first guess
+/daily [MODE..] - play today's word, the same for everyone
+/tutorial - play a practice game that explains how to play
+/modes - list the modes a game can be started with
+/setdefault [MODE..] - start your games in these modes, or in none without any
+/pool [CATEGORY] - show or pick the category answers are drawn from
+/guess <WORD> - make a guess
+/guesses - show how many guesses are left
+/length - show how many letters the answer has
+/hint - reveal one letter of the answer, at the cost of a guess
+/clue - show the answer's clue if it has one, at the cost of a guess
+/image - show the current board as an image
+/keyboard - show which letters are known to be in the answer or not
+/exit - end the current game
+/addword [WORD] [..WORD2] - add words (or your last guess) to the dictionary during a game
+/removeword <WORD> [..WORD2] - remove words from the dictionary during a game
+/chatwords [add|remove WORD..] - show or change the words only this chat plays with
+/config [SETTING VALUE] - show or change this chat's settings, like the language
+/feedback <TEXT> - send feedback to the bot's operators
+/stats - show your stats
+/season - show this month's standings
+/leaderboard - show the best streaks in this chat
+/share - show the last game's result without its words, to paste elsewhere
+/optout - stop tracking your stats and delete them, /optin to undo
+/version - show which build is running",
+        MsgKey::Welcome => "Hi! Let's play\n{help}",
+        MsgKey::WordCheckLength => "{word} isn't {length} letters long",
+        MsgKey::WordCheckAnswer => "{word} is a valid guess and can be an answer",
+        MsgKey::WordCheckGuess => "{word} is a valid guess",
+        MsgKey::WordCheckUnknown => "{word} isn't in the dictionary",
+        MsgKey::FeedbackUsage => "Usage: /feedback <TEXT>",
+        MsgKey::OptedOut => {
+            "Stats tracking is now off for you and your stats were deleted. /optin to turn it back on"
+        }
+        MsgKey::OptedIn => "Stats tracking is now on for you",
+        MsgKey::StatsOff => "Stats tracking is off for you. /optin to turn it back on",
+        MsgKey::UserStats => {
+            "Played: {played}\nWon: {wins} ({win_rate}%)\nWon with hints: {hinted_wins}\n\
+             Streak: {streak} (best {max_streak})\nGuesses:"
+        }
+        MsgKey::NoLeaderboard => "Nobody finished a game in this chat yet",
+        MsgKey::Leaderboard => "Best streaks in this chat:",
+        MsgKey::LeaderboardEntry => "{rank}. {name} - {streak} in a row, {win_rate}% won",
+        MsgKey::SeasonEnds => "Season ends in {days}d {hours}h",
+        MsgKey::NoSeasonWins => "No wins yet this season",
+        MsgKey::WinsEntry => "{rank}. {name} - {wins} wins",
+        MsgKey::LastSeasonWinner => "Last season's winner: {name} with {wins} wins",
+        MsgKey::WeekGames => "This week: {games} games played",
+        MsgKey::WeekTopPlayers => "Top players:",
+        MsgKey::WeekHardestWord => "Hardest word: {word} (lost {loss_rate}% of {plays} games)",
+        MsgKey::UnknownModes => "Unknown modes {modes}, see /modes",
+        MsgKey::DefaultModesCleared => "Your games start without a mode again",
+        MsgKey::DefaultModesSet => "Your games now start in {modes} mode",
+        MsgKey::ModesList => "Modes for /wordle, they can be combined:",
+        MsgKey::ModeStrict => "only accept answer words as guesses",
+        MsgKey::ModeMono => "line up the board with your guesses in a code block",
+        MsgKey::ModeLetters => "show the letters of each guess under its colors",
+        MsgKey::ModeChaos => "shuffle the letters of /keyboard",
+        MsgKey::ModeAnimate => "reveal each guess a tile at a time",
+        MsgKey::ModeMixed => "play a word of a random length",
+        MsgKey::ModeCoach => "tell how well you're doing after each guess",
+        MsgKey::ModeRanked => "no /hint or /clue, like the daily game",
+        MsgKey::PresetsList => "Presets, which can be combined with modes:",
+        MsgKey::PresetWithHints => "{guesses} guesses, with hints",
+        MsgKey::PresetWithoutHints => "{guesses} guesses, without hints",
+        MsgKey::PresetFitsClues => "every guess has to fit the clues so far",
+        MsgKey::GuessesOption => "guesses=N - give the game N guesses, up to {limit}",
+        MsgKey::ConfigSettings => {
+            "dictionary {dictionary} - only accept dictionary words as guesses\n\
+             privateanswer {private_answer} - send the answer of lost games to the players instead of the chat\n\
+             language {language} - language of the replies, one of {languages}\n\
+             Change with /config <SETTING> <VALUE>"
+        }
+        MsgKey::ConfigAdminsOnly => "Only chat admins can change settings",
+        MsgKey::LanguageSet => "Language set to {locale}",
+        MsgKey::LanguageUsage => {
+            "Usage: /config language <{languages}>, the languages the word lists can be played in"
+        }
+        MsgKey::ConfigUsage => "Usage: /config <SETTING> on|off",
+        MsgKey::DictionaryOn => "Guesses have to be dictionary words from the next game on",
+        MsgKey::DictionaryOff => "Any word of the right length is accepted from the next game on",
+        MsgKey::PrivateAnswerOn => {
+            "Answers of lost games are sent to their players privately from the next game on"
+        }
+        MsgKey::PrivateAnswerOff => "Answers of lost games are shown in the chat from the next game on",
+        MsgKey::UnknownSetting => "Unknown setting {setting}, see /config",
+        MsgKey::NoChatWords => "This chat has no words of its own. /chatwords add <WORD> to add some",
+        MsgKey::ChatWords => "This chat's words: {words}",
+        MsgKey::ChatWordsAdminsOnly => "Only chat admins can change this chat's words",
+        MsgKey::ChatWordsUsage => "Usage: /chatwords [add|remove <WORD> [..WORD2]]",
+        MsgKey::WordsAdded => "Added {count} words {words}",
+        MsgKey::WordsSkipped => "Skipped {words}, they aren't allowed",
+        MsgKey::WordsRemoved => "Removed {count} words {words}",
+        MsgKey::NewAnswers => "New answers {answers}\nNew dictionary words {words}",
+        MsgKey::AlreadyPresent => "{count} already present {words}",
+        MsgKey::WordBlocked => "{word}: that word is blocked",
+        MsgKey::WrongLengthWords => "Not {length} letters {words}",
+        MsgKey::WordCounts => "There are now {answers} answers and {words} dictionary words",
+        MsgKey::PoolShown => "Answers are drawn from {pool}. /pool <{options}> to change",
+        MsgKey::PoolAll => "all words",
+        MsgKey::PoolSet => "Answers are now drawn from {pool}",
+        MsgKey::UnknownPool => "There's no {category} pool, pick one of {options}",
+        MsgKey::TutorialIntro => {
+            "Let's play a practice game! The answer is a 5 letter word, and you get 6 guesses to \
+             find it. Practice games don't count towards /stats, and /exit leaves anytime.\n\
+             Start with /guess slate"
+        }
+        MsgKey::TutorialStart => "Start with /guess slate",
+        MsgKey::TutorialColors => {
+            "🟩 means a letter is in the right spot, 🟨 that it's in the word but somewhere else, \
+             and ⬛ that it isn't in the word at all.\nKeep the green letters in your next guess, \
+             like /guess crate"
+        }
+        MsgKey::TutorialClose => {
+            "Close! In a real game /keyboard shows which letters are left, and /hint reveals one \
+             at the cost of a guess.\nNow try /guess crane"
+        }
+        MsgKey::TutorialFinish => "The answer is crane, /guess crane to finish",
+        MsgKey::TutorialDone => {
+            "You solved it! 🎉 Start a real game with /wordle, or see every command with /help"
+        }
+        MsgKey::TutorialEnded => "Tutorial ended, start a real game with /wordle",
+        MsgKey::TutorialWrongLength => "Practice guesses have to be {length} letter words. {prompt}",
+        MsgKey::TutorialReminder => "You're in the tutorial. {prompt}",
+        MsgKey::AdminsOnly => "Only the bot's admins can use {command}",
+        MsgKey::EditsIgnored => "Edits are ignored, send a new /guess instead",
+        MsgKey::DictStats => {
+            "Game words: {game_count} ({never_used} never used), from {game_source}\n\
+             Dictionary words: {dict_count}, from {dict_source}\nMost used: {most_used}\n\
+             Stalest: {stalest}"
+        }
+        MsgKey::WordUsage => "{word} ({uses}x, {days}d ago)",
+        MsgKey::HardestWords => "Hardest words:",
+        MsgKey::HardestWordsEntry => "{rank}. {word} - lost {loss_rate}% of {plays} games",
+        MsgKey::NoHardestWords => "No words have been played {plays} times yet",
+        MsgKey::SelfTestRunning => "Running the solver, this can take a moment",
+        MsgKey::SelfTestFailed => "Self test failed: {error}",
+        MsgKey::SelfTestNoAnswers => "No {length} letter answers to test",
+        MsgKey::SelfTestReport => {
+            "Solved {solved}/{sampled} answers in {average} guesses on average\n\
+             Worst case: {worst} in {worst_tries} guesses\nNeeded more than {max} guesses: {lost}"
+        }
+        MsgKey::SelfTestUnsolved => "Could not solve: {words}",
+        MsgKey::NoGamesThisWeek => "No games were played this week yet",
+        MsgKey::ScoreUsage => "Usage: /score <ANSWER> <GUESS>",
+        MsgKey::ScoreLength => "Both words have to be {length} letters",
+        MsgKey::ScoreLetter => "{position}. {letter} - {verdict}",
+        MsgKey::ScoreCorrect => "right spot",
+        MsgKey::ScoreIncorrect => "wrong spot",
+        MsgKey::ScoreMissing => "not in the answer, or no copies left",
+        MsgKey::SetAnswerUsage => "Usage: /setanswer <{length} LETTER WORD> [CHAT_ID]",
+        MsgKey::AnswerBlocked => "{word} is on the blocklist",
+        MsgKey::AnswerNotSpellable => "{word} has letters that can't be guessed in {locale}",
+        MsgKey::AnswerSet => "The next game in chat {chat_id} will use {word}",
+        MsgKey::MaintenanceOn => "Maintenance mode on",
+        MsgKey::MaintenanceOff => "Maintenance mode off",
+        MsgKey::MaintenanceUsage => "Usage: /maintenance on|off",
+        MsgKey::SavePaused => "Saving paused, /resumesave once the edits are done",
+        MsgKey::SaveResumed => "Saving resumed, changes are saved with the next save",
+        MsgKey::ClearStatsUsage => "Usage: /clearstats [USER_ID]",
+        MsgKey::ClearUserConfirm => {
+            "This will delete the stats of user {user_id}. Send /clearstats confirm within {seconds}s to go ahead"
+        }
+        MsgKey::ClearAllConfirm => {
+            "This will delete everyone's stats. Send /clearstats confirm within {seconds}s to go ahead"
+        }
+        MsgKey::NothingToConfirm => "Nothing to confirm, start with /clearstats [USER_ID]",
+        MsgKey::StatsCleared => "Stats cleared, backup saved to {path}",
+        MsgKey::StatsBackupFailed => "Could not back up the stats, nothing was cleared",
+        MsgKey::DiffGameWords => "Game words",
+        MsgKey::DiffDictWords => "Dictionary words",
+        MsgKey::DiffSummary => "{name}: {added} added and {removed} removed since {file}",
+        MsgKey::DiffAdded => "Added: {words}",
+        MsgKey::DiffRemoved => "Removed: {words}",
+        MsgKey::DiffNoBase => "{name}: there's no {file} to compare with",
+        MsgKey::DiffUnreadable => "{name}: could not read {file}: {error}",
+        MsgKey::Error => "Something went wrong, please try again",
+    }
+}
+
+fn spanish(key: MsgKey) -> &'static str {
+    match key {
+        MsgKey::GameStarted => "Partida empezada - /guess con cualquier palabra de {length} letras",
+        MsgKey::StrictGameStarted => {
+            "Partida estricta empezada - /guess con una palabra de {length} letras de las respuestas"
+        }
+        MsgKey::DailyStarted => {
+            "Wordle diario #{number} empezado - /guess con cualquier palabra de {length} letras"
+        }
+        MsgKey::GameRunning => {
+            "Ya hay una partida en curso — /guess para jugar o /exit para terminar"
+        }
+        MsgKey::GameEnded => "Partida terminada. La palabra era {answer}",
+        MsgKey::NoHintsLeft => "No quedan pistas",
+        MsgKey::HintsDisabled => "Las pistas están desactivadas en esta partida",
+        MsgKey::HelpersDisabledDaily => "Las ayudas están desactivadas en el modo diario",
+        MsgKey::HelpersDisabledRanked => "Las ayudas están desactivadas en las partidas clasificatorias",
+        MsgKey::MustFitClues => "{word} no encaja con las pistas, en modo difícil los intentos deben encajar",
+        MsgKey::NoValidWords => "no queda ninguna palabra válida con tus intentos — /exit para terminar",
+        MsgKey::HintUsesLastGuess => "Una pista gastaría tu último intento",
+        MsgKey::Hint => "La posición {position} es {letter}. {tries}/{max}",
+        MsgKey::AllLettersKnown => "Ya conoces todas las letras",
+        MsgKey::ClueUsesLastGuess => "Una definición gastaría tu último intento",
+        MsgKey::Clue => "Definición: {clue}",
+        MsgKey::NoClue => "No hay definición para esta palabra",
+        MsgKey::NoGuessesYet => "Todavía no hay intentos",
+        MsgKey::GuessesLeft => "Intentos restantes: {left}",
+        MsgKey::AnswerLength => "La respuesta tiene {length} letras",
+        MsgKey::WrongLength => {
+            "Enviaste '{guess}' ({length}), hacen falta palabras de {expected} letras"
+        }
+        MsgKey::LettersOnly => "Solo letras, por favor",
+        MsgKey::NotInDictionary => "{word} no está en el diccionario. ¿/addword?",
+        MsgKey::NotAnAnswer => "{word} es una palabra, pero no está entre las respuestas",
+        MsgKey::Absent => "No están: {letters}",
+        MsgKey::OnTrack => "Vas bien 👍 quedan {count} palabras",
+        MsgKey::ToughSpot => "Está difícil 😬 quedan {count} palabras",
+        MsgKey::Win => "Ganaste. {tries}/{max}",
+        MsgKey::Loss => "Perdiste. {tries}/{max}.\nLa respuesta era {answer}",
+        MsgKey::AnswerSentPrivately => "La respuesta se envió a los jugadores en privado",
+        MsgKey::PrivateAnswer => "La respuesta de tu partida en {chat} era {answer}",
+        MsgKey::Lucky => "¡Qué suerte! 🍀",
+        MsgKey::HintsUsed => "Con {count} pistas, que no cuentan para las rachas",
+        MsgKey::NothingToShare => "Aún no se terminó ninguna partida aquí, juega una con /wordle",
+        MsgKey::ShareAfterGame => "El resultado se puede compartir con /share al acabar la partida",
+        MsgKey::Streak => "{flames} ¡{count} seguidas!",
+        MsgKey::InvalidGuess => "Intento no válido",
+        MsgKey::UnknownCommand => "Comando desconocido, prueba /help",
+        MsgKey::NoAnswers => "Ahora mismo no hay palabras con las que jugar",
+        MsgKey::NoAnswersOfLength => "No hay palabras de {length} letras con las que jugar",
+        MsgKey::InvalidMaxGuesses => "Las partidas pueden tener de 1 a {limit} intentos, como en guesses=8",
+        MsgKey::FeedbackDisabled => "Los comentarios están desactivados en este bot",
+        MsgKey::FeedbackTooSoon => "Ya enviaste un comentario hace poco, prueba más tarde",
+        MsgKey::FeedbackSent => "Gracias, comentario enviado",
+        MsgKey::Maintenance => "El bot está en mantenimiento, prueba más tarde",
+        MsgKey::ClueUsed => "Definición: {clue}. {tries}/{max}",
+        MsgKey::RemoveWordUsage => "Uso: /removeword <WORD> [..WORD2]",
+        MsgKey::Help => "\
+/wordle [MODE..] [LENGTH] [guesses=N] [WORD] - empieza una partida, mira /modes. Una WORD se \
+juega como primer intento
+/daily [MODE..] - juega la palabra de hoy, la misma para todos
+/tutorial - juega una partida de práctica que explica cómo jugar
+/modes - lista los modos con los que se puede empezar una partida
+/setdefault [MODE..] - empieza tus partidas en estos modos, o sin modo si no das ninguno
+/pool [CATEGORY] - muestra o elige la categoría de la que salen las respuestas
+/guess <WORD> - haz un intento
+/guesses - muestra cuántos intentos quedan
+/length - muestra cuántas letras tiene la respuesta
+/hint - revela una letra de la respuesta, a cambio de un intento
+/clue - muestra la definición de la respuesta si la tiene, a cambio de un intento
+/image - muestra el tablero como imagen
+/keyboard - muestra qué letras se sabe que están o no en la respuesta
+/exit - termina la partida
+/addword [WORD] [..WORD2] - añade palabras (o tu último intento) al diccionario durante una partida
+/removeword <WORD> [..WORD2] - quita palabras del diccionario durante una partida
+/chatwords [add|remove WORD..] - muestra o cambia las palabras propias de este chat
+/config [SETTING VALUE] - muestra o cambia los ajustes del chat, como el idioma
+/feedback <TEXT> - envía un comentario a los operadores del bot
+/stats - muestra tus estadísticas
+/season - muestra la clasificación de este mes
+/leaderboard - muestra las mejores rachas de este chat
+/share - muestra el resultado de la última partida sin sus palabras, para pegarlo en otro sitio
+/optout - deja de registrar tus estadísticas y bórralas, /optin para deshacerlo
+/version - muestra qué versión está funcionando",
+        MsgKey::Welcome => "¡Hola! A jugar\n{help}",
+        MsgKey::WordCheckLength => "{word} no tiene {length} letras",
+        MsgKey::WordCheckAnswer => "{word} es un intento válido y puede ser una respuesta",
+        MsgKey::WordCheckGuess => "{word} es un intento válido",
+        MsgKey::WordCheckUnknown => "{word} no está en el diccionario",
+        MsgKey::FeedbackUsage => "Uso: /feedback <TEXT>",
+        MsgKey::OptedOut => {
+            "Tus estadísticas ya no se registran y se borraron. /optin para volver a registrarlas"
+        }
+        MsgKey::OptedIn => "Tus estadísticas se vuelven a registrar",
+        MsgKey::StatsOff => "Tus estadísticas no se registran. /optin para volver a registrarlas",
+        MsgKey::UserStats => {
+            "Jugadas: {played}\nGanadas: {wins} ({win_rate}%)\nGanadas con pistas: {hinted_wins}\n\
+             Racha: {streak} (mejor {max_streak})\nIntentos:"
+        }
+        MsgKey::NoLeaderboard => "Nadie ha terminado una partida en este chat todavía",
+        MsgKey::Leaderboard => "Mejores rachas de este chat:",
+        MsgKey::LeaderboardEntry => "{rank}. {name} - {streak} seguidas, {win_rate}% ganadas",
+        MsgKey::SeasonEnds => "La temporada acaba en {days}d {hours}h",
+        MsgKey::NoSeasonWins => "Todavía no hay victorias esta temporada",
+        MsgKey::WinsEntry => "{rank}. {name} - {wins} victorias",
+        MsgKey::LastSeasonWinner => "Ganador de la temporada pasada: {name} con {wins} victorias",
+        MsgKey::WeekGames => "Esta semana: {games} partidas jugadas",
+        MsgKey::WeekTopPlayers => "Mejores jugadores:",
+        MsgKey::WeekHardestWord => {
+            "Palabra más difícil: {word} (perdida en el {loss_rate}% de {plays} partidas)"
+        }
+        MsgKey::UnknownModes => "Modos desconocidos {modes}, mira /modes",
+        MsgKey::DefaultModesCleared => "Tus partidas vuelven a empezar sin modo",
+        MsgKey::DefaultModesSet => "Tus partidas ahora empiezan en modo {modes}",
+        MsgKey::ModesList => "Modos para /wordle, se pueden combinar:",
+        MsgKey::ModeStrict => "solo acepta palabras de las respuestas como intentos",
+        MsgKey::ModeMono => "alinea el tablero con tus intentos en un bloque de código",
+        MsgKey::ModeLetters => "muestra las letras de cada intento bajo sus colores",
+        MsgKey::ModeChaos => "desordena las letras de /keyboard",
+        MsgKey::ModeAnimate => "revela cada intento casilla a casilla",
+        MsgKey::ModeMixed => "juega una palabra de longitud aleatoria",
+        MsgKey::ModeCoach => "dice qué tal vas después de cada intento",
+        MsgKey::ModeRanked => "sin /hint ni /clue, como en la partida diaria",
+        MsgKey::PresetsList => "Niveles, que se pueden combinar con los modos:",
+        MsgKey::PresetWithHints => "{guesses} intentos, con pistas",
+        MsgKey::PresetWithoutHints => "{guesses} intentos, sin pistas",
+        MsgKey::PresetFitsClues => "cada intento debe encajar con las pistas",
+        MsgKey::GuessesOption => "guesses=N - da N intentos a la partida, hasta {limit}",
+        MsgKey::ConfigSettings => {
+            "dictionary {dictionary} - solo acepta palabras del diccionario como intentos\n\
+             privateanswer {private_answer} - envía la respuesta de las partidas perdidas a los jugadores en vez de al chat\n\
+             language {language} - idioma de las respuestas, uno de {languages}\n\
+             Cámbialos con /config <SETTING> <VALUE>"
+        }
+        MsgKey::ConfigAdminsOnly => "Solo los administradores del chat pueden cambiar los ajustes",
+        MsgKey::LanguageSet => "Idioma cambiado a {locale}",
+        MsgKey::LanguageUsage => {
+            "Uso: /config language <{languages}>, los idiomas en los que se pueden jugar las palabras"
+        }
+        MsgKey::ConfigUsage => "Uso: /config <SETTING> on|off",
+        MsgKey::DictionaryOn => {
+            "Desde la próxima partida los intentos tienen que ser palabras del diccionario"
+        }
+        MsgKey::DictionaryOff => {
+            "Desde la próxima partida se acepta cualquier palabra de la longitud correcta"
+        }
+        MsgKey::PrivateAnswerOn => {
+            "Desde la próxima partida las respuestas de las partidas perdidas se envían en privado a sus jugadores"
+        }
+        MsgKey::PrivateAnswerOff => {
+            "Desde la próxima partida las respuestas de las partidas perdidas se muestran en el chat"
+        }
+        MsgKey::UnknownSetting => "Ajuste desconocido {setting}, mira /config",
+        MsgKey::NoChatWords => {
+            "Este chat no tiene palabras propias. /chatwords add <WORD> para añadir algunas"
+        }
+        MsgKey::ChatWords => "Palabras de este chat: {words}",
+        MsgKey::ChatWordsAdminsOnly => {
+            "Solo los administradores del chat pueden cambiar sus palabras"
+        }
+        MsgKey::ChatWordsUsage => "Uso: /chatwords [add|remove <WORD> [..WORD2]]",
+        MsgKey::WordsAdded => "Añadidas {count} palabras {words}",
+        MsgKey::WordsSkipped => "Se omitieron {words}, no están permitidas",
+        MsgKey::WordsRemoved => "Quitadas {count} palabras {words}",
+        MsgKey::NewAnswers => "Nuevas respuestas {answers}\nNuevas palabras del diccionario {words}",
+        MsgKey::AlreadyPresent => "{count} ya estaban {words}",
+        MsgKey::WordBlocked => "{word}: esa palabra está bloqueada",
+        MsgKey::WrongLengthWords => "No tienen {length} letras {words}",
+        MsgKey::WordCounts => "Ahora hay {answers} respuestas y {words} palabras en el diccionario",
+        MsgKey::PoolShown => "Las respuestas salen de {pool}. /pool <{options}> para cambiarlo",
+        MsgKey::PoolAll => "todas las palabras",
+        MsgKey::PoolSet => "Las respuestas ahora salen de {pool}",
+        MsgKey::UnknownPool => "No hay categoría {category}, elige una de {options}",
+        MsgKey::TutorialIntro => {
+            "¡Juguemos una partida de práctica! La respuesta es una palabra inglesa de 5 letras, y \
+             tienes 6 intentos para encontrarla. Las partidas de práctica no cuentan para /stats, \
+             y /exit sale cuando quieras.\nEmpieza con /guess slate"
+        }
+        MsgKey::TutorialStart => "Empieza con /guess slate",
+        MsgKey::TutorialColors => {
+            "🟩 significa que la letra está en su sitio, 🟨 que está en la palabra pero en otro \
+             sitio, y ⬛ que no está en la palabra.\nMantén las letras verdes en tu próximo \
+             intento, como /guess crate"
+        }
+        MsgKey::TutorialClose => {
+            "¡Casi! En una partida de verdad /keyboard muestra qué letras quedan, y /hint revela \
+             una a cambio de un intento.\nAhora prueba /guess crane"
+        }
+        MsgKey::TutorialFinish => "La respuesta es crane, /guess crane para terminar",
+        MsgKey::TutorialDone => {
+            "¡Lo resolviste! 🎉 Empieza una partida de verdad con /wordle, o mira todos los \
+             comandos con /help"
+        }
+        MsgKey::TutorialEnded => "Práctica terminada, empieza una partida de verdad con /wordle",
+        MsgKey::TutorialWrongLength => {
+            "Los intentos de práctica tienen que ser palabras de {length} letras. {prompt}"
+        }
+        MsgKey::TutorialReminder => "Estás en la práctica. {prompt}",
+        MsgKey::AdminsOnly => "Solo los administradores del bot pueden usar {command}",
+        MsgKey::EditsIgnored => "Las ediciones se ignoran, envía un nuevo /guess",
+        MsgKey::DictStats => {
+            "Palabras del juego: {game_count} ({never_used} sin usar), de {game_source}\n\
+             Palabras del diccionario: {dict_count}, de {dict_source}\nMás usadas: {most_used}\n\
+             Más antiguas: {stalest}"
+        }
+        MsgKey::WordUsage => "{word} ({uses}x, hace {days}d)",
+        MsgKey::HardestWords => "Palabras más difíciles:",
+        MsgKey::HardestWordsEntry => {
+            "{rank}. {word} - perdida en el {loss_rate}% de {plays} partidas"
+        }
+        MsgKey::NoHardestWords => "Ninguna palabra se ha jugado {plays} veces todavía",
+        MsgKey::SelfTestRunning => "Ejecutando el solucionador, puede tardar un momento",
+        MsgKey::SelfTestFailed => "La autoprueba falló: {error}",
+        MsgKey::SelfTestNoAnswers => "No hay respuestas de {length} letras para probar",
+        MsgKey::SelfTestReport => {
+            "Resueltas {solved}/{sampled} respuestas en {average} intentos de media\n\
+             Peor caso: {worst} en {worst_tries} intentos\nNecesitaron más de {max} intentos: {lost}"
+        }
+        MsgKey::SelfTestUnsolved => "No se pudieron resolver: {words}",
+        MsgKey::NoGamesThisWeek => "Todavía no se jugó ninguna partida esta semana",
+        MsgKey::ScoreUsage => "Uso: /score <RESPUESTA> <INTENTO>",
+        MsgKey::ScoreLength => "Las dos palabras tienen que tener {length} letras",
+        MsgKey::ScoreLetter => "{position}. {letter} - {verdict}",
+        MsgKey::ScoreCorrect => "en su sitio",
+        MsgKey::ScoreIncorrect => "en otro sitio",
+        MsgKey::ScoreMissing => "no está en la respuesta, o no quedan copias",
+        MsgKey::SetAnswerUsage => "Uso: /setanswer <PALABRA DE {length} LETRAS> [ID_DEL_CHAT]",
+        MsgKey::AnswerBlocked => "{word} está en la lista de bloqueo",
+        MsgKey::AnswerNotSpellable => "{word} tiene letras que no se pueden adivinar en {locale}",
+        MsgKey::AnswerSet => "La próxima partida del chat {chat_id} usará {word}",
+        MsgKey::MaintenanceOn => "Modo de mantenimiento activado",
+        MsgKey::MaintenanceOff => "Modo de mantenimiento desactivado",
+        MsgKey::MaintenanceUsage => "Uso: /maintenance on|off",
+        MsgKey::SavePaused => "Guardado en pausa, /resumesave cuando terminen los cambios",
+        MsgKey::SaveResumed => {
+            "Guardado reanudado, los cambios se guardan en el siguiente guardado"
+        }
+        MsgKey::ClearStatsUsage => "Uso: /clearstats [ID_DE_USUARIO]",
+        MsgKey::ClearUserConfirm => {
+            "Esto borrará las estadísticas del usuario {user_id}. Envía /clearstats confirm en {seconds}s para continuar"
+        }
+        MsgKey::ClearAllConfirm => {
+            "Esto borrará las estadísticas de todos. Envía /clearstats confirm en {seconds}s para continuar"
+        }
+        MsgKey::NothingToConfirm => "Nada que confirmar, empieza con /clearstats [ID_DE_USUARIO]",
+        MsgKey::StatsCleared => "Estadísticas borradas, copia guardada en {path}",
+        MsgKey::StatsBackupFailed => {
+            "No se pudo hacer una copia de las estadísticas, no se borró nada"
+        }
+        MsgKey::DiffGameWords => "Palabras del juego",
+        MsgKey::DiffDictWords => "Palabras del diccionario",
+        MsgKey::DiffSummary => "{name}: {added} añadidas y {removed} quitadas desde {file}",
+        MsgKey::DiffAdded => "Añadidas: {words}",
+        MsgKey::DiffRemoved => "Quitadas: {words}",
+        MsgKey::DiffNoBase => "{name}: no hay {file} con que comparar",
+        MsgKey::DiffUnreadable => "{name}: no se pudo leer {file}: {error}",
+        MsgKey::Error => "Algo salió mal, inténtalo de nuevo",
+    }
+}
+
+fn russian(key: MsgKey) -> &'static str {
+    match key {
+        MsgKey::GameStarted => "Игра началась - /guess с любым словом из {length} букв",
+        MsgKey::StrictGameStarted => {
+            "Строгая игра началась - /guess со словом из {length} букв из ответов"
+        }
+        MsgKey::DailyStarted => {
+            "Ежедневная игра #{number} началась - /guess с любым словом из {length} букв"
+        }
+        MsgKey::GameRunning => "Игра уже идёт — /guess чтобы играть или /exit чтобы закончить",
+        MsgKey::GameEnded => "Игра окончена. Слово было {answer}",
+        MsgKey::NoHintsLeft => "Подсказок больше нет",
+        MsgKey::HintsDisabled => "В этой игре подсказки отключены",
+        MsgKey::HelpersDisabledDaily => "В ежедневном режиме помощь отключена",
+        MsgKey::HelpersDisabledRanked => "В рейтинговых играх помощь отключена",
+        MsgKey::MustFitClues => "{word} не подходит под подсказки, в сложном режиме попытки должны подходить",
+        MsgKey::NoValidWords => "с такими попытками не осталось допустимых слов — /exit, чтобы закончить",
+        MsgKey::HintUsesLastGuess => "Подсказка заняла бы последнюю попытку",
+        MsgKey::Hint => "Буква {position}: {letter}. {tries}/{max}",
+        MsgKey::AllLettersKnown => "Вы уже знаете все буквы",
+        MsgKey::ClueUsesLastGuess => "Определение заняло бы последнюю попытку",
+        MsgKey::Clue => "Определение: {clue}",
+        MsgKey::NoClue => "У этого слова нет определения",
+        MsgKey::NoGuessesYet => "Попыток ещё не было",
+        MsgKey::GuessesLeft => "Осталось попыток: {left}",
+        MsgKey::AnswerLength => "Букв в ответе: {length}",
+        MsgKey::WrongLength => "Вы отправили '{guess}' ({length}), нужны слова из {expected} букв",
+        MsgKey::LettersOnly => "Только буквы, пожалуйста",
+        MsgKey::NotInDictionary => "{word} нет в словаре. /addword?",
+        MsgKey::NotAnAnswer => "{word} есть в словаре, но не среди ответов",
+        MsgKey::Absent => "Нет в слове: {letters}",
+        MsgKey::OnTrack => "Хороший темп 👍 осталось слов: {count}",
+        MsgKey::ToughSpot => "Непросто 😬 осталось слов: {count}",
+        MsgKey::Win => "Победа. {tries}/{max}",
+        MsgKey::Loss => "Поражение. {tries}/{max}.\nОтвет был {answer}",
+        MsgKey::AnswerSentPrivately => "Ответ отправлен игрокам в личные сообщения",
+        MsgKey::PrivateAnswer => "Ответ вашей игры в {chat} был {answer}",
+        MsgKey::Lucky => "Повезло! 🍀",
+        MsgKey::HintsUsed => "Использовано подсказок: {count}, такие игры не идут в серию",
+        MsgKey::NothingToShare => "Здесь ещё не закончили ни одной игры, начните с /wordle",
+        MsgKey::ShareAfterGame => "Результатом можно поделиться через /share после конца игры",
+        MsgKey::Streak => "{flames} {count} подряд!",
+        MsgKey::InvalidGuess => "Неверная попытка",
+        MsgKey::UnknownCommand => "Неизвестная команда, см. /help",
+        MsgKey::NoAnswers => "Сейчас нет слов для игры",
+        MsgKey::NoAnswersOfLength => "Нет слов из {length} букв для игры",
+        MsgKey::InvalidMaxGuesses => {
+            "В игре может быть от 1 до {limit} попыток, например guesses=8"
+        }
+        MsgKey::FeedbackDisabled => "Отзывы для этого бота отключены",
+        MsgKey::FeedbackTooSoon => "Вы недавно отправляли отзыв, попробуйте позже",
+        MsgKey::FeedbackSent => "Спасибо, отзыв отправлен",
+        MsgKey::Maintenance => "Бот на обслуживании, попробуйте позже",
+        MsgKey::ClueUsed => "Определение: {clue}. {tries}/{max}",
+        MsgKey::RemoveWordUsage => "Использование: /removeword <WORD> [..WORD2]",
+        MsgKey::Help => "\
+/wordle [MODE..] [LENGTH] [guesses=N] [WORD] - начать игру, см. /modes. WORD станет первой \
+попыткой
+/daily [MODE..] - сыграть слово дня, одно для всех
+/tutorial - сыграть тренировочную игру с объяснением правил
+/modes - список режимов, с которыми можно начать игру
+/setdefault [MODE..] - начинать ваши игры в этих режимах, или без режима, если их не указать
+/pool [CATEGORY] - показать или выбрать категорию, из которой берутся ответы
+/guess <WORD> - сделать попытку
+/guesses - сколько осталось попыток
+/length - сколько букв в ответе
+/hint - открыть одну букву ответа ценой попытки
+/clue - показать определение ответа, если оно есть, ценой попытки
+/image - показать доску картинкой
+/keyboard - показать, какие буквы точно есть в ответе, а каких нет
+/exit - закончить игру
+/addword [WORD] [..WORD2] - добавить слова (или последнюю попытку) в словарь во время игры
+/removeword <WORD> [..WORD2] - убрать слова из словаря во время игры
+/chatwords [add|remove WORD..] - показать или изменить собственные слова этого чата
+/config [SETTING VALUE] - показать или изменить настройки чата, например язык
+/feedback <TEXT> - отправить отзыв операторам бота
+/stats - ваша статистика
+/season - таблица этого месяца
+/leaderboard - лучшие серии в этом чате
+/share - результат последней игры без слов, чтобы поделиться
+/optout - перестать вести и удалить вашу статистику, /optin чтобы вернуть
+/version - какая сборка запущена",
+        MsgKey::Welcome => "Привет! Давайте играть\n{help}",
+        MsgKey::WordCheckLength => "В слове {word} не {length} букв",
+        MsgKey::WordCheckAnswer => "{word} подходит как попытка и может быть ответом",
+        MsgKey::WordCheckGuess => "{word} подходит как попытка",
+        MsgKey::WordCheckUnknown => "{word} нет в словаре",
+        MsgKey::FeedbackUsage => "Использование: /feedback <TEXT>",
+        MsgKey::OptedOut => {
+            "Ваша статистика больше не ведётся и удалена. /optin чтобы снова её вести"
+        }
+        MsgKey::OptedIn => "Ваша статистика снова ведётся",
+        MsgKey::StatsOff => "Ваша статистика не ведётся. /optin чтобы снова её вести",
+        MsgKey::UserStats => {
+            "Сыграно: {played}\nПобед: {wins} ({win_rate}%)\nПобед с подсказками: {hinted_wins}\n\
+             Серия: {streak} (лучшая {max_streak})\nПопытки:"
+        }
+        MsgKey::NoLeaderboard => "В этом чате ещё никто не закончил игру",
+        MsgKey::Leaderboard => "Лучшие серии в этом чате:",
+        MsgKey::LeaderboardEntry => "{rank}. {name} - {streak} подряд, {win_rate}% побед",
+        MsgKey::SeasonEnds => "Сезон закончится через {days}д {hours}ч",
+        MsgKey::NoSeasonWins => "В этом сезоне ещё нет побед",
+        MsgKey::WinsEntry => "{rank}. {name} - побед: {wins}",
+        MsgKey::LastSeasonWinner => "Победитель прошлого сезона: {name}, побед: {wins}",
+        MsgKey::WeekGames => "На этой неделе сыграно игр: {games}",
+        MsgKey::WeekTopPlayers => "Лучшие игроки:",
+        MsgKey::WeekHardestWord => {
+            "Самое трудное слово: {word} (проиграно {loss_rate}% из {plays} игр)"
+        }
+        MsgKey::UnknownModes => "Неизвестные режимы {modes}, см. /modes",
+        MsgKey::DefaultModesCleared => "Ваши игры снова начинаются без режима",
+        MsgKey::DefaultModesSet => "Ваши игры теперь начинаются в режиме {modes}",
+        MsgKey::ModesList => "Режимы для /wordle, их можно сочетать:",
+        MsgKey::ModeStrict => "принимать как попытки только слова из ответов",
+        MsgKey::ModeMono => "выравнивать доску с попытками в блоке кода",
+        MsgKey::ModeLetters => "показывать буквы каждой попытки под её цветами",
+        MsgKey::ModeChaos => "перемешать буквы /keyboard",
+        MsgKey::ModeAnimate => "открывать каждую попытку по одной клетке",
+        MsgKey::ModeMixed => "играть слово случайной длины",
+        MsgKey::ModeCoach => "говорить, как идут дела, после каждой попытки",
+        MsgKey::ModeRanked => "без /hint и /clue, как в ежедневной игре",
+        MsgKey::PresetsList => "Уровни, их можно сочетать с режимами:",
+        MsgKey::PresetWithHints => "попыток: {guesses}, с подсказками",
+        MsgKey::PresetWithoutHints => "попыток: {guesses}, без подсказок",
+        MsgKey::PresetFitsClues => "каждая попытка должна подходить под подсказки",
+        MsgKey::GuessesOption => "guesses=N - дать игре N попыток, не больше {limit}",
+        MsgKey::ConfigSettings => {
+            "dictionary {dictionary} - принимать как попытки только слова из словаря\n\
+             privateanswer {private_answer} - отправлять ответ проигранных игр игрокам, а не в чат\n\
+             language {language} - язык ответов, один из {languages}\n\
+             Изменить: /config <SETTING> <VALUE>"
+        }
+        MsgKey::ConfigAdminsOnly => "Менять настройки могут только администраторы чата",
+        MsgKey::LanguageSet => "Язык изменён на {locale}",
+        MsgKey::LanguageUsage => {
+            "Использование: /config language <{languages}>, языки, на которых можно играть слова"
+        }
+        MsgKey::ConfigUsage => "Использование: /config <SETTING> on|off",
+        MsgKey::DictionaryOn => "Со следующей игры попытки должны быть словами из словаря",
+        MsgKey::DictionaryOff => "Со следующей игры принимается любое слово нужной длины",
+        MsgKey::PrivateAnswerOn => {
+            "Со следующей игры ответы проигранных игр отправляются игрокам в личные сообщения"
+        }
+        MsgKey::PrivateAnswerOff => "Со следующей игры ответы проигранных игр показываются в чате",
+        MsgKey::UnknownSetting => "Неизвестная настройка {setting}, см. /config",
+        MsgKey::NoChatWords => {
+            "У этого чата нет своих слов. /chatwords add <WORD> чтобы добавить"
+        }
+        MsgKey::ChatWords => "Слова этого чата: {words}",
+        MsgKey::ChatWordsAdminsOnly => "Менять слова чата могут только его администраторы",
+        MsgKey::ChatWordsUsage => "Использование: /chatwords [add|remove <WORD> [..WORD2]]",
+        MsgKey::WordsAdded => "Добавлено слов: {count} {words}",
+        MsgKey::WordsSkipped => "Пропущены {words}, они не разрешены",
+        MsgKey::WordsRemoved => "Удалено слов: {count} {words}",
+        MsgKey::NewAnswers => "Новые ответы {answers}\nНовые слова словаря {words}",
+        MsgKey::AlreadyPresent => "Уже есть: {count} {words}",
+        MsgKey::WordBlocked => "{word}: это слово заблокировано",
+        MsgKey::WrongLengthWords => "Не из {length} букв {words}",
+        MsgKey::WordCounts => "Теперь ответов: {answers}, слов в словаре: {words}",
+        MsgKey::PoolShown => "Ответы берутся из {pool}. /pool <{options}> чтобы изменить",
+        MsgKey::PoolAll => "всех слов",
+        MsgKey::PoolSet => "Теперь ответы берутся из {pool}",
+        MsgKey::UnknownPool => "Категории {category} нет, выберите одну из {options}",
+        MsgKey::TutorialIntro => {
+            "Сыграем тренировочную игру! Ответ - английское слово из 5 букв, и у вас 6 попыток. \
+             Тренировочные игры не идут в /stats, а /exit выходит в любой момент.\n\
+             Начните с /guess slate"
+        }
+        MsgKey::TutorialStart => "Начните с /guess slate",
+        MsgKey::TutorialColors => {
+            "🟩 значит, что буква на своём месте, 🟨 - что она есть в слове, но в другом месте, \
+             а ⬛ - что её в слове нет.\nОставьте зелёные буквы в следующей попытке, например \
+             /guess crate"
+        }
+        MsgKey::TutorialClose => {
+            "Почти! В настоящей игре /keyboard показывает, какие буквы остались, а /hint \
+             открывает одну ценой попытки.\nТеперь попробуйте /guess crane"
+        }
+        MsgKey::TutorialFinish => "Ответ - crane, /guess crane чтобы закончить",
+        MsgKey::TutorialDone => {
+            "Получилось! 🎉 Начните настоящую игру с /wordle или посмотрите все команды в /help"
+        }
+        MsgKey::TutorialEnded => "Тренировка окончена, начните настоящую игру с /wordle",
+        MsgKey::TutorialWrongLength => {
+            "Тренировочные попытки должны быть словами из {length} букв. {prompt}"
+        }
+        MsgKey::TutorialReminder => "Вы в тренировке. {prompt}",
+        MsgKey::AdminsOnly => "{command} доступна только администраторам бота",
+        MsgKey::EditsIgnored => "Правки не учитываются, отправьте новый /guess",
+        MsgKey::DictStats => {
+            "Слова игры: {game_count} ({never_used} не использовались), из {game_source}\n\
+             Слова словаря: {dict_count}, из {dict_source}\nЧаще всего: {most_used}\n\
+             Давно не было: {stalest}"
+        }
+        MsgKey::WordUsage => "{word} ({uses}x, {days}д назад)",
+        MsgKey::HardestWords => "Самые трудные слова:",
+        MsgKey::HardestWordsEntry => "{rank}. {word} - проиграно {loss_rate}% из {plays} игр",
+        MsgKey::NoHardestWords => "Ни одно слово ещё не сыграно {plays} раз",
+        MsgKey::SelfTestRunning => "Запускаю решатель, это может занять время",
+        MsgKey::SelfTestFailed => "Самопроверка не удалась: {error}",
+        MsgKey::SelfTestNoAnswers => "Нет ответов из {length} букв для проверки",
+        MsgKey::SelfTestReport => {
+            "Решено {solved}/{sampled} ответов, в среднем за {average} попыток\n\
+             Худший случай: {worst} за {worst_tries} попыток\nПонадобилось больше {max} попыток: {lost}"
+        }
+        MsgKey::SelfTestUnsolved => "Не удалось решить: {words}",
+        MsgKey::NoGamesThisWeek => "На этой неделе ещё не было игр",
+        MsgKey::ScoreUsage => "Использование: /score <ОТВЕТ> <ПОПЫТКА>",
+        MsgKey::ScoreLength => "Оба слова должны быть из {length} букв",
+        MsgKey::ScoreLetter => "{position}. {letter} - {verdict}",
+        MsgKey::ScoreCorrect => "на своём месте",
+        MsgKey::ScoreIncorrect => "не на своём месте",
+        MsgKey::ScoreMissing => "нет в ответе, или повторов больше нет",
+        MsgKey::SetAnswerUsage => "Использование: /setanswer <СЛОВО ИЗ {length} БУКВ> [ID_ЧАТА]",
+        MsgKey::AnswerBlocked => "{word} в списке запрещённых",
+        MsgKey::AnswerNotSpellable => "В {word} есть буквы, которые нельзя угадать в {locale}",
+        MsgKey::AnswerSet => "Следующая игра в чате {chat_id} будет с {word}",
+        MsgKey::MaintenanceOn => "Режим обслуживания включён",
+        MsgKey::MaintenanceOff => "Режим обслуживания выключен",
+        MsgKey::MaintenanceUsage => "Использование: /maintenance on|off",
+        MsgKey::SavePaused => "Сохранение приостановлено, /resumesave после правок",
+        MsgKey::SaveResumed => {
+            "Сохранение возобновлено, изменения сохранятся при следующем сохранении"
+        }
+        MsgKey::ClearStatsUsage => "Использование: /clearstats [ID_ПОЛЬЗОВАТЕЛЯ]",
+        MsgKey::ClearUserConfirm => {
+            "Статистика пользователя {user_id} будет удалена. Отправьте /clearstats confirm в течение {seconds} с, чтобы продолжить"
+        }
+        MsgKey::ClearAllConfirm => {
+            "Статистика всех игроков будет удалена. Отправьте /clearstats confirm в течение {seconds} с, чтобы продолжить"
+        }
+        MsgKey::NothingToConfirm => "Нечего подтверждать, начните с /clearstats [ID_ПОЛЬЗОВАТЕЛЯ]",
+        MsgKey::StatsCleared => "Статистика удалена, копия сохранена в {path}",
+        MsgKey::StatsBackupFailed => "Не удалось сделать копию статистики, ничего не удалено",
+        MsgKey::DiffGameWords => "Слова игры",
+        MsgKey::DiffDictWords => "Слова словаря",
+        MsgKey::DiffSummary => "{name}: добавлено {added}, удалено {removed} по сравнению с {file}",
+        MsgKey::DiffAdded => "Добавлены: {words}",
+        MsgKey::DiffRemoved => "Удалены: {words}",
+        MsgKey::DiffNoBase => "{name}: нет {file} для сравнения",
+        MsgKey::DiffUnreadable => "{name}: не удалось прочитать {file}: {error}",
+        MsgKey::Error => "Что-то пошло не так, попробуйте ещё раз",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    #[test]
+    fn malformed_overrides_are_an_error() {
+        let dir = std::env::temp_dir().join(format!("telebot-i18n-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(OVERRIDES_FILE);
+
+        fs::write(&path, r#"{"en": {"win": "Nice, {tries}/{max}"}}"#).unwrap();
+        let overrides = read_overrides(&path).unwrap();
+        assert_eq!(overrides["en"][&MsgKey::Win], "Nice, {tries}/{max}");
+
+        fs::write(&path, r#"{"en": {"win": "#).unwrap();
+        assert!(read_overrides(&path).is_err());
+        fs::write(&path, r#"{"en": {"no_such_message": "hi"}}"#).unwrap();
+        assert!(read_overrides(&path).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,110 @@
+//! Keyboard showing what's known about each letter, in the layout of the chat's language
+
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::scoring::Placement;
+
+/// Best placement each guessed letter got across `guesses`. A letter found in the right spot
+/// stays green even if a later guess moved it, and misplaced beats missing.
+pub fn letter_states(guesses: &[(String, String)]) -> HashMap<&str, Placement> {
+    let mut states: HashMap<&str, Placement> = HashMap::new();
+    for (emoji, word) in guesses {
+        let placements = emoji.chars().filter_map(|tile| match tile {
+            '🟩' => Some(Placement::Correct),
+            '🟨' => Some(Placement::Incorrect),
+            '⬛' => Some(Placement::Missing),
+            _ => None,
+        });
+        for (letter, placement) in word.graphemes(true).zip(placements) {
+            let state = states.entry(letter).or_insert(placement);
+            if rank(placement) > rank(*state) {
+                *state = placement;
+            }
+        }
+    }
+    states
+}
+
+fn rank(placement: Placement) -> u8 {
+    match placement {
+        Placement::Missing => 0,
+        Placement::Incorrect => 1,
+        Placement::Correct => 2,
+    }
+}
+
+/// Renders `layout` one row per line, for a monospace code block. Letters in the right spot are
+/// shown as `[A]`, misplaced ones as `(A)`, missing ones are blanked out and letters not guessed
+/// yet are left as they are.
+pub fn render_keyboard(guesses: &[(String, String)], layout: &[&str]) -> String {
+    let states = letter_states(guesses);
+    layout
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let keys: String = row
+                .graphemes(true)
+                .map(|letter| match states.get(letter) {
+                    Some(Placement::Correct) => format!("[{}]", letter.to_uppercase()),
+                    Some(Placement::Incorrect) => format!("({})", letter.to_uppercase()),
+                    Some(Placement::Missing) => " · ".to_string(),
+                    None => format!(" {letter} "),
+                })
+                .collect();
+            // Lower rows are shifted to the right like on a real keyboard
+            format!("{}{keys}", " ".repeat(i))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// `layout` with its letters shuffled, keeping the length of each row. The same `seed` always
+/// gives the same order, so a game's keyboard doesn't move around between guesses.
+pub fn shuffled_layout(layout: &[&str], seed: u64) -> Vec<String> {
+    let mut letters: Vec<&str> = layout.iter().flat_map(|row| row.graphemes(true)).collect();
+    letters.shuffle(&mut StdRng::seed_from_u64(seed));
+
+    let mut letters = letters.into_iter();
+    layout
+        .iter()
+        .map(|row| letters.by_ref().take(row.graphemes(true).count()).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_show_their_best_placement() {
+        let guesses = [
+            ("⬛🟨⬛⬛⬛".to_string(), "abcde".to_string()),
+            ("🟩⬛⬛⬛⬛".to_string(), "bxyzw".to_string()),
+            ("⬛🟩⬛⬛⬛".to_string(), "abfgh".to_string()),
+        ];
+        let states = letter_states(&guesses);
+        // Green beats an earlier yellow, and a later gray doesn't undo either
+        assert_eq!(states["b"], Placement::Correct);
+        assert_eq!(states["a"], Placement::Missing);
+        assert!(!states.contains_key("q"));
+
+        assert_eq!(
+            render_keyboard(&guesses, &["abq", "xyz"]),
+            " · [B] q \n  ·  ·  · "
+        );
+    }
+
+    #[test]
+    fn misplaced_beats_missing() {
+        let guesses = [
+            ("🟨⬛⬛".to_string(), "cat".to_string()),
+            ("⬛⬛⬛".to_string(), "ocd".to_string()),
+        ];
+        assert_eq!(render_keyboard(&guesses, &["cdo"]), "(C) ·  · ");
+    }
+}
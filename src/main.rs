@@ -1,416 +1,6556 @@
-use std::collections::BTreeSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader, LineWriter, Write};
+mod board_image;
+mod chat_config;
+mod chat_words;
+mod daily_word;
+mod dictionary_store;
+#[cfg(test)]
+mod fake_telegram;
+mod i18n;
+mod keyboard;
+mod persist;
+mod recent_words;
+mod scoring;
+mod solver;
+mod stats;
+mod user_config;
+mod word_set;
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::future::Future;
+use std::io;
+use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::RwLock;
-use std::time::Duration;
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::{env, thread};
 
-use derive_more::From;
-use once_cell::sync::OnceCell;
-use rand::prelude::IteratorRandom;
+use chrono::{Datelike, NaiveDate, Utc};
+use derive_more::{Display, From};
+use futures::future::join_all;
+use once_cell::sync::{Lazy, OnceCell};
+use rand::prelude::{IteratorRandom, Rng};
+use teloxide::dispatching::dialogue::{
+    DialogueDispatcher, DialogueStage, DialogueWithCx, InMemStorage, InMemStorageError, Storage,
+};
+use teloxide::dispatching::DispatcherHandlerRx;
 use teloxide::macros::Transition;
 use teloxide::prelude::*;
+use teloxide::types::{
+    ChatMemberUpdated, InlineQuery, InlineQueryResult, InlineQueryResultArticle, InputFile,
+    InputMessageContent, InputMessageContentText, ParseMode,
+};
+use teloxide::utils::markdown;
+use teloxide::RequestError;
+
+use daily_word::DailyWordProvider;
+use dictionary_store::{DictionaryStore, FileStore};
+use i18n::MsgKey;
+use scoring::{letter_count, score_guess, to_emoji, Placement};
+use unicode_segmentation::UnicodeSegmentation;
+use word_set::WordSet;
 
-// We use a BTree to keep insertions/deletions cheap
+/// Where word lists, stats and settings are kept, see `find_assets_dir`
+static ASSETS_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// Where the word lists are read from and saved to
+static DICTIONARY_STORE: OnceCell<Box<dyn DictionaryStore>> = OnceCell::new();
+// The word sets are packed into one sorted buffer each, see `WordSet`, so lookups are cheap
+// binary searches while an edit moves every word after it.
+// Only one of the word set locks is ever held at a time, and never across an `.await`, so the
+// bot's handlers and the dictionary worker can't deadlock each other.
 /// List of words that can be used by the game
-static GAME_WORDS: OnceCell<RwLock<BTreeSet<String>>> = OnceCell::new();
+static GAME_WORDS: OnceCell<RwLock<WordSet>> = OnceCell::new();
 /// List of words that won't be used by the game, but can be guessed by a player
-static DICT_WORDS: OnceCell<RwLock<BTreeSet<String>>> = OnceCell::new();
+static DICT_WORDS: OnceCell<RwLock<WordSet>> = OnceCell::new();
+/// Where `GAME_WORDS` and `DICT_WORDS` were loaded from, as shown by /dictstats
+static WORD_SOURCES: OnceCell<(String, String)> = OnceCell::new();
+/// When each game word was last drawn as an answer, and how many times it has been
+static WORD_USAGE: OnceCell<RwLock<HashMap<String, WordUsage>>> = OnceCell::new();
+/// How likely each word is to be drawn as an answer relative to the others, from the weight
+/// column of the word files. Words without one weigh `DEFAULT_WORD_WEIGHT`.
+static WORD_WEIGHTS: Lazy<RwLock<HashMap<String, u32>>> = Lazy::new(Default::default);
+/// Clues of the game words that have one, from the `clue` column of the word file
+static WORD_CLUES: OnceCell<HashMap<String, String>> = OnceCell::new();
+/// Other words that also win when a game word is drawn, like synonyms, from the comma separated
+/// `also` column of the word file
+static WORD_ALTERNATES: OnceCell<HashMap<String, Vec<String>>> = OnceCell::new();
+/// Category of the game words that have one, like `animals`, from the `category` column of the
+/// word file. Chats can limit their answers to one category with /pool.
+static WORD_CATEGORIES: OnceCell<HashMap<String, String>> = OnceCell::new();
+/// Answers set by admins for the next game in a chat, by chat id
+static FORCED_ANSWERS: Lazy<Mutex<HashMap<i64, String>>> = Lazy::new(Default::default);
+/// Words that can never be added to the dictionary, not even by admins
+static BLOCKED_WORDS: OnceCell<BTreeSet<String>> = OnceCell::new();
+/// How many times /hint can be used in a single game, from the `MAX_HINTS` env var
+static MAX_HINTS: Lazy<usize> = Lazy::new(|| match env::var("MAX_HINTS") {
+    Ok(value) => value.parse().unwrap_or_else(|_| {
+        log::warn!("Ignoring invalid MAX_HINTS {value:?}");
+        DEFAULT_MAX_HINTS
+    }),
+    Err(_) => DEFAULT_MAX_HINTS,
+});
+/// Message posted when the bot is added to a group, from the `WELCOME_MESSAGE` env var. The
+/// localized welcome and /help are posted without it.
+static WELCOME_MESSAGE: Lazy<Option<String>> = Lazy::new(|| env::var("WELCOME_MESSAGE").ok());
+/// Whether a chat's settings are deleted when the bot is removed from it, from the
+/// `FORGET_REMOVED_CHATS` env var
+static FORGET_REMOVED_CHATS: Lazy<bool> =
+    Lazy::new(|| env::var("FORGET_REMOVED_CHATS").is_ok_and(|value| value == "true"));
+/// Longest the worker waits between saves, from the `SAVE_INTERVAL_SECS` env var. Dictionary
+/// edits wake it sooner, stats and settings are only saved this often.
+static SAVE_INTERVAL: Lazy<Duration> =
+    Lazy::new(|| save_interval(env::var("SAVE_INTERVAL_SECS").ok().as_deref()));
+/// How long the worker waits after a dictionary edit before saving it, so a burst of edits is
+/// saved at once, from the `TELEBOT_SAVE_INTERVAL` env var in seconds
+static SAVE_DEBOUNCE: Lazy<Duration> = Lazy::new(|| match env::var("TELEBOT_SAVE_INTERVAL") {
+    Ok(value) => value.parse().map(Duration::from_secs).unwrap_or_else(|_| {
+        log::warn!("Ignoring invalid TELEBOT_SAVE_INTERVAL {value:?}");
+        DEFAULT_SAVE_DEBOUNCE
+    }),
+    Err(_) => DEFAULT_SAVE_DEBOUNCE,
+});
+/// Edits asking the worker for a save
+static SAVE_REQUESTED: Lazy<SaveRequests> = Lazy::new(Default::default);
+/// Template for the message sent when a game is won, from the `WIN_MESSAGE` env var. Replaces
+/// the localized message in every language when set.
+static WIN_MESSAGE: Lazy<Option<String>> = Lazy::new(|| env::var("WIN_MESSAGE").ok());
+/// Template for the message sent when a game is lost, from the `LOSS_MESSAGE` env var. Replaces
+/// the localized message in every language when set.
+static LOSS_MESSAGE: Lazy<Option<String>> = Lazy::new(|| env::var("LOSS_MESSAGE").ok());
+/// Length of the words games are played with, detected from the game words at startup
+static WORD_LENGTH: OnceCell<usize> = OnceCell::new();
+/// Languages whose alphabet spells every game word, the ones chats can play the word lists in
+static WORD_LOCALES: OnceCell<Vec<&'static str>> = OnceCell::new();
+/// Lengths of words that are loaded into the word sets
+static WORD_LENGTHS: Lazy<RangeInclusive<usize>> = Lazy::new(load_word_lengths);
+/// Lengths `/wordle mixed` games pick from, from the `MIXED_LENGTHS` env var. Defaults to every
+/// loaded length.
+static MIXED_LENGTHS: Lazy<RangeInclusive<usize>> = Lazy::new(|| {
+    let Ok(value) = env::var("MIXED_LENGTHS") else {
+        return WORD_LENGTHS.clone();
+    };
+    let range = value
+        .split_once('-')
+        .and_then(|(min, max)| Some(min.trim().parse().ok()?..=max.trim().parse().ok()?));
+    match range {
+        Some(range) if !range.is_empty() => range,
+        _ => {
+            log::warn!("Ignoring invalid MIXED_LENGTHS {value:?}");
+            WORD_LENGTHS.clone()
+        }
+    }
+});
 /// Flag to indicate to our worker thread that the dictionary has been updated
 static DIRTY_DICTIONARY: OnceCell<AtomicBool> = OnceCell::new();
+/// Lines of the words left out for being outside `WORD_LENGTHS`, by the custom file they're
+/// saved back to so they're still there once the range changes
+static FILTERED_WORD_LINES: Lazy<Mutex<HashMap<String, Vec<String>>>> = Lazy::new(Default::default);
+/// Flag to indicate to our worker thread that answers were drawn, changing their usage
+static DIRTY_USAGE: AtomicBool = AtomicBool::new(false);
 /// Flag to indicate to our worker thread that the process is exiting
 static APP_EXITING: OnceCell<AtomicBool> = OnceCell::new();
+/// Short names operators gave to commands, like `/w` for `/wordle`, from `aliases.txt` in the
+/// assets directory
+static COMMAND_ALIASES: OnceCell<HashMap<String, String>> = OnceCell::new();
+/// Character commands can start with besides `/`, like `!wordle`, from the `COMMAND_PREFIX` env var
+static COMMAND_PREFIX: Lazy<char> = Lazy::new(|| {
+    let Ok(value) = env::var("COMMAND_PREFIX") else {
+        return '/';
+    };
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(prefix), None) if prefix.is_ascii_punctuation() => prefix,
+        _ => {
+            log::warn!("Ignoring invalid COMMAND_PREFIX {value:?}, it has to be one symbol");
+            '/'
+        }
+    }
+});
+/// Telegram user ids allowed to run admin commands, from the `ADMIN_IDS` env var
+static ADMIN_IDS: OnceCell<BTreeSet<i64>> = OnceCell::new();
+/// Flag set by admins to hold off players while the bot is being worked on
+static MAINTENANCE: AtomicBool = AtomicBool::new(false);
+/// Flag set by admins to hold off the worker's saves during bulk edits
+static SAVE_PAUSED: AtomicBool = AtomicBool::new(false);
+/// When each user last sent `/feedback`, so the admin chat can't be flooded
+static LAST_FEEDBACK: Lazy<Mutex<HashMap<i64, Instant>>> = Lazy::new(Default::default);
+/// Minimum time between two commands of a user, from the `COMMAND_COOLDOWN_MS` env var. Zero
+/// turns the cooldown off.
+static COMMAND_COOLDOWN: Lazy<Duration> = Lazy::new(|| match env::var("COMMAND_COOLDOWN_MS") {
+    Ok(value) => value
+        .parse()
+        .map(Duration::from_millis)
+        .unwrap_or_else(|_| {
+            log::warn!("Ignoring invalid COMMAND_COOLDOWN_MS {value:?}");
+            DEFAULT_COMMAND_COOLDOWN
+        }),
+    Err(_) => DEFAULT_COMMAND_COOLDOWN,
+});
+/// When each user last sent a command that went through, by user id. Only users seen within
+/// `COMMAND_COOLDOWN` are kept.
+static LAST_COMMAND: Lazy<Mutex<HashMap<i64, Instant>>> = Lazy::new(Default::default);
+/// Games each user won in a row recently, with when they last won, by user id. These are kept
+/// in memory only, unlike the stats.
+static SESSION_STREAKS: Lazy<Mutex<HashMap<i64, (u32, Instant)>>> = Lazy::new(Default::default);
+/// Chats with an animated guess being revealed, see `reveal_guess`
+static ANIMATING_CHATS: Lazy<Mutex<HashSet<i64>>> = Lazy::new(Default::default);
+/// `/clearstats` requests waiting to be confirmed, by admin user id
+static PENDING_CLEARS: Lazy<Mutex<HashMap<i64, PendingClear>>> = Lazy::new(Default::default);
+/// Answer of the daily game and the day it's for, drawn by the first game of the day
+static DAILY_WORD: Lazy<Mutex<Option<(NaiveDate, String)>>> = Lazy::new(Default::default);
+/// Spoiler free result of each chat's last finished game, for `/share`
+static LAST_SHARES: Lazy<Mutex<HashMap<i64, String>>> = Lazy::new(Default::default);
+
+const DEFAULT_MAX_HINTS: usize = 1;
+
+const DEFAULT_WORD_WEIGHT: u32 = 1;
+
+/// Length games are played with when the game words don't all have the same length
+const DEFAULT_WORD_LENGTH: usize = 5;
+
+const DEFAULT_SAVE_INTERVAL: Duration = Duration::from_secs(2 * 60);
+
+const DEFAULT_SAVE_DEBOUNCE: Duration = Duration::from_secs(5);
+
+const DEFAULT_COMMAND_COOLDOWN: Duration = Duration::from_secs(1);
+
+/// First line of the word files written by the worker, identifying their format version.
+/// v1 files hold a word per line, optionally followed by tab separated `key=value` metadata, and
+/// lines starting with `#` are comments.
+const WORD_FILE_HEADER: &str = "#telebot-words v1";
+
+/// Word list played and guessed when neither a custom nor a base word file exists
+const FALLBACK_WORDS: &str = include_str!("fallback_words.txt");
+
+/// Every command the bot handles, which aliases can't take the name of
+const BUILTIN_COMMANDS: &[&str] = &[
+    "/addword",
+    "/chatwords",
+    "/clearstats",
+    "/clue",
+    "/config",
+    "/daily",
+    "/dictstats",
+    "/diffwords",
+    "/dumpanswers",
+    "/end",
+    "/exit",
+    "/feedback",
+    "/guess",
+    "/guesses",
+    "/hardestwords",
+    "/help",
+    "/hint",
+    "/image",
+    "/keyboard",
+    "/leaderboard",
+    "/left",
+    "/length",
+    "/maintenance",
+    "/modes",
+    "/optin",
+    "/optout",
+    "/pausesave",
+    "/pool",
+    "/recent",
+    "/removeword",
+    "/resumesave",
+    "/score",
+    "/season",
+    "/selftest",
+    "/setanswer",
+    "/setdefault",
+    "/share",
+    "/stats",
+    "/stop",
+    "/tutorial",
+    "/version",
+    "/wordle",
+];
+
+/// Commands players can still use during maintenance
+const MAINTENANCE_COMMANDS: &[&str] = &[
+    "/feedback",
+    "/help",
+    "/leaderboard",
+    "/modes",
+    "/optin",
+    "/optout",
+    "/season",
+    "/stats",
+    "/version",
+];
+
+/// Guesses a game gets unless a preset changes it
+const DEFAULT_MAX_GUESSES: usize = 6;
+
+/// Most guesses `/wordle guesses=N` can give a game, longer boards get hard to follow
+const MAX_GUESSES_LIMIT: usize = 12;
+
+/// How many times an answer has to be played before it's ranked by /hardestwords
+const HARDEST_WORDS_MIN_PLAYS: u32 = 5;
+
+/// How many additions /recent lists when it isn't given a number
+const RECENT_WORDS_SHOWN: usize = 10;
+
+/// How many players /leaderboard lists
+const LEADERBOARD_SIZE: usize = 10;
+
+/// Width of the longest bar of the guess distribution shown by /stats
+const STATS_BAR_WIDTH: u32 = 10;
+
+/// How many of the added and removed words /diffwords lists
+const DIFF_SAMPLE_SIZE: usize = 10;
+
+/// How many answers /selftest has the solver play, to keep it quick on big word lists
+const SELFTEST_SAMPLE_SIZE: usize = 200;
+
+/// How long a user has to wait between two `/feedback` messages
+const FEEDBACK_COOLDOWN: Duration = Duration::from_secs(10 * 60);
+
+/// How long after a win the next one still counts towards a session streak
+const SESSION_STREAK_WINDOW: Duration = Duration::from_secs(30 * 60);
+
+/// How many times a game result is sent before giving up
+const MAX_SEND_ATTEMPTS: u32 = 4;
+/// Wait before the first retry of a send that hit a network error, doubled on each retry
+const SEND_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Longest message Telegram accepts, in characters
+const MAX_MESSAGE_CHARS: usize = 4096;
+
+/// How long each tile of an animated guess takes to show up
+const ANIMATION_FRAME_DELAY: Duration = Duration::from_millis(400);
+
+/// How long an admin has to confirm a `/clearstats`
+const CLEAR_CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
 
 #[tokio::main]
 async fn main() {
-    // Load the dictionaries first
-    load_game_words();
-    load_dict_words();
-    DIRTY_DICTIONARY
-        .set(AtomicBool::new(false))
-        .expect("could not initialize DIRTY_DICTIONARY");
-    APP_EXITING
-        .set(AtomicBool::new(false))
-        .expect("could not initialize DIRTY_DICTIONARY");
+    dotenv::dotenv().ok();
+
+    let assets = find_assets_dir().unwrap_or_else(|err| panic!("could not find assets: {err}"));
+    // Load the dictionaries first, upgrading any files saved in an older format
+    let store = FileStore::new(assets.clone());
+    migrate_assets(&store, &assets).expect("could not migrate assets");
+    init(assets, Box::new(store));
 
     // Start a background thread that waits for the dictionary to be edited
     let background_thread = thread::spawn(dictionary_worker);
 
     run().await;
     APP_EXITING.get().unwrap().store(true, Ordering::Relaxed);
+    // Wakes the worker up for its final save
+    request_save();
     background_thread
         .join()
         .expect("failed to join background thread");
 }
 
-fn assets_dir() -> PathBuf {
-    Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("assets")
+/// Loads the word lists from `store` and everything else from `assets`, setting up the state
+/// the handlers and the worker share
+fn init(assets: PathBuf, store: Box<dyn DictionaryStore>) {
+    ASSETS_DIR
+        .set(assets)
+        .expect("ASSETS_DIR already initialized");
+    if DICTIONARY_STORE.set(store).is_err() {
+        panic!("DICTIONARY_STORE already initialized");
+    }
+    let game_source = load_game_words();
+    WORD_LENGTH
+        .set(detect_word_length())
+        .expect("could not initialize WORD_LENGTH");
+    let dict_source = load_dict_words();
+    let word_locales = {
+        let game_words = GAME_WORDS.get().expect("GAME_WORDS is not initialized");
+        let game_words = game_words.read().expect("failed to lock GAME_WORDS");
+        locales_spelling(game_words.iter())
+    };
+    let word_locales = if word_locales.is_empty() {
+        log::warn!("No language's alphabet spells every game word, allowing them all");
+        i18n::LOCALES.to_vec()
+    } else {
+        word_locales
+    };
+    WORD_LOCALES
+        .set(word_locales)
+        .expect("WORD_LOCALES already initialized");
+    WORD_SOURCES
+        .set((game_source, dict_source))
+        .expect("WORD_SOURCES already initialized");
+    stats::load(&assets_dir());
+    chat_config::load(&assets_dir());
+    i18n::load_overrides(&assets_dir());
+    chat_words::load(&assets_dir());
+    recent_words::load(&assets_dir());
+    user_config::load(&assets_dir());
+    load_blocked_words();
+    DIRTY_DICTIONARY
+        .set(AtomicBool::new(false))
+        .expect("could not initialize DIRTY_DICTIONARY");
+    APP_EXITING
+        .set(AtomicBool::new(false))
+        .expect("could not initialize DIRTY_DICTIONARY");
+    ADMIN_IDS
+        .set(load_admin_ids())
+        .expect("could not initialize ADMIN_IDS");
+    COMMAND_ALIASES
+        .set(load_command_aliases())
+        .expect("could not initialize COMMAND_ALIASES");
 }
 
-fn dictionary_worker() {
-    let app_exiting = APP_EXITING.get().unwrap();
-    let dirty_dictionary = DIRTY_DICTIONARY.get().unwrap();
+fn load_admin_ids() -> BTreeSet<i64> {
+    let admin_ids = env::var("ADMIN_IDS").unwrap_or_default();
+    admin_ids
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .filter_map(|id| match id.parse() {
+            Ok(id) => Some(id),
+            Err(_) => {
+                log::warn!("Ignoring invalid admin id {id:?}");
+                None
+            }
+        })
+        .collect()
+}
 
-    while !app_exiting.load(Ordering::Relaxed) {
-        if dirty_dictionary.swap(false, Ordering::Relaxed) {
-            // The dictionary has been updated. We need to serialize both
-            println!("Updating word lists");
-            let dictionaries: [_; 2] = [
-                (&GAME_WORDS, assets_dir().join("words_custom.txt")),
-                (&DICT_WORDS, assets_dir().join("dictionary_custom.txt")),
-            ];
-            for (dict, file_path) in dictionaries {
-                let dict = dict.get().expect("dictionary not initialized");
-                let dict = dict.read().expect("could not lock dictionary");
+/// Reads `/alias /command` lines from `aliases.txt`, skipping aliases that would shadow a
+/// command or don't point at one
+fn load_command_aliases() -> HashMap<String, String> {
+    let path = assets_dir().join("aliases.txt");
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return HashMap::new();
+    };
 
-                let output_file =
-                    File::create(file_path).expect("could not create custom dictoinary file");
-                let mut output_file = LineWriter::new(output_file);
-
-                for word in &*dict {
-                    output_file
-                        .write_all(word.as_bytes())
-                        .expect("failed to write custom word");
-                    output_file
-                        .write_all("\n".as_bytes())
-                        .expect("failed to write newline");
-                }
-            }
+    let mut aliases = HashMap::new();
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('#') || line.is_empty() {
+            continue;
         }
+        let mut columns = line.split_whitespace();
+        let (Some(alias), Some(command), None) = (columns.next(), columns.next(), columns.next())
+        else {
+            log::warn!("Ignoring invalid alias line {line:?} in {}", path.display());
+            continue;
+        };
 
-        // Wait 5m
-        thread::sleep(Duration::from_secs(2 * 60));
+        if !alias.starts_with('/') || alias.contains('@') {
+            log::warn!("Ignoring alias {alias:?}, aliases have to look like /name");
+        } else if BUILTIN_COMMANDS.contains(&alias) {
+            log::warn!("Ignoring alias {alias:?}, it would shadow the command of that name");
+        } else if !BUILTIN_COMMANDS.contains(&command) {
+            log::warn!("Ignoring alias {alias:?}, {command:?} is not a command");
+        } else if let Some(previous) = aliases.insert(alias.to_string(), command.to_string()) {
+            log::warn!("Alias {alias:?} is defined twice, using {command} over {previous}");
+        }
     }
+    log::info!("Loaded {} command aliases", aliases.len());
+    aliases
 }
 
-fn load_game_words() {
-    let mut btree = BTreeSet::default();
-    let assets_dir = assets_dir();
+/// `text` with a leading command alias replaced by the command it stands for. Commands sent with
+/// the `COMMAND_PREFIX` are turned into `/` commands first.
+fn resolve_alias(text: &str) -> String {
+    let aliases = COMMAND_ALIASES
+        .get()
+        .expect("COMMAND_ALIASES is not initialized");
+    expand_alias(text, *COMMAND_PREFIX, aliases)
+}
 
-    let file = if assets_dir.join("words_custom.txt").exists() {
-        File::open(assets_dir.join("words_custom.txt")).expect("no such file")
-    } else {
-        File::open(assets_dir.join("words.txt")).expect("no such file")
+/// `resolve_alias` with the given prefix and aliases
+fn expand_alias(text: &str, prefix: char, aliases: &HashMap<String, String>) -> String {
+    let text = text.trim_start();
+    // Telegram's command menu always sends `/`, so those keep working with another prefix
+    let prefixed = with_slash_prefix(text, prefix, aliases);
+    let text = prefixed.as_deref().unwrap_or(text);
+    let (first, rest) = match text.split_once(char::is_whitespace) {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (text, None),
     };
 
-    let buf = BufReader::new(file);
-    for line in buf.lines() {
-        btree.insert(line.expect("could not parse line"));
+    match (aliases.get(strip_bot_mention(first)), rest) {
+        (Some(command), Some(rest)) => format!("{command} {rest}"),
+        (Some(command), None) => command.clone(),
+        (None, _) => text.to_string(),
     }
-
-    GAME_WORDS
-        .set(RwLock::new(btree))
-        .expect("GAME_WORDS already initialized")
 }
 
-fn load_dict_words() {
-    let mut btree = BTreeSet::default();
-    let assets_dir = assets_dir();
-
-    let file = if assets_dir.join("dictionary_custom.txt").exists() {
-        File::open(assets_dir.join("dictionary_custom.txt")).expect("no such file")
-    } else {
-        File::open(assets_dir.join("dictionary.txt")).expect("no such file")
-    };
-
-    let buf = BufReader::new(file);
-    for line in buf.lines() {
-        btree.insert(line.expect("could not parse line"));
+/// `text` as a `/` command if it's a command or alias sent with `prefix` instead, like
+/// `!wordle`. Anything else starting with the prefix is a normal message, as in `!!!` or `?`.
+fn with_slash_prefix(
+    text: &str,
+    prefix: char,
+    aliases: &HashMap<String, String>,
+) -> Option<String> {
+    if prefix == '/' {
+        return None;
     }
+    let rest = text.strip_prefix(prefix)?;
+    let name = rest.split(char::is_whitespace).next().unwrap_or_default();
+    if !name.starts_with(char::is_alphabetic) {
+        return None;
+    }
+    let command = format!("/{name}");
+    let command = strip_bot_mention(&command);
+    let known = BUILTIN_COMMANDS.contains(&command) || aliases.contains_key(command);
+    known.then(|| format!("/{rest}"))
+}
 
-    DICT_WORDS
-        .set(RwLock::new(btree))
-        .expect("DICT_WORDS already initialized")
+fn is_admin(cx: &TransitionIn<AutoSend<Bot>>) -> bool {
+    let admin_ids = ADMIN_IDS.get().expect("ADMIN_IDS is not initialized");
+    cx.update
+        .from()
+        .is_some_and(|user| admin_ids.contains(&user.id))
 }
 
-fn get_random_word() -> String {
-    let game_words = GAME_WORDS.get().expect("GAME_WORDS is not initialized");
-    let game_words = game_words.read().expect("failed to lock GAME_WORDS");
-    game_words
-        .iter()
-        .choose(&mut rand::thread_rng())
-        .unwrap()
+fn assets_dir() -> PathBuf {
+    ASSETS_DIR
+        .get()
+        .expect("ASSETS_DIR is not initialized")
         .clone()
 }
 
-fn is_dictionary_word(word: &str) -> bool {
-    let dict_words = DICT_WORDS.get().expect("DICT_WORDS is not initialized");
-    let dict_words = dict_words.read().expect("failed to lock DICT_WORDS");
-
-    dict_words.contains(word)
+/// Picks the assets directory. `TELEBOT_ASSETS_DIR` wins when set, and is created if it doesn't
+/// exist yet. Otherwise it's the first existing `assets` directory in the crate, which is only
+/// known when run through cargo, or next to the executable.
+fn find_assets_dir() -> io::Result<PathBuf> {
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").map(|dir| Path::new(&dir).join("assets"));
+    let exe_dir = env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join("assets")));
+    let candidates: Vec<PathBuf> = manifest_dir.into_iter().chain(exe_dir).collect();
+    pick_assets_dir(
+        env::var_os("TELEBOT_ASSETS_DIR").map(PathBuf::from),
+        &candidates,
+    )
 }
 
-async fn run() {
-    teloxide::enable_logging!();
-    log::info!("Starting bot...");
-    dotenv::dotenv().ok();
+/// `configured` if there's one, creating it if needed, or else the first of `candidates` that is
+/// an existing directory
+fn pick_assets_dir(configured: Option<PathBuf>, candidates: &[PathBuf]) -> io::Result<PathBuf> {
+    if let Some(dir) = configured {
+        if !dir.exists() {
+            log::warn!(
+                "No assets directory at {}, creating it to save words and stats in",
+                dir.display()
+            );
+            fs::create_dir_all(&dir)?;
+        }
+        return Ok(dir);
+    }
 
-    let bot = Bot::from_env().auto_send();
+    if let Some(dir) = candidates.iter().find(|dir| dir.is_dir()) {
+        return Ok(dir.clone());
+    }
+    let tried: Vec<String> = candidates
+        .iter()
+        .map(|dir| dir.display().to_string())
+        .collect();
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!(
+            "no assets directory at {}, set TELEBOT_ASSETS_DIR to pick one",
+            tried.join(" or ")
+        ),
+    ))
+}
 
-    teloxide::dialogues_repl(bot, |message, dialogue| async move {
-        handle_message(message, dialogue)
-            .await
-            .expect("Something wrong with the bot!")
-    })
-    .await;
+fn dictionary_worker() {
+    let app_exiting = APP_EXITING.get().unwrap();
+    run_saves(
+        &SAVE_REQUESTED,
+        &SAVE_PAUSED,
+        app_exiting,
+        (*SAVE_INTERVAL, *SAVE_DEBOUNCE),
+        save_changes,
+    );
 }
 
-async fn handle_message(
-    cx: UpdateWithCx<AutoSend<Bot>, Message>,
-    dialogue: Dialogue,
-) -> TransitionOut<Dialogue> {
-    match cx.update.text().map(ToOwned::to_owned) {
-        None => next(dialogue),
-        Some(ans) => dialogue.react(cx, ans).await,
+/// Calls `save` every time `requests` asks for it or the first of `timing`, the interval, passes,
+/// waiting the debounce after requests, see `SaveRequests::wait`. Skips saving while `paused` is
+/// set, and saves a last time once `exiting` is.
+fn run_saves(
+    requests: &SaveRequests,
+    paused: &AtomicBool,
+    exiting: &AtomicBool,
+    (interval, debounce): (Duration, Duration),
+    mut save: impl FnMut(),
+) {
+    while !exiting.load(Ordering::Relaxed) {
+        requests.wait(interval, debounce, exiting);
+        // Edits made while saving is paused stay dirty, so they're all saved at once on resume
+        if !paused.load(Ordering::Relaxed) {
+            save();
+        }
     }
+    // Edits made since the last save would be lost otherwise, even if saving is paused
+    save();
 }
 
-#[derive(From, Transition, Clone)]
-pub enum Dialogue {
-    Start(StartState),
-    Guess(GuessState),
+/// Whether a save was asked for, with the condvar that wakes the worker up for it
+#[derive(Default)]
+struct SaveRequests {
+    requested: Mutex<bool>,
+    wake: Condvar,
 }
 
-impl Default for Dialogue {
-    fn default() -> Self {
-        Self::Start(StartState)
+impl SaveRequests {
+    /// Asks for a save, waking up the worker if it's waiting
+    fn request(&self) {
+        *self
+            .requested
+            .lock()
+            .expect("failed to lock SAVE_REQUESTED") = true;
+        self.wake.notify_one();
+    }
+
+    /// Blocks until a save is requested or `interval` passes. After a request it waits `debounce`
+    /// more, so the edits that quickly follow are saved along with it. Returns right away once
+    /// `exiting` is set.
+    fn wait(&self, interval: Duration, debounce: Duration, exiting: &AtomicBool) {
+        let exiting = || exiting.load(Ordering::Relaxed);
+        let requested = self
+            .requested
+            .lock()
+            .expect("failed to lock SAVE_REQUESTED");
+        let (mut requested, _) = self
+            .wake
+            .wait_timeout_while(requested, interval, |requested| !*requested && !exiting())
+            .expect("failed to lock SAVE_REQUESTED");
+        if *requested {
+            // Requests made meanwhile only wake us up early, their edits are in the coming save
+            (requested, _) = self
+                .wake
+                .wait_timeout_while(requested, debounce, |_| !exiting())
+                .expect("failed to lock SAVE_REQUESTED");
+        }
+        *requested = false;
     }
 }
 
-pub enum DictionaryAction<'a> {
-    Add(&'a [&'a str]),
-    Remove(&'a [&'a str]),
+/// Wakes the worker up to save soon, for edits that shouldn't wait for the next `SAVE_INTERVAL`
+fn request_save() {
+    SAVE_REQUESTED.request();
 }
 
-async fn edit_dictionary(action: DictionaryAction<'_>, cx: TransitionIn<AutoSend<Bot>>) {
-    //-> AutoRequest<JsonRequest<SendMessage>> {
+/// Flags the word lists for the worker to save, and asks it to save soon
+fn mark_dictionary_dirty() {
+    DIRTY_DICTIONARY
+        .get()
+        .unwrap()
+        .store(true, Ordering::Relaxed);
+    request_save();
+}
+
+/// Saves everything that changed since the last save.
+///
+/// Edits set their dirty flag only once the data is changed under its lock, and a flag is
+/// cleared before its data is read for a save. So an edit racing a save is either in what's
+/// saved, or sets the flag again for the next save: an edit can be saved twice but never lost.
+/// Failed saves set their flag again too.
+fn save_changes() {
     let dirty_dictionary = DIRTY_DICTIONARY.get().unwrap();
-    match action {
-        DictionaryAction::Add(words) => {
-            let mut added_words = BTreeSet::new();
+    let store = DICTIONARY_STORE
+        .get()
+        .expect("DICTIONARY_STORE is not initialized");
+    let dictionary_changed = dirty_dictionary.swap(false, Ordering::Relaxed);
+    let usage_changed = DIRTY_USAGE.swap(false, Ordering::Relaxed);
+    if dictionary_changed || usage_changed {
+        log::info!("Updating word lists");
+        // Snapshot the usage first so we never hold its lock together with a word set lock
+        let word_usage = WORD_USAGE.get().expect("WORD_USAGE not initialized");
+        let word_usage = word_usage
+            .read()
+            .expect("could not lock WORD_USAGE")
+            .clone();
+        let no_usage = HashMap::new();
+        let word_weights = WORD_WEIGHTS
+            .read()
+            .expect("could not lock WORD_WEIGHTS")
+            .clone();
 
-            let dictionaries: [_; 2] = [&GAME_WORDS, &DICT_WORDS];
-            for dict in dictionaries {
+        // Usage only lives in the game words, so the dictionary is kept as is when only it changed
+        let dictionaries: [_; 2] = [
+            (&GAME_WORDS, "words_custom.txt", &word_usage, true),
+            (
+                &DICT_WORDS,
+                "dictionary_custom.txt",
+                &no_usage,
+                dictionary_changed,
+            ),
+        ];
+        for (dict, file_name, usage, changed) in dictionaries {
+            if !changed {
+                continue;
+            }
+            // The list is written out first so the word set isn't locked during the save
+            let mut contents = format!("{WORD_FILE_HEADER}\n");
+            {
                 let dict = dict.get().expect("dictionary not initialized");
-                let mut dict = dict.write().expect("could not lock dictionary");
-
-                for word in words {
-                    if word.len() != 5 {
-                        continue;
+                let dict = dict.read().expect("could not lock dictionary");
+                for word in dict.iter() {
+                    contents.push_str(word);
+                    // Usage is stored as extra tab separated columns, so plain word lists are
+                    // upgraded to the richer format the first time they're saved
+                    if let Some(usage) = usage.get(word) {
+                        let _ = write!(
+                            contents,
+                            "\tuses={}\tlast_used={}",
+                            usage.uses, usage.last_used
+                        );
                     }
-
-                    if dict.insert(word.to_string()) {
-                        added_words.insert(*word);
+                    if let Some(weight) = word_weights.get(word) {
+                        let _ = write!(contents, "\tweight={weight}");
+                    }
+                    if let Some(clue) = word_clue(word) {
+                        let _ = write!(contents, "\tclue={clue}");
+                    }
+                    if let Some(category) = word_category(word) {
+                        let _ = write!(contents, "\tcategory={category}");
+                    }
+                    let alternates = word_alternates(word);
+                    if !alternates.is_empty() {
+                        let _ = write!(contents, "\talso={}", alternates.join(","));
                     }
+                    contents.push('\n');
                 }
             }
-            dirty_dictionary.store(true, Ordering::Relaxed);
-            cx.answer(format!("Added {:?}", added_words)).await.ok();
-        }
-        DictionaryAction::Remove(words) => {
-            let mut removed_words = BTreeSet::new();
-
-            let dictionaries: [_; 2] = [&GAME_WORDS, &DICT_WORDS];
-            for dict in dictionaries {
-                let dict = dict.get().expect("dictionary not initialized");
-                let mut dict = dict.write().expect("could not lock dictionary");
+            // A run with a narrower length range mustn't delete the other words from the lists
+            let filtered_lines = FILTERED_WORD_LINES
+                .lock()
+                .expect("failed to lock FILTERED_WORD_LINES");
+            for line in filtered_lines.get(file_name).into_iter().flatten() {
+                contents.push_str(line);
+                contents.push('\n');
+            }
+            drop(filtered_lines);
 
-                for word in words {
-                    if dict.remove(*word) {
-                        removed_words.insert(*word);
-                    }
+            if let Err(err) = store.save(file_name, &contents) {
+                log::error!("Failed to save {file_name}: {err}");
+                if dictionary_changed {
+                    dirty_dictionary.store(true, Ordering::Relaxed);
+                }
+                if usage_changed {
+                    DIRTY_USAGE.store(true, Ordering::Relaxed);
                 }
             }
-            dirty_dictionary.store(true, Ordering::Relaxed);
-            cx.answer(format!("Removed {:?}", removed_words)).await.ok();
         }
     }
-}
 
-#[derive(Clone)]
-pub struct StartState;
+    if let Err(err) = stats::save_if_dirty(&assets_dir()) {
+        log::error!("Failed to save stats: {err}");
+    }
+    if let Err(err) = chat_config::save_if_dirty(&assets_dir()) {
+        log::error!("Failed to save chat settings: {err}");
+    }
+    if let Err(err) = chat_words::save_if_dirty(&assets_dir()) {
+        log::error!("Failed to save chat word lists: {err}");
+    }
+    if let Err(err) = recent_words::save_if_dirty(&assets_dir()) {
+        log::error!("Failed to save recently added words: {err}");
+    }
+    if let Err(err) = user_config::save_if_dirty(&assets_dir()) {
+        log::error!("Failed to save user preferences: {err}");
+    }
+}
 
-#[teloxide(subtransition)]
-async fn start_state(
-    state: StartState,
-    cx: TransitionIn<AutoSend<Bot>>,
-    ans: String,
-) -> TransitionOut<Dialogue> {
-    let input: Vec<String> = ans.split_whitespace().map(String::from).collect();
-    match ans.as_str() {
-        "/wordle" => {
-            cx.answer("Wordle game started - /guess any 5 letter word")
-                .await?;
-            next(GuessState {
-                answer: get_random_word(),
-                guesses: Default::default(),
-                last_input: input,
-            })
-        }
-        "/420" => {
-            "heh";
-            next(state)
+/// Upgrades files saved by older versions of the bot to the current formats. The word lists are
+/// upgraded in `store`, so they're replaced as safely as the worker's saves.
+fn migrate_assets(store: &dyn DictionaryStore, dir: &Path) -> io::Result<()> {
+    for file_name in ["words_custom.txt", "dictionary_custom.txt"] {
+        let Some(contents) = store.load(file_name)? else {
+            continue;
+        };
+        if contents.starts_with(WORD_FILE_HEADER) {
+            continue;
         }
-        _ => next(state),
+        // v0 files are plain word lists without a header, which read the same way in v1
+        log::info!("Migrating {file_name} to {WORD_FILE_HEADER}");
+        store.save(file_name, &format!("{WORD_FILE_HEADER}\n{contents}"))?;
     }
+
+    stats::migrate(dir)
 }
 
-#[derive(Clone)]
-pub struct GuessState {
-    pub answer: String,
-    // Emoji representation as well as word guessed
-    pub guesses: Vec<(String, String)>,
-    pub last_input: Vec<String>,
+/// Reads a word list, preferring the custom copy saved by the worker over the shipped base file,
+/// and returns it along with where it was read from.
+///
+/// Words outside the configured length range are skipped, and `on_word` sees the metadata of
+/// every word that's kept.
+fn load_words(
+    custom_file: &str,
+    base_file: &str,
+    on_word: impl FnMut(&str, &[(&str, &str)]),
+) -> (WordSet, String) {
+    let store = DICTIONARY_STORE
+        .get()
+        .expect("DICTIONARY_STORE is not initialized");
+    load_words_from(store.as_ref(), custom_file, base_file, on_word)
 }
 
-#[teloxide(subtransition)]
-async fn guess_state(
-    state: GuessState,
-    cx: TransitionIn<AutoSend<Bot>>,
-    ans: String,
-) -> TransitionOut<Dialogue> {
-    let input: Vec<String> = ans.split_whitespace().map(String::from).collect();
-    let input_str: Vec<&str> = input.iter().map(String::as_str).collect();
+/// `load_words` reading from `store`
+fn load_words_from(
+    store: &dyn DictionaryStore,
+    custom_file: &str,
+    base_file: &str,
+    mut on_word: impl FnMut(&str, &[(&str, &str)]),
+) -> (WordSet, String) {
+    let mut words = Vec::new();
+    let custom = store
+        .load(custom_file)
+        .unwrap_or_else(|err| {
+            // A corrupt custom copy shouldn't keep the bot from starting when the base list works
+            log::warn!("Could not read {custom_file}, loading {base_file} instead: {err}");
+            None
+        })
+        .filter(|contents| {
+            // A truncated save would leave the games without words
+            let has_words = contents
+                .lines()
+                .any(|line| !line.starts_with('#') && !parse_word_line(line).0.is_empty());
+            if !has_words {
+                log::warn!("{custom_file} has no words, loading {base_file} instead");
+            }
+            has_words
+        });
+    let file_path = |file_name| assets_dir().join(file_name).display().to_string();
+    let (file_name, source, contents) = match custom {
+        Some(contents) => (custom_file, file_path(custom_file), contents),
+        None => match store.load(base_file).expect("could not read word file") {
+            Some(contents) => (base_file, file_path(base_file), contents),
+            // Lets the bot run out of the box, without downloading the word lists
+            None => {
+                log::warn!("Neither {custom_file} nor {base_file} exist, using built-in words");
+                let source = "the built-in words";
+                (source, source.to_string(), FALLBACK_WORDS.to_string())
+            }
+        },
+    };
 
-    let mut new_state = state.clone();
-    new_state.last_input = input.clone();
+    let mut filtered_lines = Vec::new();
+    let mut empty = 0;
+    for line in contents.lines() {
+        // Comments include the header of files saved by the worker
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
 
-    match new_state.last_input[0].as_str() {
-        "/addword" | "/addword@doomybot" => {
-            let wants_to_add_previous_guess = input.len() == 1 && state.last_input.len() == 2;
+        let (word, metadata) = parse_word_line(line);
+        // Lines with only metadata, like a stray `\t3`, have no word at all
+        if word.is_empty() {
+            empty += 1;
+            continue;
+        }
+        if !WORD_LENGTHS.contains(&letter_count(word)) {
+            filtered_lines.push(line.to_string());
+            continue;
+        }
+        let weight = metadata
+            .iter()
+            .find(|(key, _)| *key == "weight")
+            .and_then(|(_, value)| value.parse::<u32>().ok());
+        if let Some(weight) = weight {
+            WORD_WEIGHTS
+                .write()
+                .expect("failed to lock WORD_WEIGHTS")
+                .insert(word.to_string(), weight);
+        }
+        on_word(word, &metadata);
+        words.push(word);
+    }
 
-            if wants_to_add_previous_guess {
-                edit_dictionary(DictionaryAction::Add(&[&state.last_input[1]]), cx).await;
-            } else {
-                edit_dictionary(DictionaryAction::Add(&input_str[1..]), cx).await;
-            }
+    if !filtered_lines.is_empty() {
+        log::info!(
+            "Filtered {} words from {file_name} outside lengths {:?}",
+            filtered_lines.len(),
+            *WORD_LENGTHS
+        );
+    }
+    FILTERED_WORD_LINES
+        .lock()
+        .expect("failed to lock FILTERED_WORD_LINES")
+        .insert(custom_file.to_string(), filtered_lines);
+    if empty > 0 {
+        log::warn!("Skipped {empty} lines without a word in {file_name}");
+    }
+    let words: WordSet = words.into_iter().collect();
+    log::info!("Loaded {} words from {source}", words.len());
+    (words, source)
+}
 
-            next(new_state)
+/// Loads `GAME_WORDS` and their metadata, returning where they were read from
+fn load_game_words() -> String {
+    let mut word_usage = HashMap::new();
+    let mut word_clues = HashMap::new();
+    let mut word_categories = HashMap::new();
+    let mut word_alternates = HashMap::new();
+    let (words, source) = load_words("words_custom.txt", "words.txt", |word, metadata| {
+        if let Some(usage) = WordUsage::from_metadata(metadata) {
+            word_usage.insert(word.to_string(), usage);
         }
-        "/exit" | "/end" | "/stop" => {
-            let word = state.answer;
-            cx.answer(format!("Ending game. Word was {word}")).await?;
-            next(StartState)
+        if let Some((_, clue)) = metadata.iter().find(|(key, _)| *key == "clue") {
+            word_clues.insert(word.to_string(), clue.to_string());
         }
-        "/removeword" => {
-            if input.len() < 2 {
-                cx.answer("Usage: /removeword <WORD> [..WORD2]").await?;
-            } else {
-                edit_dictionary(DictionaryAction::Remove(&input_str[1..]), cx).await;
+        if let Some((_, category)) = metadata.iter().find(|(key, _)| *key == "category") {
+            word_categories.insert(word.to_string(), category.to_lowercase());
+        }
+        if let Some((_, also)) = metadata.iter().find(|(key, _)| *key == "also") {
+            // Alternatives are guessed against the same board, so they need the same length
+            let alternates: Vec<String> = also
+                .split(',')
+                .map(|alternate| alternate.trim().to_lowercase())
+                .filter(|alternate| letter_count(alternate) == letter_count(word))
+                .filter(|alternate| alternate != word)
+                .collect();
+            if !alternates.is_empty() {
+                word_alternates.insert(word.to_string(), alternates);
             }
-
-            next(new_state)
         }
-        "/guess" if input.len() == 2 => {
-            let attempt = input_str[1];
-            let answer = &state.answer;
+    });
 
-            let mut placement = [Placement::Missing; 5];
-
-            // return early if length of attempt is wrong amount of characters
-            if attempt.chars().count() != 5 {
-                cx.answer("Guess was not 5 characters").await.ok();
-                return next(new_state);
-            }
+    GAME_WORDS
+        .set(RwLock::new(words))
+        .expect("GAME_WORDS already initialized");
+    WORD_USAGE
+        .set(RwLock::new(word_usage))
+        .expect("WORD_USAGE already initialized");
+    WORD_CLUES
+        .set(word_clues)
+        .expect("WORD_CLUES already initialized");
+    WORD_CATEGORIES
+        .set(word_categories)
+        .expect("WORD_CATEGORIES already initialized");
+    WORD_ALTERNATES
+        .set(word_alternates)
+        .expect("WORD_ALTERNATES already initialized");
+    source
+}
 
-            if !is_dictionary_word(attempt) {
-                cx.answer(format!("{attempt} is not in the dictionary. /addword?"))
-                    .await
-                    .ok();
-                return next(new_state);
-            }
+/// Length shared by every game word, see `shared_length`
+fn detect_word_length() -> usize {
+    let game_words = GAME_WORDS.get().expect("GAME_WORDS is not initialized");
+    let game_words = game_words.read().expect("failed to lock GAME_WORDS");
+    shared_length(game_words.iter())
+}
 
-            let mut corrected_answer: Vec<char> = answer.clone().chars().collect();
+/// Length shared by all of `words`, so a word file of 6 letter words plays 6 letter games.
+/// Falls back to `DEFAULT_WORD_LENGTH` when the lengths are mixed.
+fn shared_length<'a>(words: impl Iterator<Item = &'a str>) -> usize {
+    let lengths: BTreeSet<usize> = words.map(letter_count).collect();
 
-            // check for correct placement
-            attempt.chars().zip(answer.chars()).enumerate().for_each(
-                |(i, (attempt_char, answer_char))| {
-                    if attempt_char == answer_char {
-                        placement[i] = Placement::Correct;
-                        // remove the char from our corrected_answer so we can check for misplaced chars without dupes
-                        corrected_answer[i] = ' ';
-                    }
-                },
+    match lengths.first() {
+        Some(&length) if lengths.len() == 1 => {
+            log::info!("Detected {length} letter game words");
+            length
+        }
+        _ => {
+            log::info!(
+                "Game words have lengths {lengths:?}, playing with {DEFAULT_WORD_LENGTH} letters"
             );
+            DEFAULT_WORD_LENGTH
+        }
+    }
+}
 
-            // check for misplaced characters
-            attempt.chars().enumerate().for_each(|(i, attempt_char)| {
-                if placement[i] != Placement::Correct && corrected_answer.contains(&attempt_char) {
-                    placement[i] = Placement::Incorrect;
-                }
-            });
-
-            // get the answer
-            let result = to_emoji(&placement);
+/// Length of the words games are played with
+fn word_length() -> usize {
+    *WORD_LENGTH.get().expect("WORD_LENGTH is not initialized")
+}
 
-            // add to our guess history
-            let mut guesses = state.guesses.clone();
-            guesses.push((result, attempt.to_string()));
-            let emoji_string = guesses
-                .iter()
-                .map(|(a, _)| a.clone())
-                .collect::<Vec<String>>()
-                .join("\n");
-
-            let tries = guesses.len();
-            // if we won...
-            match placement == [Placement::Correct; 5] {
-                true => {
-                    cx.answer(format!("You won. {tries}/6\n{emoji_string}"))
-                        .await
-                        .ok();
-                    next(StartState)
-                }
-                false => {
-                    // check to see if we're out of guesses
-                    let next_guess = tries + 1;
-                    if next_guess < 7 {
-                        cx.answer(format!("{tries}/6\n{emoji_string}")).await.ok();
-                        next(GuessState {
-                            answer: answer.to_string(),
-                            guesses,
-                            last_input: input,
-                        })
-                    } else {
-                        // lost
-                        let answer = state.answer;
-                        cx.answer(format!(
-                            "You lost. 6/6. Cringe.\nAnswer was {answer}\n{emoji_string}"
-                        ))
-                        .await
-                        .ok();
-                        next(StartState)
-                    }
-                }
-            }
-        }
-        "/guess" => {
-            cx.answer("Invalid guess");
-            next(state)
+/// Answer of the daily game on `date`, the same in every chat, from the configured
+/// `daily_word::source`.
+///
+/// The first draw of the day is kept, so words added or removed later that day don't change it.
+async fn get_daily_word(date: NaiveDate) -> Option<String> {
+    if let Some((day, word)) = &*DAILY_WORD.lock().expect("failed to lock DAILY_WORD") {
+        if *day == date {
+            return Some(word.clone());
         }
+    }
+
+    let word = daily_word::source().word_for(date).await?;
+    let mut daily_word = DAILY_WORD.lock().expect("failed to lock DAILY_WORD");
+    match &*daily_word {
+        // Drawn by another game while this one was fetching it
+        Some((day, kept)) if *day == date => Some(kept.clone()),
         _ => {
-            // Not meant for us?
-            next(state)
+            *daily_word = Some((date, word.clone()));
+            Some(word)
         }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum Placement {
-    Correct,
-    Incorrect,
-    Missing,
+/// The daily answer on `date` picked from the game words. Chat words and pools are left out so
+/// it really is shared.
+fn local_daily_word(date: NaiveDate) -> Option<String> {
+    let game_words = GAME_WORDS.get().expect("GAME_WORDS is not initialized");
+    let game_words = game_words.read().expect("failed to lock GAME_WORDS");
+    let words = game_words
+        .iter()
+        .filter(|word| letter_count(word) == word_length());
+    pick_daily_word(words, date).map(String::from)
 }
 
-fn to_emoji(placement: &[Placement]) -> String {
-    placement
-        .iter()
-        .map(|p| match p {
-            Placement::Correct => '🟩',
-            Placement::Incorrect => '🟨',
-            Placement::Missing => '⬛',
+/// The word of `words` with the lowest hash of it and `date`. The hash never changes, unlike
+/// rand's generators, so a restart picks the same word, and editing a word only changes the pick
+/// when it's the one picked or beats it.
+fn pick_daily_word<'a>(
+    words: impl IntoIterator<Item = &'a str>,
+    date: NaiveDate,
+) -> Option<&'a str> {
+    let day = date.num_days_from_ce().to_le_bytes();
+    words
+        .into_iter()
+        .min_by_key(|word| fnv1a([day.as_slice(), word.as_bytes()]))
+}
+
+/// 64 bit FNV-1a hash of `parts` one after the other
+fn fnv1a<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> u64 {
+    parts
+        .into_iter()
+        .flatten()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
         })
-        .collect()
+}
+
+/// Puzzle number of the daily game on `date`, counting from the first one
+fn daily_number(date: NaiveDate) -> i64 {
+    let first = NaiveDate::from_ymd_opt(2022, 1, 1).expect("invalid first daily date");
+    (date - first).num_days() + 1
+}
+
+/// Whether any game word or word of the chat is `length` letters long, for games started as
+/// `/wordle <LENGTH>`
+fn has_answers_of_length(chat_id: i64, length: usize) -> bool {
+    let chat_words = chat_words::words(chat_id);
+    let game_words = GAME_WORDS.get().expect("GAME_WORDS is not initialized");
+    let game_words = game_words.read().expect("failed to lock GAME_WORDS");
+    let found = game_words
+        .iter()
+        .chain(chat_words.iter().map(String::as_str))
+        .any(|word| letter_count(word) == length);
+    found
+}
+
+fn word_clue(word: &str) -> Option<&'static str> {
+    let word_clues = WORD_CLUES.get().expect("WORD_CLUES is not initialized");
+    word_clues.get(word).map(String::as_str)
+}
+
+/// Other words that win when `word` is the answer
+fn word_alternates(word: &str) -> &'static [String] {
+    let word_alternates = WORD_ALTERNATES
+        .get()
+        .expect("WORD_ALTERNATES is not initialized");
+    word_alternates.get(word).map_or(&[], Vec::as_slice)
+}
+
+fn word_category(word: &str) -> Option<&'static str> {
+    let word_categories = WORD_CATEGORIES
+        .get()
+        .expect("WORD_CATEGORIES is not initialized");
+    word_categories.get(word).map(String::as_str)
+}
+
+/// Every category some game word is in
+fn word_categories() -> BTreeSet<&'static str> {
+    let word_categories = WORD_CATEGORIES
+        .get()
+        .expect("WORD_CATEGORIES is not initialized");
+    word_categories.values().map(String::as_str).collect()
+}
+
+/// Loads `DICT_WORDS`, returning where they were read from
+fn load_dict_words() -> String {
+    let (words, source) = load_words("dictionary_custom.txt", "dictionary.txt", |_, _| ());
+
+    DICT_WORDS
+        .set(RwLock::new(words))
+        .expect("DICT_WORDS already initialized");
+    source
+}
+
+fn load_blocked_words() {
+    let store = DICTIONARY_STORE
+        .get()
+        .expect("DICTIONARY_STORE is not initialized");
+    let blocked_words: BTreeSet<String> = match store.load("blocklist.txt") {
+        Ok(Some(contents)) => contents
+            .lines()
+            .map(|line| line.trim().to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect(),
+        _ => {
+            log::warn!("No blocklist found");
+            BTreeSet::new()
+        }
+    };
+
+    // Words may have been added before they were blocked, so point them out to be removed
+    let dictionaries: [_; 2] = [(&GAME_WORDS, "GAME_WORDS"), (&DICT_WORDS, "DICT_WORDS")];
+    for (dict, name) in dictionaries {
+        let dict = dict.get().expect("dictionary not initialized");
+        let dict = dict.read().expect("could not lock dictionary");
+        for word in dict.iter() {
+            if blocked_words.contains(&word.to_lowercase()) {
+                log::warn!("Blocked word {word:?} is in {name}, consider /removeword");
+            }
+        }
+    }
+
+    BLOCKED_WORDS
+        .set(blocked_words)
+        .expect("BLOCKED_WORDS already initialized")
+}
+
+fn is_blocked_word(word: &str) -> bool {
+    let blocked_words = BLOCKED_WORDS
+        .get()
+        .expect("BLOCKED_WORDS is not initialized");
+    blocked_words.contains(&word.to_lowercase())
+}
+
+/// Reads the `MIN_WORD_LEN`/`MAX_WORD_LEN` bounds, see `word_lengths`
+fn load_word_lengths() -> RangeInclusive<usize> {
+    let min = env::var("MIN_WORD_LEN").ok();
+    let max = env::var("MAX_WORD_LEN").ok();
+    word_lengths(min.as_deref(), max.as_deref())
+}
+
+/// Lengths of the words loaded given the `MIN_WORD_LEN`/`MAX_WORD_LEN` values, defaulting to no
+/// bound on either side
+fn word_lengths(min: Option<&str>, max: Option<&str>) -> RangeInclusive<usize> {
+    let bound = |name: &str, value: Option<&str>, default: usize| match value {
+        Some(value) => value.parse().unwrap_or_else(|_| {
+            log::warn!("Ignoring invalid {name} {value:?}");
+            default
+        }),
+        None => default,
+    };
+    // Empty words would break games, so words always have at least a letter
+    bound("MIN_WORD_LEN", min, 1).max(1)..=bound("MAX_WORD_LEN", max, usize::MAX)
+}
+
+/// Time between the worker's saves given the `SAVE_INTERVAL_SECS` value, defaulting to
+/// `DEFAULT_SAVE_INTERVAL` when it's unset, not a number or zero
+fn save_interval(secs: Option<&str>) -> Duration {
+    match secs {
+        Some(value) => match value.trim().parse::<u64>() {
+            Ok(secs) if secs > 0 => Duration::from_secs(secs),
+            _ => {
+                log::warn!("Ignoring invalid SAVE_INTERVAL_SECS {value:?}");
+                DEFAULT_SAVE_INTERVAL
+            }
+        },
+        None => DEFAULT_SAVE_INTERVAL,
+    }
+}
+
+/// Splits a word file line into the word and its `key=value` metadata columns. A bare number
+/// column, as in hand written `word\tweight` lines, is read as the word's weight.
+///
+/// Plain word-per-line files are lines without any metadata, so they load the same way.
+fn parse_word_line(line: &str) -> (&str, Vec<(&str, &str)>) {
+    let mut columns = line.split('\t');
+    let word = columns.next().unwrap_or_default().trim();
+    let metadata = columns
+        .map(str::trim)
+        .filter_map(|column| match column.split_once('=') {
+            Some(pair) => Some(pair),
+            None if column.parse::<u32>().is_ok() => Some(("weight", column)),
+            None => None,
+        })
+        .collect();
+    (word, metadata)
+}
+
+/// How often a game word has been drawn as an answer
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WordUsage {
+    pub uses: u32,
+    /// Unix timestamp in seconds
+    pub last_used: u64,
+}
+
+impl WordUsage {
+    fn from_metadata(metadata: &[(&str, &str)]) -> Option<Self> {
+        let mut usage = None;
+        for (key, value) in metadata {
+            match *key {
+                "uses" => usage.get_or_insert_with(Self::default).uses = value.parse().ok()?,
+                "last_used" => {
+                    usage.get_or_insert_with(Self::default).last_used = value.parse().ok()?
+                }
+                _ => (),
+            }
+        }
+        usage
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// Draws an answer for a game in `chat_id`, from the game words and the chat's own words. A chat
+/// that picked a /pool only gets answers from that category. `None` if there are no words at all.
+///
+/// The answer's length is picked first among the `lengths` there are words of, so every length
+/// is as likely as the others however many words it has.
+fn get_random_word(chat_id: i64, lengths: &RangeInclusive<usize>) -> Option<String> {
+    // Copied first so the chat's words aren't locked together with GAME_WORDS
+    let chat_words = chat_words::words(chat_id);
+    let pool = chat_config::get(chat_id).pool;
+    let word = {
+        let game_words = GAME_WORDS.get().expect("GAME_WORDS is not initialized");
+        let game_words = game_words.read().expect("failed to lock GAME_WORDS");
+        // Nothing is copied out of GAME_WORDS, the candidates are walked in place for every step.
+        // Chat words that are game words already are skipped, so they're not drawn twice as often.
+        let chat_words: Vec<&str> = chat_words
+            .iter()
+            .map(String::as_str)
+            .filter(|word| !game_words.contains(word))
+            .collect();
+        let all_words = || game_words.iter().chain(chat_words.iter().copied());
+
+        // The category's words may have all been removed since it was picked
+        let pool = pool.filter(|pool| {
+            let found = all_words().any(|word| word_category(word) == Some(pool.as_str()));
+            if !found {
+                log::warn!("No answers left in pool {pool:?} of chat {chat_id}, using all");
+            }
+            found
+        });
+        let in_pool = |word: &&str| {
+            pool.as_deref()
+                .is_none_or(|pool| word_category(word) == Some(pool))
+        };
+
+        let mut rng = rand::thread_rng();
+        let available: BTreeSet<usize> = all_words()
+            .filter(in_pool)
+            .map(letter_count)
+            .filter(|length| lengths.contains(length))
+            .collect();
+        let length = available.into_iter().choose(&mut rng);
+        if length.is_none() {
+            log::warn!("No answers of lengths {lengths:?} in chat {chat_id}, using all");
+        }
+        let candidates = || {
+            all_words()
+                .filter(in_pool)
+                .filter(move |word| length.is_none_or(|length| letter_count(word) == length))
+        };
+
+        let word_weights = WORD_WEIGHTS.read().expect("failed to lock WORD_WEIGHTS");
+        let weight = |word: &str| {
+            u64::from(
+                word_weights
+                    .get(word)
+                    .copied()
+                    .unwrap_or(DEFAULT_WORD_WEIGHT),
+            )
+        };
+        let total_weight: u64 = candidates().map(weight).sum();
+        let word = if total_weight == 0 {
+            // Every word weighs 0, in which case they're all as likely
+            candidates().choose(&mut rng)
+        } else {
+            let mut target = rng.gen_range(0..total_weight);
+            candidates().find(|word| match target.checked_sub(weight(word)) {
+                Some(rest) => {
+                    target = rest;
+                    false
+                }
+                None => true,
+            })
+        };
+        word?.to_string()
+    };
+
+    let word_usage = WORD_USAGE.get().expect("WORD_USAGE is not initialized");
+    let mut word_usage = word_usage.write().expect("failed to lock WORD_USAGE");
+    let usage = word_usage.entry(word.clone()).or_default();
+    usage.uses += 1;
+    usage.last_used = unix_now();
+    // Saved on the worker's regular interval, waking it for every game would rewrite the word
+    // lists every few seconds
+    DIRTY_USAGE.store(true, Ordering::Relaxed);
+
+    Some(word)
+}
+
+/// Whether `word` can be guessed, either globally or, given a chat, because the chat added it
+fn is_dictionary_word(word: &str, chat_id: Option<i64>) -> bool {
+    let in_dictionary = {
+        let dict_words = DICT_WORDS.get().expect("DICT_WORDS is not initialized");
+        let dict_words = dict_words.read().expect("failed to lock DICT_WORDS");
+        dict_words.contains(word)
+    };
+
+    in_dictionary || chat_id.is_some_and(|chat_id| chat_words::contains(chat_id, word))
+}
+
+/// Whether `word` can be an answer, either globally or, given a chat, because the chat added it
+fn is_game_word(word: &str, chat_id: Option<i64>) -> bool {
+    let in_game_words = {
+        let game_words = GAME_WORDS.get().expect("GAME_WORDS is not initialized");
+        let game_words = game_words.read().expect("failed to lock GAME_WORDS");
+        game_words.contains(word)
+    };
+
+    in_game_words || chat_id.is_some_and(|chat_id| chat_words::contains(chat_id, word))
+}
+
+async fn run() {
+    teloxide::enable_logging!();
+    log::info!("Starting bot...");
+
+    let tokens = env::var("BOT_TOKENS").unwrap_or_default();
+    let mut bots: Vec<Bot> = tokens
+        .split(',')
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(Bot::new)
+        .collect();
+    if bots.is_empty() {
+        bots.push(Bot::from_env());
+    }
+    log::info!("Running {} bot instance(s)", bots.len());
+
+    match env::var("SUMMARY_CHAT_ID").map(|id| id.parse()) {
+        Ok(Ok(chat_id)) => {
+            tokio::spawn(post_weekly_summaries(bots[0].clone().auto_send(), chat_id));
+        }
+        Ok(Err(_)) => log::warn!("Ignoring invalid SUMMARY_CHAT_ID"),
+        Err(_) => (),
+    }
+
+    // Every instance shares the same word lists, stats and maintenance flag, but each keeps its
+    // own dialogues, so a game started with one bot can't be continued with another
+    join_all(bots.into_iter().map(|bot| dispatch(bot.auto_send()))).await;
+}
+
+/// Posts a summary of the week's games to `chat_id` once a week
+async fn post_weekly_summaries(bot: AutoSend<Bot>, chat_id: i64) {
+    // Checking hourly means a failed post is retried without spamming the chat
+    let mut interval = tokio::time::interval(Duration::from_secs(60 * 60));
+    loop {
+        interval.tick().await;
+        stats::start_week_if_due();
+        let locale = chat_config::get(chat_id).locale;
+        let Some(summary) = weekly_summary(&locale) else {
+            continue;
+        };
+        if let Err(err) = bot.send_message(chat_id, summary).await {
+            log::error!("Failed to post weekly summary: {err}");
+            continue;
+        }
+        stats::clear_last_week();
+    }
+}
+
+/// Summarizes the games of the week that ended, or `None` if it was posted or nobody played
+fn weekly_summary(locale: &str) -> Option<String> {
+    stats::read(|stats| {
+        let week = stats.last_week.as_ref()?;
+
+        let games = week.games.to_string();
+        let mut summary = i18n::msg(locale, MsgKey::WeekGames, &[("games", &games)]);
+        let top_players = stats.top_players(week, 3);
+        if !top_players.is_empty() {
+            summary += "\n";
+            summary += &i18n::msg(locale, MsgKey::WeekTopPlayers, &[]);
+            summary += &wins_entries(locale, &top_players);
+        }
+        if let Some((word, answer_stats)) = week.hardest_word() {
+            let args = [
+                ("word", word),
+                (
+                    "loss_rate",
+                    &format!("{:.0}", answer_stats.loss_rate() * 100.0),
+                ),
+                ("plays", &answer_stats.plays.to_string()),
+            ];
+            summary += "\n";
+            summary += &i18n::msg(locale, MsgKey::WeekHardestWord, &args);
+        }
+        Some(summary)
+    })
+}
+
+/// Ranked `(name, wins)` lines of a standings message, each on a new line
+fn wins_entries(locale: &str, players: &[(&str, u32)]) -> String {
+    players
+        .iter()
+        .enumerate()
+        .map(|(i, (name, wins))| {
+            let rank = (i + 1).to_string();
+            let wins = wins.to_string();
+            let args = [("rank", rank.as_str()), ("name", name), ("wins", &wins)];
+            format!("\n{}", i18n::msg(locale, MsgKey::WinsEntry, &args))
+        })
+        .collect()
+}
+
+async fn dispatch(bot: AutoSend<Bot>) {
+    // Dialogues are keyed by chat id, so everyone in a group plays the same game. A private
+    // chat's id is the user's own id, which never clashes with a group's (those are negative),
+    // so a user's DM game and their games in any number of groups are independent of each other.
+    // Each bot instance also keeps its own dialogues.
+    let storage = InMemStorage::new();
+    let chat_storage = storage.clone();
+    Dispatcher::new(bot)
+        .messages_handler(DialogueDispatcher::with_storage(handle_dialogue, storage))
+        .my_chat_members_handler(move |rx| handle_my_chat_members(rx, chat_storage))
+        .edited_messages_handler(handle_edited_messages)
+        .inline_queries_handler(handle_inline_queries)
+        .setup_ctrlc_handler()
+        .dispatch()
+        .await;
+}
+
+/// Reacts to a message with the dialogue kept for its chat
+async fn handle_dialogue(
+    DialogueWithCx { cx, dialogue }: DialogueWithCx<
+        AutoSend<Bot>,
+        Message,
+        Dialogue,
+        InMemStorageError,
+    >,
+) -> DialogueStage<Dialogue> {
+    let dialogue = dialogue.expect("std::convert::Infallible");
+    handle_message(cx, dialogue).await
+}
+
+/// Welcomes groups the bot is added to, and forgets the games of chats it's removed from. Stats
+/// are always kept, and chat settings too unless `FORGET_REMOVED_CHATS` is set.
+async fn handle_my_chat_members(
+    mut rx: DispatcherHandlerRx<AutoSend<Bot>, ChatMemberUpdated>,
+    storage: Arc<InMemStorage<Dialogue>>,
+) {
+    while let Some(cx) = rx.recv().await {
+        let update = &cx.update;
+        let chat_id = update.chat.id;
+        let was_present = update.old_chat_member.is_present();
+        let is_present = update.new_chat_member.is_present();
+
+        if !was_present && is_present && !update.chat.is_private() {
+            log::info!("Added to chat {chat_id}");
+            let welcome = WELCOME_MESSAGE.clone().unwrap_or_else(|| {
+                let locale = chat_config::get(chat_id).locale;
+                let help = i18n::msg(&locale, MsgKey::Help, &[]);
+                i18n::msg(&locale, MsgKey::Welcome, &[("help", &help)])
+            });
+            cx.requester.send_message(chat_id, welcome).await.ok();
+        } else if was_present && !is_present {
+            log::info!("Removed from chat {chat_id}");
+            // Fails when the chat had no game running, which is fine
+            storage.clone().remove_dialogue(chat_id).await.ok();
+            FORCED_ANSWERS
+                .lock()
+                .expect("failed to lock FORCED_ANSWERS")
+                .remove(&chat_id);
+            if *FORGET_REMOVED_CHATS {
+                chat_config::remove(chat_id);
+            }
+        }
+    }
+}
+
+/// Edited messages never affect a game: treating an edit as a new guess would let players rewrite
+/// a losing guess after seeing its colors. Edited guesses get a reminder to send a new message.
+async fn handle_edited_messages(mut rx: DispatcherHandlerRx<AutoSend<Bot>, Message>) {
+    while let Some(cx) = rx.recv().await {
+        let command = cx
+            .update
+            .text()
+            .and_then(|text| text.split_whitespace().next())
+            .map(strip_bot_mention);
+        if command == Some("/guess") {
+            tokio::spawn(async move {
+                cx.reply_to(tr(&cx, MsgKey::EditsIgnored, &[])).await.ok();
+            });
+        }
+    }
+}
+
+/// Answers `@botname <word>` queries (inline mode has to be enabled with @BotFather) with
+/// whether the word can be guessed, so people can check words without starting a game
+async fn handle_inline_queries(mut rx: DispatcherHandlerRx<AutoSend<Bot>, InlineQuery>) {
+    while let Some(cx) = rx.recv().await {
+        tokio::spawn(async move {
+            let word = cx.update.query.trim().to_lowercase();
+            if word.is_empty() {
+                return;
+            }
+
+            // Inline queries aren't sent from a chat, so they're answered in the user's language
+            let locale = cx
+                .update
+                .from
+                .language_code
+                .as_deref()
+                .filter(|code| i18n::LOCALES.contains(code))
+                .unwrap_or(i18n::DEFAULT_LOCALE);
+            let length = word_length().to_string();
+            let key = if letter_count(&word) != word_length() {
+                MsgKey::WordCheckLength
+            } else if is_game_word(&word, None) {
+                MsgKey::WordCheckAnswer
+            } else if is_dictionary_word(&word, None) {
+                MsgKey::WordCheckGuess
+            } else {
+                MsgKey::WordCheckUnknown
+            };
+            let verdict = i18n::msg(locale, key, &[("word", &word), ("length", &length)]);
+
+            let result = InlineQueryResultArticle::new(
+                "word-check",
+                verdict.clone(),
+                InputMessageContent::Text(InputMessageContentText::new(verdict)),
+            );
+            if let Err(err) = cx
+                .requester
+                .answer_inline_query(cx.update.id, [InlineQueryResult::Article(result)])
+                .await
+            {
+                log::error!("Failed to answer inline query: {err}");
+            }
+        });
+    }
+}
+
+/// Errors that can happen while reacting to a message
+#[derive(Debug, Display, From)]
+pub enum BotError {
+    #[display(fmt = "failed to talk to Telegram: {}", _0)]
+    Request(RequestError),
+}
+
+/// Reacts to a message, keeping the chat's previous dialogue if anything goes wrong so one bad
+/// update can't take the bot down for everyone
+async fn handle_message(
+    cx: UpdateWithCx<AutoSend<Bot>, Message>,
+    dialogue: Dialogue,
+) -> DialogueStage<Dialogue> {
+    let previous = dialogue.clone();
+    let requester = cx.requester.clone();
+    let chat_id = cx.update.chat.id;
+    let locale = chat_config::get(chat_id).locale;
+
+    match route_message(cx, dialogue).await {
+        Ok(stage) => stage,
+        Err(err) => {
+            log::error!("Error handling message in chat {chat_id}: {err}");
+            requester
+                .send_message(chat_id, i18n::msg(&locale, MsgKey::Error, &[]))
+                .await
+                .ok();
+            DialogueStage::Next(previous)
+        }
+    }
+}
+
+async fn route_message(
+    cx: UpdateWithCx<AutoSend<Bot>, Message>,
+    dialogue: Dialogue,
+) -> TransitionOut<Dialogue, BotError> {
+    // Aliases are resolved first, so every state sees the command they stand for
+    let ans = match cx.update.text().map(resolve_alias) {
+        None => return next(dialogue),
+        Some(ans) => ans,
+    };
+    let command = ans
+        .split_whitespace()
+        .next()
+        .map(strip_bot_mention)
+        .unwrap_or_default();
+
+    let user_id = cx.update.from().map(|user| user.id);
+    // Floods are dropped without a reply, answering them would only add to the flood
+    if command.starts_with('/') && !user_id.is_none_or(command_allowed) {
+        log::debug!("Dropping {command} from user {user_id:?} within the command cooldown");
+        return next(dialogue);
+    }
+    // While under maintenance, players' dialogues are left untouched so their games resume as
+    // they were once maintenance ends
+    if held_by_maintenance(command) && !is_admin(&cx) {
+        cx.answer(tr(&cx, MsgKey::Maintenance, &[])).await?;
+        return next(dialogue);
+    }
+
+    match command {
+        "/help" => {
+            cx.answer(tr(&cx, MsgKey::Help, &[])).await?;
+        }
+        "/modes" => {
+            cx.answer(modes_list(&chat_locale(&cx))).await?;
+        }
+        "/feedback" => {
+            let text = ans
+                .split_once(char::is_whitespace)
+                .map(|(_, text)| text.trim());
+            send_feedback(&cx, text.unwrap_or_default()).await?;
+        }
+        "/stats" => {
+            if let Some(user_id) = user_id {
+                cx.answer(user_stats(&chat_locale(&cx), user_id)).await?;
+            }
+        }
+        "/season" => {
+            stats::start_season_if_due();
+            cx.answer(season_standings(&chat_locale(&cx))).await?;
+        }
+        "/leaderboard" => {
+            cx.answer(chat_leaderboard(&chat_locale(&cx), cx.update.chat.id))
+                .await?;
+        }
+        "/setdefault" => {
+            if let Some(user_id) = user_id {
+                let modes: Vec<&str> = ans.split_whitespace().skip(1).collect();
+                cx.answer(set_default_modes(&chat_locale(&cx), user_id, &modes))
+                    .await?;
+            }
+        }
+        "/optout" => {
+            if let Some(user_id) = user_id {
+                stats::opt_out(user_id);
+                cx.answer(tr(&cx, MsgKey::OptedOut, &[])).await?;
+            }
+        }
+        "/optin" => {
+            if let Some(user_id) = user_id {
+                stats::opt_in(user_id);
+                cx.answer(tr(&cx, MsgKey::OptedIn, &[])).await?;
+            }
+        }
+        "/dictstats" => {
+            if is_admin(&cx) {
+                cx.answer(dictionary_stats(&chat_locale(&cx))).await?;
+            } else {
+                cx.answer(tr(&cx, MsgKey::AdminsOnly, &[("command", "/dictstats")]))
+                    .await?;
+            }
+        }
+        "/diffwords" => {
+            if is_admin(&cx) {
+                cx.answer(fit_message(word_list_diff(&chat_locale(&cx))))
+                    .await?;
+            } else {
+                cx.answer(tr(&cx, MsgKey::AdminsOnly, &[("command", "/diffwords")]))
+                    .await?;
+            }
+        }
+        "/hardestwords" => {
+            if is_admin(&cx) {
+                cx.answer(hardest_words(&chat_locale(&cx))).await?;
+            } else {
+                cx.answer(tr(&cx, MsgKey::AdminsOnly, &[("command", "/hardestwords")]))
+                    .await?;
+            }
+        }
+        "/selftest" => {
+            if is_admin(&cx) {
+                cx.answer(tr(&cx, MsgKey::SelfTestRunning, &[])).await?;
+                let locale = chat_locale(&cx);
+                let report = tokio::task::spawn_blocking(move || self_test(&locale))
+                    .await
+                    .unwrap_or_else(|err| {
+                        tr(&cx, MsgKey::SelfTestFailed, &[("error", &err.to_string())])
+                    });
+                cx.answer(report).await?;
+            } else {
+                cx.answer(tr(&cx, MsgKey::AdminsOnly, &[("command", "/selftest")]))
+                    .await?;
+            }
+        }
+        "/dumpanswers" => {
+            if !is_admin(&cx) {
+                cx.answer(tr(&cx, MsgKey::AdminsOnly, &[("command", "/dumpanswers")]))
+                    .await?;
+            } else if let Some(csv) = answers_csv() {
+                cx.answer_document(InputFile::memory("answers.csv", csv.into_bytes()))
+                    .await?;
+            } else {
+                cx.answer(tr(&cx, MsgKey::NoGamesThisWeek, &[])).await?;
+            }
+        }
+        "/recent" => {
+            let reply = if is_admin(&cx) {
+                let limit = ans
+                    .split_whitespace()
+                    .nth(1)
+                    .and_then(|limit| limit.parse().ok())
+                    .unwrap_or(RECENT_WORDS_SHOWN);
+                recent_words::list(limit)
+            } else {
+                tr(&cx, MsgKey::AdminsOnly, &[("command", "/recent")])
+            };
+            cx.answer(fit_message(reply)).await?;
+        }
+        "/score" => {
+            let mut args = ans.split_whitespace().skip(1);
+            let reply = if is_admin(&cx) {
+                score_breakdown(&chat_locale(&cx), args.next(), args.next())
+            } else {
+                tr(&cx, MsgKey::AdminsOnly, &[("command", "/score")])
+            };
+            cx.answer(reply).await?;
+        }
+        "/setanswer" => {
+            let mut args = ans.split_whitespace().skip(1);
+            set_forced_answer(&cx, args.next(), args.next()).await?;
+        }
+        "/maintenance" => {
+            set_maintenance(&cx, ans.split_whitespace().nth(1)).await?;
+        }
+        "/pausesave" => {
+            pause_saves(&cx, true).await?;
+        }
+        "/resumesave" => {
+            pause_saves(&cx, false).await?;
+        }
+        "/clearstats" => {
+            clear_stats(&cx, ans.split_whitespace().nth(1)).await?;
+        }
+        "/chatwords" => {
+            let args: Vec<&str> = ans.split_whitespace().skip(1).collect();
+            edit_chat_words(&cx, &args).await?;
+        }
+        "/config" => {
+            let mut args = ans.split_whitespace().skip(1);
+            configure_chat(&cx, args.next(), args.next()).await?;
+        }
+        _ => return dialogue.react(cx, ans).await,
+    }
+
+    next(dialogue)
+}
+
+/// Whether maintenance holds off `command` for players. Only commands that don't change games,
+/// word lists or settings are let through.
+fn held_by_maintenance(command: &str) -> bool {
+    MAINTENANCE.load(Ordering::Relaxed)
+        && command.starts_with('/')
+        && !MAINTENANCE_COMMANDS.contains(&command)
+}
+
+/// Whether `user_id` is past the command cooldown, starting a new one if so
+fn command_allowed(user_id: i64) -> bool {
+    let cooldown = *COMMAND_COOLDOWN;
+    if cooldown.is_zero() {
+        return true;
+    }
+
+    let mut last_command = LAST_COMMAND.lock().expect("failed to lock LAST_COMMAND");
+    cooldown_passed(&mut last_command, user_id, Instant::now(), cooldown)
+}
+
+/// Whether `user_id` sent no command in `last_command` within `cooldown` before `now`, recording
+/// `now` as their last command if so. Users whose cooldown is over are dropped.
+fn cooldown_passed(
+    last_command: &mut HashMap<i64, Instant>,
+    user_id: i64,
+    now: Instant,
+    cooldown: Duration,
+) -> bool {
+    last_command.retain(|_, sent| now.duration_since(*sent) < cooldown);
+    if last_command.contains_key(&user_id) {
+        return false;
+    }
+    last_command.insert(user_id, now);
+    true
+}
+
+async fn send_feedback(cx: &TransitionIn<AutoSend<Bot>>, text: &str) -> Result<(), BotError> {
+    let feedback_chat = match env::var("FEEDBACK_CHAT_ID")
+        .ok()
+        .and_then(|id| id.parse::<i64>().ok())
+    {
+        Some(chat_id) => chat_id,
+        None => {
+            cx.answer(tr(cx, MsgKey::FeedbackDisabled, &[])).await?;
+            return Ok(());
+        }
+    };
+
+    let user = match cx.update.from() {
+        Some(user) => user,
+        None => return Ok(()),
+    };
+    if text.is_empty() {
+        cx.answer(tr(cx, MsgKey::FeedbackUsage, &[])).await?;
+        return Ok(());
+    }
+
+    let recently_sent = LAST_FEEDBACK
+        .lock()
+        .expect("failed to lock LAST_FEEDBACK")
+        .get(&user.id)
+        .is_some_and(|sent| sent.elapsed() < FEEDBACK_COOLDOWN);
+    if recently_sent {
+        cx.answer(tr(cx, MsgKey::FeedbackTooSoon, &[])).await?;
+        return Ok(());
+    }
+
+    let name = user.full_name();
+    let id = user.id;
+    cx.requester
+        .send_message(
+            feedback_chat,
+            format!("Feedback from {name} ({id}):\n{text}"),
+        )
+        .await?;
+    // Only a delivered message counts, so a failed forward can be retried right away
+    LAST_FEEDBACK
+        .lock()
+        .expect("failed to lock LAST_FEEDBACK")
+        .insert(user.id, Instant::now());
+    cx.answer(tr(cx, MsgKey::FeedbackSent, &[])).await?;
+    Ok(())
+}
+
+/// Summarizes word list sizes and which game words are overused or have gone stale
+fn dictionary_stats(locale: &str) -> String {
+    let word_usage = WORD_USAGE.get().expect("WORD_USAGE is not initialized");
+    let word_usage = word_usage
+        .read()
+        .expect("failed to lock WORD_USAGE")
+        .clone();
+
+    let game_words = GAME_WORDS.get().expect("GAME_WORDS is not initialized");
+    let game_words = game_words.read().expect("failed to lock GAME_WORDS");
+    let game_count = game_words.len();
+    let never_used = game_words
+        .iter()
+        .filter(|word| !word_usage.contains_key(*word))
+        .count();
+    let mut usage: Vec<(String, WordUsage)> = game_words
+        .iter()
+        .filter_map(|word| Some((word.to_string(), *word_usage.get(word)?)))
+        .collect();
+    drop(game_words);
+
+    let dict_words = DICT_WORDS.get().expect("DICT_WORDS is not initialized");
+    let dict_count = dict_words.read().expect("failed to lock DICT_WORDS").len();
+
+    let now = unix_now();
+    let format_words = |words: &[(String, WordUsage)]| {
+        words
+            .iter()
+            .map(|(word, usage)| {
+                let days_ago = now.saturating_sub(usage.last_used) / (24 * 60 * 60);
+                let args = [
+                    ("word", word.as_str()),
+                    ("uses", &usage.uses.to_string()),
+                    ("days", &days_ago.to_string()),
+                ];
+                i18n::msg(locale, MsgKey::WordUsage, &args)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    usage.sort_by_key(|(_, usage)| Reverse(usage.uses));
+    let most_used = format_words(&usage[..usage.len().min(5)]);
+    usage.sort_by_key(|(_, usage)| usage.last_used);
+    let stalest = format_words(&usage[..usage.len().min(5)]);
+    let (game_source, dict_source) = WORD_SOURCES.get().expect("WORD_SOURCES is not initialized");
+
+    let game_count = game_count.to_string();
+    let args = [
+        ("game_count", game_count.as_str()),
+        ("never_used", &never_used.to_string()),
+        ("game_source", game_source),
+        ("dict_count", &dict_count.to_string()),
+        ("dict_source", dict_source),
+        ("most_used", &most_used),
+        ("stalest", &stalest),
+    ];
+    i18n::msg(locale, MsgKey::DictStats, &args)
+}
+
+fn user_stats(locale: &str, user_id: i64) -> String {
+    stats::read(|stats| {
+        if stats.opted_out.contains(&user_id) {
+            return i18n::msg(locale, MsgKey::StatsOff, &[]);
+        }
+
+        let user_stats = stats.users.get(&user_id).cloned().unwrap_or_default();
+        let mut text = i18n::msg(
+            locale,
+            MsgKey::UserStats,
+            &[
+                ("played", &user_stats.played.to_string()),
+                ("wins", &user_stats.wins.to_string()),
+                ("win_rate", &user_stats.win_rate().to_string()),
+                ("hinted_wins", &user_stats.hinted_wins.to_string()),
+                ("streak", &user_stats.current_streak.to_string()),
+                ("max_streak", &user_stats.max_streak.to_string()),
+            ],
+        );
+        // Always shows the usual 6 rows, plus any longer wins from presets with more guesses
+        let mut distribution = user_stats.guess_distribution.clone();
+        if distribution.len() < DEFAULT_MAX_GUESSES {
+            distribution.resize(DEFAULT_MAX_GUESSES, 0);
+        }
+        let most = distribution
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or_default()
+            .max(1);
+        for (i, wins) in distribution.iter().enumerate() {
+            let bar = "█".repeat((wins * STATS_BAR_WIDTH).div_ceil(most) as usize);
+            text += &format!("\n{} {bar} {wins}", i + 1);
+        }
+        text
+    })
+}
+
+/// Best players of the chat by their longest streak, for /leaderboard
+fn chat_leaderboard(locale: &str, chat_id: i64) -> String {
+    stats::read(|stats| {
+        let players = stats.chat_leaderboard(chat_id, LEADERBOARD_SIZE);
+        if players.is_empty() {
+            return i18n::msg(locale, MsgKey::NoLeaderboard, &[]);
+        }
+
+        let mut leaderboard = i18n::msg(locale, MsgKey::Leaderboard, &[]);
+        for (i, player) in players.iter().enumerate() {
+            let rank = (i + 1).to_string();
+            let streak = player.max_streak.to_string();
+            let win_rate = player.win_rate().to_string();
+            let args = [
+                ("rank", rank.as_str()),
+                ("name", &player.name),
+                ("streak", &streak),
+                ("win_rate", &win_rate),
+            ];
+            leaderboard += "\n";
+            leaderboard += &i18n::msg(locale, MsgKey::LeaderboardEntry, &args);
+        }
+        leaderboard
+    })
+}
+
+/// Standings of the current season, with the time left and the previous season's winner
+fn season_standings(locale: &str) -> String {
+    stats::read(|stats| {
+        let left = stats::next_month_start(stats.season.started).saturating_sub(unix_now());
+        let mut standings = i18n::msg(
+            locale,
+            MsgKey::SeasonEnds,
+            &[
+                ("days", &(left / (24 * 60 * 60)).to_string()),
+                ("hours", &(left / (60 * 60) % 24).to_string()),
+            ],
+        );
+
+        let top_players = stats.top_players(&stats.season, 10);
+        if top_players.is_empty() {
+            standings += "\n";
+            standings += &i18n::msg(locale, MsgKey::NoSeasonWins, &[]);
+        }
+        standings += &wins_entries(locale, &top_players);
+
+        let last_winner = stats
+            .past_seasons
+            .last()
+            .and_then(|season| season.standings.first());
+        if let Some((name, wins)) = last_winner {
+            let args = [("name", name.as_str()), ("wins", &wins.to_string())];
+            standings += "\n";
+            standings += &i18n::msg(locale, MsgKey::LastSeasonWinner, &args);
+        }
+        standings
+    })
+}
+
+/// Sets the modes and presets a bare /wordle starts `user_id`'s games in, clearing them without
+/// `modes`
+fn set_default_modes(locale: &str, user_id: i64, modes: &[&str]) -> String {
+    let name = |mode: &str| {
+        GameMode::parse(mode)
+            .map(GameMode::name)
+            .or_else(|| Some(Preset::parse(mode)?.name))
+    };
+    let (valid, invalid): (Vec<&str>, Vec<&str>) =
+        modes.iter().partition(|mode| name(mode).is_some());
+    if !invalid.is_empty() {
+        let modes = invalid.join(", ");
+        return i18n::msg(locale, MsgKey::UnknownModes, &[("modes", &modes)]);
+    }
+
+    let modes: Vec<String> = valid
+        .iter()
+        .filter_map(|mode| name(mode))
+        .map(String::from)
+        .collect();
+    let reply = if modes.is_empty() {
+        i18n::msg(locale, MsgKey::DefaultModesCleared, &[])
+    } else {
+        let modes = modes.join(" ");
+        i18n::msg(locale, MsgKey::DefaultModesSet, &[("modes", &modes)])
+    };
+    user_config::update(user_id, |config| config.default_modes = modes);
+    reply
+}
+
+/// Has the solver play a sample of the answers, as a check that the word lists are healthy
+fn self_test(locale: &str) -> String {
+    let words: Vec<String> = {
+        let game_words = GAME_WORDS.get().expect("GAME_WORDS is not initialized");
+        let game_words = game_words.read().expect("failed to lock GAME_WORDS");
+        game_words
+            .iter()
+            .filter(|word| letter_count(word) == word_length())
+            .map(str::to_string)
+            .collect()
+    };
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let sample = words
+        .iter()
+        .choose_multiple(&mut rand::thread_rng(), SELFTEST_SAMPLE_SIZE);
+
+    let mut total = 0;
+    let mut lost = 0;
+    let mut worst: Option<(&str, usize)> = None;
+    let mut unsolved = Vec::new();
+    for answer in &sample {
+        let Some(guesses) = solver::solve(answer, &words) else {
+            unsolved.push(**answer);
+            continue;
+        };
+        total += guesses.len();
+        if guesses.len() > DEFAULT_MAX_GUESSES {
+            lost += 1;
+        }
+        if worst.is_none_or(|(_, tries)| guesses.len() > tries) {
+            worst = Some((answer, guesses.len()));
+        }
+    }
+
+    let solved = sample.len() - unsolved.len();
+    let Some((worst_word, worst_tries)) = worst else {
+        let length = word_length().to_string();
+        return i18n::msg(locale, MsgKey::SelfTestNoAnswers, &[("length", &length)]);
+    };
+    let solved_text = solved.to_string();
+    let args = [
+        ("solved", solved_text.as_str()),
+        ("sampled", &sample.len().to_string()),
+        ("average", &format!("{:.2}", total as f64 / solved as f64)),
+        ("worst", worst_word),
+        ("worst_tries", &worst_tries.to_string()),
+        ("max", &DEFAULT_MAX_GUESSES.to_string()),
+        ("lost", &lost.to_string()),
+    ];
+    let mut report = i18n::msg(locale, MsgKey::SelfTestReport, &args);
+    if !unsolved.is_empty() {
+        let words = unsolved.join(", ");
+        report += "\n";
+        report += &i18n::msg(locale, MsgKey::SelfTestUnsolved, &[("words", &words)]);
+    }
+    report
+}
+
+/// Shows how `guess` would be scored against `answer`, letter by letter, without a game
+fn score_breakdown(locale: &str, answer: Option<&str>, guess: Option<&str>) -> String {
+    let (Some(answer), Some(guess)) = (answer, guess) else {
+        return i18n::msg(locale, MsgKey::ScoreUsage, &[]);
+    };
+    let answer = answer.to_lowercase();
+    let guess = guess.to_lowercase();
+    let is_word = |word: &str| {
+        letter_count(word) == word_length()
+            && word
+                .graphemes(true)
+                .all(|letter| letter.chars().next().is_some_and(char::is_alphabetic))
+    };
+    if !is_word(&answer) || !is_word(&guess) {
+        let length = word_length().to_string();
+        return i18n::msg(locale, MsgKey::ScoreLength, &[("length", &length)]);
+    }
+
+    let placement = score_guess(&guess, &answer);
+    let mut breakdown = to_emoji(&placement);
+    for (i, (letter, placement)) in guess.graphemes(true).zip(placement).enumerate() {
+        let verdict = match placement {
+            Placement::Correct => MsgKey::ScoreCorrect,
+            Placement::Incorrect => MsgKey::ScoreIncorrect,
+            Placement::Missing => MsgKey::ScoreMissing,
+        };
+        let position = (i + 1).to_string();
+        let args = [
+            ("position", position.as_str()),
+            ("letter", letter),
+            ("verdict", &i18n::msg(locale, verdict, &[])),
+        ];
+        breakdown += "\n";
+        breakdown += &i18n::msg(locale, MsgKey::ScoreLetter, &args);
+    }
+    breakdown
+}
+
+/// This week's answers as CSV, with how many guesses they took on average. `None` if there were
+/// no games. Games of players who opted out are never recorded, so they can't show up here.
+fn answers_csv() -> Option<String> {
+    // Rolled first, so the games of a week that's over aren't exported as this week's
+    stats::start_week_if_due();
+    stats::read(|stats| {
+        if stats.week.answers.is_empty() {
+            return None;
+        }
+
+        let mut csv = String::from("answer,plays,losses,average_tries\n");
+        for (answer, answer_stats) in &stats.week.answers {
+            csv += &format!(
+                "{answer},{},{},{:.2}\n",
+                answer_stats.plays,
+                answer_stats.losses,
+                answer_stats.average_tries()
+            );
+        }
+        Some(csv)
+    })
+}
+
+/// How the word sets differ from the shipped base files, to audit what was added and removed
+/// over time. The base files are read on demand instead of being kept around.
+fn word_list_diff(locale: &str) -> String {
+    let store = DICTIONARY_STORE
+        .get()
+        .expect("DICTIONARY_STORE is not initialized");
+    let sample = |words: &[&str]| {
+        let mut sample = words[..words.len().min(DIFF_SAMPLE_SIZE)].join(", ");
+        if words.len() > DIFF_SAMPLE_SIZE {
+            sample += ", ..";
+        }
+        sample
+    };
+
+    let dictionaries: [_; 2] = [
+        (&GAME_WORDS, "words.txt", MsgKey::DiffGameWords),
+        (&DICT_WORDS, "dictionary.txt", MsgKey::DiffDictWords),
+    ];
+    let mut report = Vec::new();
+    for (dict, base_file, name) in dictionaries {
+        let name = i18n::msg(locale, name, &[]);
+        let contents = match store.load(base_file) {
+            Ok(Some(contents)) => contents,
+            Ok(None) => {
+                let args = [("name", name.as_str()), ("file", base_file)];
+                report.push(i18n::msg(locale, MsgKey::DiffNoBase, &args));
+                continue;
+            }
+            Err(err) => {
+                let args = [
+                    ("name", name.as_str()),
+                    ("file", base_file),
+                    ("error", &err.to_string()),
+                ];
+                report.push(i18n::msg(locale, MsgKey::DiffUnreadable, &args));
+                continue;
+            }
+        };
+        let dict = dict.get().expect("dictionary not initialized");
+        let dict = dict.read().expect("could not lock dictionary");
+        let (added, removed) = diff_words(&dict, &contents);
+
+        let args = [
+            ("name", name.as_str()),
+            ("added", &added.len().to_string()),
+            ("removed", &removed.len().to_string()),
+            ("file", base_file),
+        ];
+        let mut line = i18n::msg(locale, MsgKey::DiffSummary, &args);
+        if !added.is_empty() {
+            line += "\n";
+            line += &i18n::msg(locale, MsgKey::DiffAdded, &[("words", &sample(&added))]);
+        }
+        if !removed.is_empty() {
+            line += "\n";
+            line += &i18n::msg(locale, MsgKey::DiffRemoved, &[("words", &sample(&removed))]);
+        }
+        report.push(line);
+    }
+    report.join("\n\n")
+}
+
+/// Words of `words` that aren't in the `base` file, and words of `base` that aren't in `words`,
+/// both in order
+fn diff_words<'a>(words: &'a WordSet, base: &'a str) -> (Vec<&'a str>, Vec<&'a str>) {
+    // Words the loader would have skipped aren't missing from the word set
+    let base: BTreeSet<&str> = base
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| parse_word_line(line).0)
+        .filter(|word| !word.is_empty() && WORD_LENGTHS.contains(&letter_count(word)))
+        .collect();
+    let mut added: Vec<&str> = words.iter().filter(|word| !base.contains(word)).collect();
+    added.sort_unstable();
+    let removed = base
+        .into_iter()
+        .filter(|word| !words.contains(word))
+        .collect();
+    (added, removed)
+}
+
+/// Lists the answers players lose to most often, to help curators retire unfair words
+fn hardest_words(locale: &str) -> String {
+    let hardest = stats::read(|stats| {
+        stats
+            .hardest_words(HARDEST_WORDS_MIN_PLAYS, 10)
+            .into_iter()
+            .enumerate()
+            .map(|(i, (word, answer_stats))| {
+                let rank = (i + 1).to_string();
+                let args = [
+                    ("rank", rank.as_str()),
+                    ("word", word),
+                    (
+                        "loss_rate",
+                        &format!("{:.0}", answer_stats.loss_rate() * 100.0),
+                    ),
+                    ("plays", &answer_stats.plays.to_string()),
+                ];
+                i18n::msg(locale, MsgKey::HardestWordsEntry, &args)
+            })
+            .collect::<Vec<_>>()
+    });
+
+    if hardest.is_empty() {
+        let plays = HARDEST_WORDS_MIN_PLAYS.to_string();
+        i18n::msg(locale, MsgKey::NoHardestWords, &[("plays", &plays)])
+    } else {
+        let title = i18n::msg(locale, MsgKey::HardestWords, &[]);
+        format!("{title}\n{}", hardest.join("\n"))
+    }
+}
+
+/// Lets an admin pick the answer of the next game in a chat, e.g. for an event. The answer can
+/// be set from a private chat with the bot by passing the group's chat id, to avoid spoilers.
+async fn set_forced_answer(
+    cx: &TransitionIn<AutoSend<Bot>>,
+    word: Option<&str>,
+    chat_id: Option<&str>,
+) -> Result<(), BotError> {
+    if !is_admin(cx) {
+        cx.answer(tr(cx, MsgKey::AdminsOnly, &[("command", "/setanswer")]))
+            .await?;
+        return Ok(());
+    }
+
+    let word = word.map(normalize_guess).unwrap_or_default();
+    let chat_id = match chat_id {
+        None => Ok(cx.update.chat.id),
+        Some(chat_id) => chat_id.parse(),
+    };
+    let chat_id = match chat_id {
+        Ok(chat_id) if letter_count(&word) == word_length() => chat_id,
+        _ => {
+            let length = word_length().to_string();
+            cx.answer(tr(cx, MsgKey::SetAnswerUsage, &[("length", &length)]))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let locale = play_locale(&chat_config::get(chat_id).locale);
+    if let Err(reason) = check_forced_answer(&word, locale) {
+        cx.answer(tr(cx, reason, &[("word", &word), ("locale", locale)]))
+            .await?;
+        return Ok(());
+    }
+
+    // Answers are always guessable, so a word missing from the dictionary doesn't have to join it
+    FORCED_ANSWERS
+        .lock()
+        .expect("failed to lock FORCED_ANSWERS")
+        .insert(chat_id, word.clone());
+    let chat_id = chat_id.to_string();
+    cx.answer(tr(
+        cx,
+        MsgKey::AnswerSet,
+        &[("chat_id", &chat_id), ("word", &word)],
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Why `word` can't be set as the answer of a chat playing in `locale`, if it can't
+fn check_forced_answer(word: &str, locale: &str) -> Result<(), MsgKey> {
+    // Blocked words are never played, not even by admins
+    if is_blocked_word(word) {
+        return Err(MsgKey::AnswerBlocked);
+    }
+    // Guesses are checked against the chat's alphabet, so any other answer couldn't be won
+    if !in_alphabet(word, locale) {
+        return Err(MsgKey::AnswerNotSpellable);
+    }
+    Ok(())
+}
+
+/// Languages whose alphabet has every letter of `words`
+fn locales_spelling<'a>(words: impl IntoIterator<Item = &'a str>) -> Vec<&'static str> {
+    let letters: BTreeSet<&str> = words
+        .into_iter()
+        .flat_map(|word| word.graphemes(true))
+        .collect();
+    i18n::LOCALES
+        .into_iter()
+        .filter(|locale| {
+            let alphabet = i18n::alphabet(locale);
+            letters.iter().all(|letter| alphabet.contains(letter))
+        })
+        .collect()
+}
+
+/// Language whose alphabet and keyboard a chat set to `locale` plays with. That's `locale`
+/// unless the word lists can't be spelled in it, say after they were swapped for another
+/// language's, in which case it's the first language that can.
+fn play_locale(locale: &str) -> &'static str {
+    let word_locales = WORD_LOCALES.get().expect("WORD_LOCALES is not initialized");
+    word_locales
+        .iter()
+        .find(|word_locale| **word_locale == locale)
+        .unwrap_or(&word_locales[0])
+}
+
+/// Whether every letter of `word` is in the alphabet of `locale`
+fn in_alphabet(word: &str, locale: &str) -> bool {
+    let alphabet = i18n::alphabet(locale);
+    word.graphemes(true).all(|letter| alphabet.contains(letter))
+}
+
+/// Returns the next answer for a chat, using up the answer set with /setanswer if there is one
+fn next_answer(chat_id: i64, lengths: &RangeInclusive<usize>) -> Option<String> {
+    let forced_answer = FORCED_ANSWERS
+        .lock()
+        .expect("failed to lock FORCED_ANSWERS")
+        .remove(&chat_id);
+    forced_answer.or_else(|| get_random_word(chat_id, lengths))
+}
+
+async fn set_maintenance(
+    cx: &TransitionIn<AutoSend<Bot>>,
+    setting: Option<&str>,
+) -> Result<(), BotError> {
+    if !is_admin(cx) {
+        cx.answer(tr(cx, MsgKey::AdminsOnly, &[("command", "/maintenance")]))
+            .await?;
+        return Ok(());
+    }
+
+    match setting {
+        Some("on") => {
+            MAINTENANCE.store(true, Ordering::Relaxed);
+            cx.answer(tr(cx, MsgKey::MaintenanceOn, &[])).await?;
+        }
+        Some("off") => {
+            MAINTENANCE.store(false, Ordering::Relaxed);
+            cx.answer(tr(cx, MsgKey::MaintenanceOff, &[])).await?;
+        }
+        _ => {
+            cx.answer(tr(cx, MsgKey::MaintenanceUsage, &[])).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Pauses or resumes the worker's saves. Resuming always saves the word lists once, even if
+/// nothing changed while paused.
+async fn pause_saves(cx: &TransitionIn<AutoSend<Bot>>, paused: bool) -> Result<(), BotError> {
+    if !is_admin(cx) {
+        let command = if paused { "/pausesave" } else { "/resumesave" };
+        cx.answer(tr(cx, MsgKey::AdminsOnly, &[("command", command)]))
+            .await?;
+        return Ok(());
+    }
+
+    SAVE_PAUSED.store(paused, Ordering::Relaxed);
+    if paused {
+        cx.answer(tr(cx, MsgKey::SavePaused, &[])).await?;
+    } else {
+        mark_dictionary_dirty();
+        cx.answer(tr(cx, MsgKey::SaveResumed, &[])).await?;
+    }
+    Ok(())
+}
+
+/// Shows the chat's settings, or changes one of them as `/config <SETTING> <VALUE>`
+async fn configure_chat(
+    cx: &TransitionIn<AutoSend<Bot>>,
+    setting: Option<&str>,
+    value: Option<&str>,
+) -> Result<(), BotError> {
+    let chat_id = cx.update.chat.id;
+    let Some(setting) = setting else {
+        let config = chat_config::get(chat_id);
+        let on_off = |enabled| if enabled { "on" } else { "off" };
+        let languages = WORD_LOCALES
+            .get()
+            .expect("WORD_LOCALES is not initialized")
+            .join(", ");
+        let args = [
+            ("dictionary", on_off(config.require_dictionary)),
+            ("private_answer", on_off(config.private_answer)),
+            ("language", &config.locale),
+            ("languages", &languages),
+        ];
+        cx.answer(i18n::msg(&config.locale, MsgKey::ConfigSettings, &args))
+            .await?;
+        return Ok(());
+    };
+
+    if !can_configure_chat(cx).await? {
+        cx.answer(tr(cx, MsgKey::ConfigAdminsOnly, &[])).await?;
+        return Ok(());
+    }
+
+    if setting == "language" {
+        // Only languages that can spell the answers, guesses are checked against their alphabet
+        let word_locales = WORD_LOCALES.get().expect("WORD_LOCALES is not initialized");
+        match value.filter(|locale| word_locales.contains(locale)) {
+            Some(locale) => {
+                chat_config::update(chat_id, |config| config.locale = locale.into());
+                // Already in the new language
+                cx.answer(tr(cx, MsgKey::LanguageSet, &[("locale", locale)]))
+                    .await?;
+            }
+            None => {
+                let languages = word_locales.join("|");
+                cx.answer(tr(cx, MsgKey::LanguageUsage, &[("languages", &languages)]))
+                    .await?;
+            }
+        }
+        return Ok(());
+    }
+
+    let enabled = match value {
+        Some("on") => true,
+        Some("off") => false,
+        _ => {
+            cx.answer(tr(cx, MsgKey::ConfigUsage, &[])).await?;
+            return Ok(());
+        }
+    };
+    match setting {
+        "dictionary" => {
+            chat_config::update(chat_id, |config| config.require_dictionary = enabled);
+            let key = if enabled {
+                MsgKey::DictionaryOn
+            } else {
+                MsgKey::DictionaryOff
+            };
+            cx.answer(tr(cx, key, &[])).await?;
+        }
+        "privateanswer" => {
+            chat_config::update(chat_id, |config| config.private_answer = enabled);
+            let key = if enabled {
+                MsgKey::PrivateAnswerOn
+            } else {
+                MsgKey::PrivateAnswerOff
+            };
+            cx.answer(tr(cx, key, &[])).await?;
+        }
+        _ => {
+            cx.answer(tr(cx, MsgKey::UnknownSetting, &[("setting", setting)]))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Lists the chat's own words, or changes them as `/chatwords add|remove <WORD> [..WORD2]`.
+/// They're only used in this chat's games, as answers as well as guesses.
+async fn edit_chat_words(cx: &TransitionIn<AutoSend<Bot>>, args: &[&str]) -> Result<(), BotError> {
+    let chat_id = cx.update.chat.id;
+    let Some((action, words)) = args.split_first() else {
+        let words = chat_words::words(chat_id);
+        if words.is_empty() {
+            cx.answer(tr(cx, MsgKey::NoChatWords, &[])).await?;
+        } else {
+            let words: Vec<&str> = words.iter().map(String::as_str).collect();
+            let words = words.join(", ");
+            cx.answer(tr(cx, MsgKey::ChatWords, &[("words", &words)]))
+                .await?;
+        }
+        return Ok(());
+    };
+
+    if !can_configure_chat(cx).await? {
+        cx.answer(tr(cx, MsgKey::ChatWordsAdminsOnly, &[])).await?;
+        return Ok(());
+    }
+
+    let words: Vec<String> = words.iter().map(|word| normalize_guess(word)).collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    match *action {
+        "add" if !words.is_empty() => {
+            let (valid, invalid): (Vec<&str>, Vec<&str>) = words
+                .iter()
+                .partition(|word| letter_count(word) == word_length() && !is_blocked_word(word));
+            let added = chat_words::add(chat_id, &valid);
+            let mut reply = words_message(&chat_locale(cx), MsgKey::WordsAdded, added);
+            if !invalid.is_empty() {
+                let words = format!("{invalid:?}");
+                reply += "\n";
+                reply += &tr(cx, MsgKey::WordsSkipped, &[("words", &words)]);
+            }
+            cx.answer(reply).await?;
+        }
+        "remove" if !words.is_empty() => {
+            let removed = chat_words::remove(chat_id, &words);
+            let reply = words_message(&chat_locale(cx), MsgKey::WordsRemoved, removed);
+            cx.answer(reply).await?;
+        }
+        _ => {
+            cx.answer(tr(cx, MsgKey::ChatWordsUsage, &[])).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Bot admins can configure any chat, everyone else only private chats or groups they administer
+async fn can_configure_chat(cx: &TransitionIn<AutoSend<Bot>>) -> Result<bool, BotError> {
+    if is_admin(cx) || cx.update.chat.is_private() {
+        return Ok(true);
+    }
+    let Some(user) = cx.update.from() else {
+        return Ok(false);
+    };
+
+    let member = cx
+        .requester
+        .get_chat_member(cx.update.chat.id, user.id)
+        .await?;
+    Ok(member.is_privileged())
+}
+
+struct PendingClear {
+    /// User whose stats would be cleared, `None` for everyone
+    user_id: Option<i64>,
+    requested: Instant,
+}
+
+/// `/clearstats [USER_ID]` only asks for confirmation, the stats are cleared by a following
+/// `/clearstats confirm` from the same admin
+async fn clear_stats(cx: &TransitionIn<AutoSend<Bot>>, arg: Option<&str>) -> Result<(), BotError> {
+    let admin_id = match cx.update.from() {
+        Some(user) if is_admin(cx) => user.id,
+        _ => {
+            cx.answer(tr(cx, MsgKey::AdminsOnly, &[("command", "/clearstats")]))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let target = match arg {
+        Some("confirm") => {
+            let pending = PENDING_CLEARS
+                .lock()
+                .expect("failed to lock PENDING_CLEARS")
+                .remove(&admin_id)
+                .filter(|pending| pending.requested.elapsed() < CLEAR_CONFIRM_TIMEOUT);
+            let Some(PendingClear {
+                user_id: target, ..
+            }) = pending
+            else {
+                cx.answer(tr(cx, MsgKey::NothingToConfirm, &[])).await?;
+                return Ok(());
+            };
+
+            match stats::clear(&assets_dir(), target) {
+                Ok(backup) => {
+                    log::info!("Admin {admin_id} cleared stats of {target:?}");
+                    let path = backup.display().to_string();
+                    cx.answer(tr(cx, MsgKey::StatsCleared, &[("path", &path)]))
+                        .await?;
+                }
+                Err(err) => {
+                    log::error!("Failed to clear stats: {err}");
+                    cx.answer(tr(cx, MsgKey::StatsBackupFailed, &[])).await?;
+                }
+            }
+            return Ok(());
+        }
+        Some(user_id) => match user_id.parse::<i64>() {
+            Ok(user_id) => Some(user_id),
+            Err(_) => {
+                cx.answer(tr(cx, MsgKey::ClearStatsUsage, &[])).await?;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
+    PENDING_CLEARS
+        .lock()
+        .expect("failed to lock PENDING_CLEARS")
+        .insert(
+            admin_id,
+            PendingClear {
+                user_id: target,
+                requested: Instant::now(),
+            },
+        );
+    let seconds = CLEAR_CONFIRM_TIMEOUT.as_secs().to_string();
+    let reply = match target {
+        Some(user_id) => {
+            let user_id = user_id.to_string();
+            let args = [("user_id", user_id.as_str()), ("seconds", &seconds)];
+            tr(cx, MsgKey::ClearUserConfirm, &args)
+        }
+        None => tr(cx, MsgKey::ClearAllConfirm, &[("seconds", &seconds)]),
+    };
+    cx.answer(reply).await?;
+    Ok(())
+}
+
+// The states are dispatched by value through `Transition`, so the game state isn't boxed
+#[allow(clippy::large_enum_variant)]
+#[derive(From, Transition, Clone)]
+pub enum Dialogue {
+    Start(StartState),
+    Guess(GuessState),
+    Tutorial(TutorialState),
+}
+
+impl Default for Dialogue {
+    fn default() -> Self {
+        Self::Start(StartState)
+    }
+}
+
+pub enum DictionaryAction<'a> {
+    /// Adds the words that have the given length, the one of the running game
+    Add(&'a [&'a str], usize),
+    Remove(&'a [&'a str]),
+}
+
+async fn edit_dictionary(action: DictionaryAction<'_>, cx: TransitionIn<AutoSend<Bot>>) {
+    //-> AutoRequest<JsonRequest<SendMessage>> {
+    match action {
+        DictionaryAction::Add(words, length) => {
+            let report = merge_words(words, length);
+            let added: Vec<&str> = report.added().into_iter().collect();
+            if !added.is_empty() {
+                mark_dictionary_dirty();
+            }
+            recent_words::record(&added, cx.update.from());
+            let reply = report.message(&chat_locale(&cx));
+            cx.answer(fit_message(reply)).await.ok();
+        }
+        DictionaryAction::Remove(words) => {
+            let removed_words = remove_words(words);
+            mark_dictionary_dirty();
+            let reply = words_message(&chat_locale(&cx), MsgKey::WordsRemoved, removed_words);
+            cx.answer(reply).await.ok();
+        }
+    }
+}
+
+/// Removes `words` from both word sets, returning the ones that were in either
+fn remove_words<'a>(words: &[&'a str]) -> BTreeSet<&'a str> {
+    let mut removed_words = BTreeSet::new();
+
+    let dictionaries: [_; 2] = [&GAME_WORDS, &DICT_WORDS];
+    for dict in dictionaries {
+        let dict = dict.get().expect("dictionary not initialized");
+        let mut dict = dict.write().expect("could not lock dictionary");
+
+        for word in words {
+            if dict.remove(word) {
+                removed_words.insert(*word);
+            }
+        }
+    }
+    removed_words
+}
+
+/// Describes the running build, so operators can confirm what's deployed
+fn version_info() -> String {
+    let profile = if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    };
+    format!(
+        "{} {} ({}, {profile} build, {})",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        env!("TELEBOT_GIT_HASH"),
+        env!("TELEBOT_BUILD_DATE"),
+    )
+}
+
+/// Strips the `@botname` suffix Telegram appends to commands in group chats,
+/// so `/guess@doomybot` matches the same arm as `/guess`
+fn strip_bot_mention(cmd: &str) -> &str {
+    match cmd.split_once('@') {
+        Some((command, _)) if command.starts_with('/') => command,
+        _ => cmd,
+    }
+}
+
+/// What happened to each word merged into the word sets
+#[derive(Default)]
+pub struct MergeReport<'a> {
+    /// Words that were missing from `GAME_WORDS`
+    pub added_to_game: BTreeSet<&'a str>,
+    /// Words that were missing from `DICT_WORDS`
+    pub added_to_dict: BTreeSet<&'a str>,
+    /// Words that were already in both sets
+    pub already_present: BTreeSet<&'a str>,
+    /// Words on the blocklist
+    pub blocked: BTreeSet<&'a str>,
+    /// Words that aren't `length` letters long
+    pub wrong_length: BTreeSet<&'a str>,
+    /// Length the words had to have to be added
+    pub length: usize,
+    /// Size of `GAME_WORDS` once the words were added
+    pub game_size: usize,
+    /// Size of `DICT_WORDS` once the words were added
+    pub dict_size: usize,
+}
+
+impl<'a> MergeReport<'a> {
+    /// Words that were missing from at least one of the sets
+    pub fn added(&self) -> BTreeSet<&'a str> {
+        self.added_to_game
+            .union(&self.added_to_dict)
+            .copied()
+            .collect()
+    }
+}
+
+impl MergeReport<'_> {
+    /// Reply telling what was added and what wasn't, in `locale`
+    pub fn message(&self, locale: &str) -> String {
+        let mut message = words_message(locale, MsgKey::WordsAdded, self.added());
+        // Words that were already in one of the sets only made it into the other
+        if self.added_to_game != self.added_to_dict {
+            let answers = format!("{:?}", self.added_to_game);
+            let words = format!("{:?}", self.added_to_dict);
+            message += "\n";
+            message += &i18n::msg(
+                locale,
+                MsgKey::NewAnswers,
+                &[("answers", &answers), ("words", &words)],
+            );
+        }
+        if !self.already_present.is_empty() {
+            message += "\n";
+            message += &words_message(
+                locale,
+                MsgKey::AlreadyPresent,
+                self.already_present.iter().copied(),
+            );
+        }
+        for word in &self.blocked {
+            message += "\n";
+            message += &i18n::msg(locale, MsgKey::WordBlocked, &[("word", word)]);
+        }
+        if !self.wrong_length.is_empty() {
+            let length = self.length.to_string();
+            let words = format!("{:?}", self.wrong_length);
+            message += "\n";
+            message += &i18n::msg(
+                locale,
+                MsgKey::WrongLengthWords,
+                &[("length", &length), ("words", &words)],
+            );
+        }
+        let answers = with_thousands_separators(self.game_size);
+        let words = with_thousands_separators(self.dict_size);
+        message += "\n";
+        message += &i18n::msg(
+            locale,
+            MsgKey::WordCounts,
+            &[("answers", &answers), ("words", &words)],
+        );
+        message
+    }
+}
+
+/// Message `key` about `words`, filling in their `{count}` and the `{words}` themselves
+fn words_message<'a>(
+    locale: &str,
+    key: MsgKey,
+    words: impl IntoIterator<Item = &'a str>,
+) -> String {
+    let words: Vec<&str> = words.into_iter().collect();
+    let count = words.len().to_string();
+    let words = format!("{words:?}");
+    i18n::msg(locale, key, &[("count", &count), ("words", &words)])
+}
+
+/// `12483` as `12,483`
+fn with_thousands_separators(n: usize) -> String {
+    let digits = n.to_string();
+    let mut formatted = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// Cuts `text` down to Telegram's message length limit, marking that it was cut
+fn fit_message(mut text: String) -> String {
+    if text.chars().count() <= MAX_MESSAGE_CHARS {
+        return text;
+    }
+    let end = text
+        .char_indices()
+        .nth(MAX_MESSAGE_CHARS - 1)
+        .map_or(text.len(), |(i, _)| i);
+    text.truncate(end);
+    text.push('…');
+    text
+}
+
+/// Adds the `length` letter words to both the game and dictionary sets, reporting which ones
+/// were new to each
+fn merge_words<'a>(words: &[&'a str], length: usize) -> MergeReport<'a> {
+    let mut report = MergeReport {
+        length,
+        ..Default::default()
+    };
+    let mut incoming = BTreeSet::new();
+    for word in words {
+        if word.trim().is_empty() {
+            continue;
+        } else if is_blocked_word(word) {
+            report.blocked.insert(*word);
+        } else if letter_count(word) != length {
+            report.wrong_length.insert(*word);
+        } else {
+            incoming.insert(*word);
+        }
+    }
+
+    // Every word lands in both sets, so a word that was only in one of them is fixed up too
+    let dictionaries = [
+        (
+            &GAME_WORDS,
+            &mut report.added_to_game,
+            &mut report.game_size,
+        ),
+        (
+            &DICT_WORDS,
+            &mut report.added_to_dict,
+            &mut report.dict_size,
+        ),
+    ];
+    for (dict, added, size) in dictionaries {
+        let dict = dict.get().expect("dictionary not initialized");
+        let mut dict = dict.write().expect("could not lock dictionary");
+        *size = insert_words(&mut dict, &incoming, added);
+    }
+    report.already_present = incoming.difference(&report.added()).copied().collect();
+
+    report
+}
+
+/// Inserts `words` into `dict`, recording the ones it didn't have in `added`. Returns the size of
+/// `dict` afterwards, read under the same lock so it counts exactly these words.
+fn insert_words<'a>(
+    dict: &mut WordSet,
+    words: &BTreeSet<&'a str>,
+    added: &mut BTreeSet<&'a str>,
+) -> usize {
+    for word in words {
+        if dict.insert(word) {
+            added.insert(*word);
+        }
+    }
+    dict.len()
+}
+
+#[derive(Clone)]
+pub struct StartState;
+
+#[teloxide(subtransition)]
+async fn start_state(
+    state: StartState,
+    cx: TransitionIn<AutoSend<Bot>>,
+    ans: String,
+) -> TransitionOut<Dialogue, BotError> {
+    let input: Vec<String> = ans.split_whitespace().map(String::from).collect();
+    let command = input
+        .first()
+        .map(|cmd| strip_bot_mention(cmd))
+        .unwrap_or_default();
+
+    match command {
+        "/wordle" | "/daily" => {
+            // Daily games are the same for everyone, so presets and lengths don't apply to them
+            let daily = (command == "/daily").then(|| Utc::now().naive_utc().date());
+            let mut guess_pool = GuessPool::Dictionary;
+            let mut board = BoardStyle::Emoji;
+            let mut keyboard_seed = None;
+            let mut animate = false;
+            let mut coach = false;
+            let mut ranked = false;
+            let mut lengths = word_length()..=word_length();
+            let mut max_guesses = DEFAULT_MAX_GUESSES;
+            let mut hints_allowed = true;
+            let mut hard_mode = false;
+            // The player's default modes come first, so modes passed to /wordle win over them
+            let defaults = cx
+                .update
+                .from()
+                .map(|user| user_config::get(user.id).default_modes)
+                .unwrap_or_default();
+            // Anything that isn't a mode is taken as the first guess, as in `/wordle crane`
+            let mut first_guess = None;
+            for arg in defaults.iter().chain(input.iter().skip(1)) {
+                if let Some(preset) = Preset::parse(arg) {
+                    max_guesses = preset.max_guesses;
+                    hints_allowed = preset.hints;
+                    hard_mode = preset.hard;
+                    continue;
+                }
+                if let Ok(length) = arg.parse::<usize>() {
+                    lengths = length..=length;
+                    continue;
+                }
+                if let Some(count) = arg.strip_prefix("guesses=") {
+                    match count.parse() {
+                        Ok(count @ 1..=MAX_GUESSES_LIMIT) => max_guesses = count,
+                        _ => {
+                            let limit = MAX_GUESSES_LIMIT.to_string();
+                            cx.answer(tr(&cx, MsgKey::InvalidMaxGuesses, &[("limit", &limit)]))
+                                .await?;
+                            return next(state);
+                        }
+                    }
+                    continue;
+                }
+                match GameMode::parse(arg) {
+                    Some(GameMode::Strict) => guess_pool = GuessPool::Game,
+                    Some(GameMode::Mono) => board = BoardStyle::Mono,
+                    Some(GameMode::Letters) => board = BoardStyle::Letters,
+                    Some(GameMode::Chaos) => keyboard_seed = Some(rand::random()),
+                    Some(GameMode::Animate) => animate = true,
+                    Some(GameMode::Mixed) => lengths = MIXED_LENGTHS.clone(),
+                    Some(GameMode::Coach) => coach = true,
+                    Some(GameMode::Ranked) => ranked = true,
+                    None => first_guess = first_guess.or(Some(arg.clone())),
+                }
+            }
+            let started = match (daily, guess_pool) {
+                (Some(_), _) => MsgKey::DailyStarted,
+                (None, GuessPool::Dictionary) => MsgKey::GameStarted,
+                (None, GuessPool::Game) => MsgKey::StrictGameStarted,
+            };
+            let single_length = lengths.start() == lengths.end();
+            if daily.is_none()
+                && single_length
+                && !has_answers_of_length(cx.update.chat.id, *lengths.start())
+            {
+                let length = lengths.start().to_string();
+                cx.answer(tr(&cx, MsgKey::NoAnswersOfLength, &[("length", &length)]))
+                    .await?;
+                return next(state);
+            }
+            // Drawn first, so the length announced is the one of the answer even in mixed games
+            let answer = match daily {
+                Some(date) => {
+                    max_guesses = DEFAULT_MAX_GUESSES;
+                    get_daily_word(date).await
+                }
+                None => next_answer(cx.update.chat.id, &lengths),
+            };
+            let Some(answer) = answer else {
+                cx.answer(tr(&cx, MsgKey::NoAnswers, &[])).await?;
+                return next(state);
+            };
+            let daily = daily.map(daily_number);
+            let word_length = letter_count(&answer);
+            let length = word_length.to_string();
+            let number = daily.unwrap_or_default().to_string();
+            cx.answer(tr(
+                &cx,
+                started,
+                &[("length", &length), ("number", &number)],
+            ))
+            .await?;
+            let config = chat_config::get(cx.update.chat.id);
+            let mut answers = vec![answer];
+            answers.extend(word_alternates(&answers[0]).iter().cloned());
+            let game = GuessState {
+                answers,
+                word_length,
+                guesses: Default::default(),
+                last_input: input,
+                last_input_by: cx.update.from().map(|user| user.id),
+                guessers: Vec::new(),
+                guess_pool,
+                hints: Vec::new(),
+                board,
+                keyboard_seed,
+                animate,
+                coach,
+                max_guesses,
+                hints_allowed,
+                hard_mode,
+                daily,
+                ranked,
+                require_dictionary: config.require_dictionary,
+                private_answer: config.private_answer,
+                participants: BTreeSet::new(),
+                absent: BTreeSet::new(),
+                clue_used: false,
+            };
+            match first_guess {
+                Some(word) => game.react(cx, format!("/guess {word}")).await,
+                None => next(game),
+            }
+        }
+        "/tutorial" => {
+            cx.answer(tr(&cx, MsgKey::TutorialIntro, &[])).await?;
+            next(TutorialState::default())
+        }
+        "/pool" => {
+            set_pool(&cx, input.get(1).map(String::as_str)).await?;
+            next(state)
+        }
+        "/share" => {
+            let share = LAST_SHARES
+                .lock()
+                .expect("failed to lock LAST_SHARES")
+                .get(&cx.update.chat.id)
+                .cloned();
+            let share = share.unwrap_or_else(|| tr(&cx, MsgKey::NothingToShare, &[]));
+            cx.answer(share).await?;
+            next(state)
+        }
+        "/version" => {
+            cx.answer(version_info()).await?;
+            next(state)
+        }
+        #[allow(clippy::no_effect)]
+        "/420" => {
+            "heh";
+            next(state)
+        }
+        _ => next(state),
+    }
+}
+
+/// Shows the chat's answer category, or picks the one its next games draw answers from
+async fn set_pool(
+    cx: &TransitionIn<AutoSend<Bot>>,
+    category: Option<&str>,
+) -> Result<(), BotError> {
+    let chat_id = cx.update.chat.id;
+    let categories = word_categories();
+    let options = || {
+        let mut options: Vec<&str> = categories.iter().copied().collect();
+        options.push("all");
+        options.join(", ")
+    };
+
+    match category.map(str::to_lowercase) {
+        None => {
+            let pool = chat_config::get(chat_id)
+                .pool
+                .unwrap_or_else(|| tr(cx, MsgKey::PoolAll, &[]));
+            let args = [("pool", pool.as_str()), ("options", &options())];
+            cx.answer(tr(cx, MsgKey::PoolShown, &args)).await?;
+        }
+        Some(category) if category == "all" => {
+            chat_config::update(chat_id, |config| config.pool = None);
+            let pool = tr(cx, MsgKey::PoolAll, &[]);
+            cx.answer(tr(cx, MsgKey::PoolSet, &[("pool", &pool)]))
+                .await?;
+        }
+        Some(category) if categories.contains(category.as_str()) => {
+            let reply = tr(cx, MsgKey::PoolSet, &[("pool", &category)]);
+            chat_config::update(chat_id, |config| config.pool = Some(category));
+            cx.answer(reply).await?;
+        }
+        Some(category) => {
+            let args = [("category", category.as_str()), ("options", &options())];
+            cx.answer(tr(cx, MsgKey::UnknownPool, &args)).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Options a game can be started with, as `/wordle <MODE>..`
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    Strict,
+    Mono,
+    Letters,
+    Chaos,
+    Animate,
+    Mixed,
+    Coach,
+    Ranked,
+}
+
+impl GameMode {
+    /// Every mode, in the order they're listed by /modes
+    pub const ALL: [GameMode; 8] = [
+        GameMode::Strict,
+        GameMode::Mono,
+        GameMode::Letters,
+        GameMode::Chaos,
+        GameMode::Animate,
+        GameMode::Mixed,
+        GameMode::Coach,
+        GameMode::Ranked,
+    ];
+
+    /// Name of the mode, as passed to /wordle
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Strict => "strict",
+            GameMode::Mono => "mono",
+            GameMode::Letters => "letters",
+            GameMode::Chaos => "chaos",
+            GameMode::Animate => "animate",
+            GameMode::Mixed => "mixed",
+            GameMode::Coach => "coach",
+            GameMode::Ranked => "ranked",
+        }
+    }
+
+    /// What the mode does, as listed by /modes
+    pub fn description(self) -> MsgKey {
+        match self {
+            GameMode::Strict => MsgKey::ModeStrict,
+            GameMode::Mono => MsgKey::ModeMono,
+            GameMode::Letters => MsgKey::ModeLetters,
+            GameMode::Chaos => MsgKey::ModeChaos,
+            GameMode::Animate => MsgKey::ModeAnimate,
+            GameMode::Mixed => MsgKey::ModeMixed,
+            GameMode::Coach => MsgKey::ModeCoach,
+            GameMode::Ranked => MsgKey::ModeRanked,
+        }
+    }
+
+    pub fn parse(arg: &str) -> Option<GameMode> {
+        GameMode::ALL
+            .into_iter()
+            .find(|mode| mode.name().eq_ignore_ascii_case(arg))
+    }
+}
+
+/// Named difficulties a game can be started with, as `/wordle <PRESET>`
+pub struct Preset {
+    pub name: &'static str,
+    pub max_guesses: usize,
+    /// Whether /hint and /clue can be used
+    pub hints: bool,
+    /// Whether every guess has to fit the clues of the ones before it
+    pub hard: bool,
+}
+
+/// Every preset, in the order they're listed by /modes
+pub const PRESETS: [Preset; 3] = [
+    Preset {
+        name: "easy",
+        max_guesses: 8,
+        hints: true,
+        hard: false,
+    },
+    Preset {
+        name: "normal",
+        max_guesses: DEFAULT_MAX_GUESSES,
+        hints: true,
+        hard: false,
+    },
+    Preset {
+        name: "hard-mode",
+        max_guesses: 5,
+        hints: false,
+        hard: true,
+    },
+];
+
+impl Preset {
+    pub fn parse(arg: &str) -> Option<&'static Preset> {
+        PRESETS
+            .iter()
+            .find(|preset| preset.name.eq_ignore_ascii_case(arg))
+    }
+
+    pub fn description(&self, locale: &str) -> String {
+        let key = if self.hints {
+            MsgKey::PresetWithHints
+        } else {
+            MsgKey::PresetWithoutHints
+        };
+        let mut description = i18n::msg(locale, key, &[("guesses", &self.max_guesses.to_string())]);
+        if self.hard {
+            description += ", ";
+            description += &i18n::msg(locale, MsgKey::PresetFitsClues, &[]);
+        }
+        description
+    }
+}
+
+/// Lists every game mode and preset for /modes
+fn modes_list(locale: &str) -> String {
+    let mut list = i18n::msg(locale, MsgKey::ModesList, &[]);
+    for mode in GameMode::ALL {
+        let description = i18n::msg(locale, mode.description(), &[]);
+        list += &format!("\n{} - {description}", mode.name());
+    }
+    list += "\n\n";
+    list += &i18n::msg(locale, MsgKey::PresetsList, &[]);
+    for preset in &PRESETS {
+        list += &format!("\n{} - {}", preset.name, preset.description(locale));
+    }
+    list += "\n\n";
+    let limit = MAX_GUESSES_LIMIT.to_string();
+    list += &i18n::msg(locale, MsgKey::GuessesOption, &[("limit", &limit)]);
+    list
+}
+
+#[derive(Clone)]
+pub struct GuessState {
+    /// Words that win the game. The first is the one drawn, the others are the alternatives from
+    /// its `also` column.
+    pub answers: Vec<String>,
+    /// Letters in the answer, picked with `/wordle <LENGTH>` or by the mode at game start
+    pub word_length: usize,
+    // Emoji representation as well as word guessed
+    pub guesses: Vec<(String, String)>,
+    /// Name of whoever made each of `guesses`, shown next to it in group chats. Names are empty in
+    /// private chats, where it's always the same player.
+    pub guessers: Vec<String>,
+    pub last_input: Vec<String>,
+    /// Telegram user id of whoever sent `last_input`, so one player's /addword doesn't pick up
+    /// another player's guess in a group
+    pub last_input_by: Option<i64>,
+    pub guess_pool: GuessPool,
+    /// Positions of the answer revealed by /hint
+    pub hints: Vec<usize>,
+    /// How the board is shown after each guess
+    pub board: BoardStyle,
+    /// Seed of the shuffled /keyboard layout in chaos games, `None` keeps the usual layout
+    pub keyboard_seed: Option<u64>,
+    /// Whether each guess is revealed a tile at a time, see `reveal_guess`
+    pub animate: bool,
+    /// Whether each guess is followed by how likely a win still is, see `coach_message`
+    pub coach: bool,
+    /// Guesses the game is lost after, including the ones spent on hints and clues
+    pub max_guesses: usize,
+    /// Whether /hint and /clue can be used, off in the hard-mode preset
+    pub hints_allowed: bool,
+    /// Whether each guess has to fit the clues of the ones before it, as in the hard-mode preset
+    pub hard_mode: bool,
+    /// Puzzle number of daily games, `None` for the others
+    pub daily: Option<i64>,
+    /// Whether the game was started in ranked mode, which has no helpers like daily games
+    pub ranked: bool,
+    /// Whether guesses have to be dictionary words, from the chat's settings when the game started
+    pub require_dictionary: bool,
+    /// Whether a lost game's answer is sent to `participants` instead of the chat, from the chat's
+    /// settings when the game started
+    pub private_answer: bool,
+    /// Telegram user ids of everyone who made a guess
+    pub participants: BTreeSet<i64>,
+    /// Letters guessed so far that aren't anywhere in the answer
+    pub absent: BTreeSet<String>,
+    /// Whether /clue was used, which costs a guess like a hint
+    pub clue_used: bool,
+}
+
+impl GuessState {
+    /// Why /hint and /clue can't be used in this game, if they can't. Everyone plays the same
+    /// daily word and ranked games are played to compare, so helpers would make them unfair.
+    /// They're only allowed in practice games.
+    pub fn helpers_disabled(&self) -> Option<MsgKey> {
+        if self.daily.is_some() {
+            Some(MsgKey::HelpersDisabledDaily)
+        } else if self.ranked {
+            Some(MsgKey::HelpersDisabledRanked)
+        } else {
+            None
+        }
+    }
+
+    /// The answer that was drawn, which hints and clues are about
+    pub fn answer(&self) -> &str {
+        &self.answers[0]
+    }
+
+    /// The answer `attempt` is closest to, which it's colored against. Greens count for more than
+    /// yellows, and ties go to the drawn answer.
+    pub fn closest_answer(&self, attempt: &str) -> &str {
+        let closeness = |answer: &&String| {
+            let placement = score_guess(attempt, answer);
+            let correct = placement
+                .iter()
+                .filter(|p| **p == Placement::Correct)
+                .count();
+            let incorrect = placement
+                .iter()
+                .filter(|p| **p == Placement::Incorrect)
+                .count();
+            (correct, incorrect)
+        };
+        let mut closest = &self.answers[0];
+        for answer in &self.answers[1..] {
+            if closeness(&answer) > closeness(&closest) {
+                closest = answer;
+            }
+        }
+        closest
+    }
+
+    /// Rows of the keyboard shown under the board and by /keyboard, shuffled in chaos games
+    pub fn keyboard_layout(&self, locale: &str) -> Vec<String> {
+        let layout = i18n::keyboard_layout(locale);
+        match self.keyboard_seed {
+            Some(seed) => keyboard::shuffled_layout(layout, seed),
+            None => layout.iter().map(|row| row.to_string()).collect(),
+        }
+    }
+
+    /// Every answer, as shown when the game ends
+    pub fn answers_text(&self) -> String {
+        self.answers.join(" / ")
+    }
+
+    /// Guesses used up so far, including the ones spent on hints and clues
+    pub fn tries(&self) -> usize {
+        self.guesses.len() + self.hints_used()
+    }
+
+    /// How many times the player got help from /hint or /clue
+    pub fn hints_used(&self) -> usize {
+        self.hints.len() + usize::from(self.clue_used)
+    }
+
+    /// Plays `attempt`, a guess that passed the alphabet, length and dictionary checks, made by
+    /// `user_id` as `guesser`. Returns the game after the guess and how it goes on from there.
+    pub fn apply_guess(
+        &self,
+        attempt: &str,
+        guesser: String,
+        user_id: Option<i64>,
+    ) -> (GuessState, GuessOutcome) {
+        let answer = self.closest_answer(attempt);
+        let placement = score_guess(attempt, answer);
+        let mut played = self.clone();
+        played
+            .guesses
+            .push((to_emoji(&placement), attempt.to_string()));
+        played.guessers.push(guesser);
+        played.participants.extend(user_id);
+
+        let outcome = if placement.iter().all(|p| *p == Placement::Correct) {
+            GuessOutcome::Won
+        } else if played.tries() >= self.max_guesses {
+            // Hints cost a guess each, so they can end the game early too
+            GuessOutcome::Lost
+        } else {
+            let newly_absent = absent_letters(attempt, answer)
+                .into_iter()
+                .filter(|letter| played.absent.insert(letter.to_string()))
+                .map(String::from)
+                .collect();
+            GuessOutcome::Continue { newly_absent }
+        };
+        (played, outcome)
+    }
+}
+
+/// How a game goes on after a guess, see `GuessState::apply_guess`
+#[derive(Debug, PartialEq, Eq)]
+pub enum GuessOutcome {
+    /// The guess was an answer
+    Won,
+    /// The guess wasn't an answer and used up the last guess
+    Lost,
+    /// There are guesses left, the game goes on
+    Continue {
+        /// Letters of the guess that are known not to be in the answer since this guess
+        newly_absent: Vec<String>,
+    },
+}
+
+/// Ways of showing the guesses made so far
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BoardStyle {
+    /// Only the colors of each guess
+    Emoji,
+    /// Each guess next to its colors in a code block, so it lines up on every client
+    Mono,
+    /// The letters of each guess under its colors
+    Letters,
+}
+
+/// Which word set a guess has to come from to be accepted
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GuessPool {
+    /// Any word in `DICT_WORDS`
+    Dictionary,
+    /// Only the curated answers in `GAME_WORDS`, for a tighter vocabulary
+    Game,
+}
+
+#[teloxide(subtransition)]
+async fn guess_state(
+    state: GuessState,
+    cx: TransitionIn<AutoSend<Bot>>,
+    ans: String,
+) -> TransitionOut<Dialogue, BotError> {
+    let input: Vec<String> = ans.split_whitespace().map(String::from).collect();
+    let input_str: Vec<&str> = input.iter().map(String::as_str).collect();
+
+    let mut new_state = state.clone();
+    new_state.last_input = input.clone();
+    new_state.last_input_by = cx.update.from().map(|user| user.id);
+
+    let command = input
+        .first()
+        .map(|cmd| strip_bot_mention(cmd))
+        .unwrap_or_default();
+
+    match command {
+        // Everyone in a group shares the chat's game, so starting another would reset it for all
+        "/wordle" | "/daily" | "/tutorial" => {
+            cx.answer(tr(&cx, MsgKey::GameRunning, &[])).await?;
+            next(state)
+        }
+        "/addword" => {
+            let wants_to_add_previous_guess = input.len() == 1
+                && state.last_input.len() == 2
+                && state.last_input_by == cx.update.from().map(|user| user.id);
+
+            if wants_to_add_previous_guess {
+                let previous_guess = normalize_guess(&state.last_input[1]);
+                let words = [previous_guess.as_str()];
+                edit_dictionary(DictionaryAction::Add(&words, state.word_length), cx).await;
+            } else {
+                let words = &input_str[1..];
+                edit_dictionary(DictionaryAction::Add(words, state.word_length), cx).await;
+            }
+
+            next(new_state)
+        }
+        "/version" => {
+            cx.answer(version_info()).await?;
+            next(new_state)
+        }
+        "/hint" => {
+            let tries = state.tries();
+            // Positions already solved by a guess or an earlier hint aren't worth revealing
+            let unknown: Vec<usize> = (0..state.word_length)
+                .filter(|i| !state.hints.contains(i))
+                .filter(|i| {
+                    !state
+                        .guesses
+                        .iter()
+                        .any(|(emoji, _)| emoji.chars().nth(*i) == Some('🟩'))
+                })
+                .collect();
+            let position = unknown.into_iter().choose(&mut rand::thread_rng());
+
+            if let Some(disabled) = state.helpers_disabled() {
+                cx.answer(tr(&cx, disabled, &[])).await?;
+            } else if !state.hints_allowed {
+                cx.answer(tr(&cx, MsgKey::HintsDisabled, &[])).await?;
+            } else if state.hints.len() >= *MAX_HINTS {
+                cx.answer(tr(&cx, MsgKey::NoHintsLeft, &[])).await?;
+            } else if tries + 1 >= state.max_guesses {
+                cx.answer(tr(&cx, MsgKey::HintUsesLastGuess, &[])).await?;
+            } else if let Some(position) = position {
+                let letter = state
+                    .answer()
+                    .graphemes(true)
+                    .nth(position)
+                    .unwrap_or_default();
+                new_state.hints.push(position);
+                let text = tr(
+                    &cx,
+                    MsgKey::Hint,
+                    &[
+                        ("position", &(position + 1).to_string()),
+                        ("letter", &letter.to_uppercase()),
+                        ("tries", &(tries + 1).to_string()),
+                        ("max", &state.max_guesses.to_string()),
+                    ],
+                );
+                cx.answer(text).await?;
+            } else {
+                cx.answer(tr(&cx, MsgKey::AllLettersKnown, &[])).await?;
+            }
+            next(new_state)
+        }
+        "/clue" => {
+            let tries = state.tries();
+            if let Some(disabled) = state.helpers_disabled() {
+                cx.answer(tr(&cx, disabled, &[])).await?;
+            } else if !state.hints_allowed {
+                cx.answer(tr(&cx, MsgKey::HintsDisabled, &[])).await?;
+            } else if state.clue_used {
+                let clue = word_clue(state.answer()).unwrap_or_default();
+                cx.answer(tr(&cx, MsgKey::Clue, &[("clue", clue)])).await?;
+            } else if let Some(clue) = word_clue(state.answer()) {
+                if tries + 1 >= state.max_guesses {
+                    cx.answer(tr(&cx, MsgKey::ClueUsesLastGuess, &[])).await?;
+                } else {
+                    new_state.clue_used = true;
+                    let args = [
+                        ("clue", clue),
+                        ("tries", &(tries + 1).to_string()),
+                        ("max", &state.max_guesses.to_string()),
+                    ];
+                    cx.answer(tr(&cx, MsgKey::ClueUsed, &args)).await?;
+                }
+            } else {
+                cx.answer(tr(&cx, MsgKey::NoClue, &[])).await?;
+            }
+            next(new_state)
+        }
+        // Leaves the state alone, so `/addword` still refers to the last guess afterwards
+        "/guesses" | "/left" => {
+            let left = state.max_guesses.saturating_sub(state.tries()).to_string();
+            cx.answer(tr(&cx, MsgKey::GuessesLeft, &[("left", &left)]))
+                .await?;
+            next(state)
+        }
+        "/length" => {
+            let length = state.word_length.to_string();
+            cx.answer(tr(&cx, MsgKey::AnswerLength, &[("length", &length)]))
+                .await?;
+            next(state)
+        }
+        "/keyboard" => {
+            let locale = play_locale(&chat_config::get(cx.update.chat.id).locale);
+            let layout = state.keyboard_layout(locale);
+            let layout: Vec<&str> = layout.iter().map(String::as_str).collect();
+            let keyboard = keyboard::render_keyboard(&state.guesses, &layout);
+            cx.answer(markdown::code_block(&keyboard))
+                .parse_mode(ParseMode::MarkdownV2)
+                .await?;
+            next(new_state)
+        }
+        // Sharing a game before it's over would give away how it's going to the other players
+        "/share" => {
+            cx.answer(tr(&cx, MsgKey::ShareAfterGame, &[])).await?;
+            next(new_state)
+        }
+        "/image" => {
+            if state.guesses.is_empty() {
+                cx.answer(tr(&cx, MsgKey::NoGuessesYet, &[])).await?;
+            } else {
+                let png = board_image::render_board_png(&state.guesses);
+                cx.answer_photo(InputFile::memory("board.png", png)).await?;
+            }
+            next(new_state)
+        }
+        "/exit" | "/end" | "/stop" => {
+            if let Some(user) = cx.update.from() {
+                update_session_streak(user.id, false);
+            }
+            let word = state.answers_text();
+            cx.answer(tr(&cx, MsgKey::GameEnded, &[("answer", &word)]))
+                .await?;
+            next(StartState)
+        }
+        "/removeword" => {
+            if input.len() < 2 {
+                cx.answer(tr(&cx, MsgKey::RemoveWordUsage, &[])).await?;
+            } else {
+                edit_dictionary(DictionaryAction::Remove(&input_str[1..]), cx).await;
+            }
+
+            next(new_state)
+        }
+        "/guess" if input.len() == 2 => {
+            let attempt = normalize_guess(input_str[1]);
+            let attempt = attempt.as_str();
+            // Alternative answers are taken even when they aren't dictionary words
+            let is_answer = state.answers.iter().any(|answer| answer == attempt);
+
+            // Checked before the length, so emoji and symbols aren't mistaken for a short or
+            // misspelled word. A guess without any letter is trimmed down to nothing.
+            let locale = play_locale(&chat_config::get(cx.update.chat.id).locale);
+            if attempt.is_empty() || !in_alphabet(attempt, locale) {
+                cx.answer(tr(&cx, MsgKey::LettersOnly, &[])).await.ok();
+                return next(new_state);
+            }
+
+            // return early if length of attempt is wrong amount of characters
+            let length = letter_count(attempt);
+            if length != state.word_length {
+                let args = [
+                    ("guess", attempt),
+                    ("length", &length.to_string()),
+                    ("expected", &state.word_length.to_string()),
+                ];
+                cx.answer(tr(&cx, MsgKey::WrongLength, &args)).await.ok();
+                return next(new_state);
+            }
+
+            if !is_answer
+                && state.require_dictionary
+                && !is_dictionary_word(attempt, Some(cx.update.chat.id))
+            {
+                cx.answer(tr(&cx, MsgKey::NotInDictionary, &[("word", attempt)]))
+                    .await
+                    .ok();
+                return next(new_state);
+            }
+
+            if !is_answer
+                && state.guess_pool == GuessPool::Game
+                && !is_game_word(attempt, Some(cx.update.chat.id))
+            {
+                cx.answer(tr(&cx, MsgKey::NotAnAnswer, &[("word", attempt)]))
+                    .await
+                    .ok();
+                return next(new_state);
+            }
+
+            // Hard mode only takes guesses that could still be the answer. None do once the
+            // clues contradict each other, which can happen with alternative answers.
+            if state.hard_mode && !fits_clues(&state.guesses, attempt) {
+                let key = if any_guess_fits(&state, &state.guesses, cx.update.chat.id) {
+                    MsgKey::MustFitClues
+                } else {
+                    MsgKey::NoValidWords
+                };
+                cx.answer(tr(&cx, key, &[("word", attempt)])).await.ok();
+                return next(new_state);
+            }
+
+            let guesser = match cx.update.from() {
+                Some(user) if !cx.update.chat.is_private() => user.first_name.clone(),
+                _ => String::new(),
+            };
+            let user = cx.update.from();
+            let (played, outcome) =
+                new_state.apply_guess(attempt, guesser, user.map(|user| user.id));
+            let tries = played.tries();
+            match outcome {
+                GuessOutcome::Won => {
+                    stats::record_game(stats::GameRecord {
+                        user,
+                        chat_id: cx.update.chat.id,
+                        answer: state.answer(),
+                        won: true,
+                        hints: state.hints_used(),
+                        tries,
+                    });
+                    let tries_text = tries.to_string();
+                    let max_text = state.max_guesses.to_string();
+                    let args = [
+                        ("tries", tries_text.as_str()),
+                        ("max", &max_text),
+                        ("answer", state.closest_answer(attempt)),
+                    ];
+                    let mut text = end_message(&cx, &WIN_MESSAGE, MsgKey::Win, &args);
+                    if tries == 1 {
+                        text += " ";
+                        text += &tr(&cx, MsgKey::Lucky, &[]);
+                    }
+                    text += &hints_note(&cx, &state);
+                    // Games won with help don't count towards streaks, but don't end them either
+                    let streak = user
+                        .filter(|_| state.hints_used() == 0)
+                        .map_or(0, |user| update_session_streak(user.id, true));
+                    if streak > 1 {
+                        // One more flame every 5 wins, to keep long streaks exciting
+                        let flames = "🔥".repeat((1 + streak as usize / 5).min(3));
+                        let count = streak.to_string();
+                        text += "\n";
+                        text += &tr(
+                            &cx,
+                            MsgKey::Streak,
+                            &[("flames", &flames), ("count", &count)],
+                        );
+                    }
+                    reveal_guess(&cx, &text, &played.guesses, &played.guessers, &state).await;
+                    let share =
+                        store_share(cx.update.chat.id, &state, &played.guesses, tries, true);
+                    cx.answer(share).await?;
+                    next(StartState)
+                }
+                GuessOutcome::Continue { newly_absent } => {
+                    let max = state.max_guesses;
+                    let mut text = format!("{tries}/{max} {}", progress_bar(tries, max));
+                    if !newly_absent.is_empty() {
+                        let letters = newly_absent.join(", ");
+                        text += "\n";
+                        text += &tr(&cx, MsgKey::Absent, &[("letters", &letters)]);
+                    }
+                    if state.coach {
+                        text += "\n";
+                        text += &coach_message(&cx, &played.guesses, max - tries);
+                    }
+                    if state.hard_mode
+                        && !any_guess_fits(&state, &played.guesses, cx.update.chat.id)
+                    {
+                        text += "\n";
+                        text += &tr(&cx, MsgKey::NoValidWords, &[]);
+                    }
+                    reveal_guess(&cx, &text, &played.guesses, &played.guessers, &state).await;
+                    next(played)
+                }
+                GuessOutcome::Lost => {
+                    // Hints keep a won game out of the streak, but a loss always ends it
+                    if let Some(user) = user {
+                        update_session_streak(user.id, false);
+                    }
+                    stats::record_game(stats::GameRecord {
+                        user,
+                        chat_id: cx.update.chat.id,
+                        answer: state.answer(),
+                        won: false,
+                        hints: state.hints_used(),
+                        tries,
+                    });
+                    let answers = state.answers_text();
+                    // Onlookers only see a masked answer once every player got it privately
+                    let private = state.private_answer
+                        && !cx.update.chat.is_private()
+                        && send_answer_privately(&cx, &played.participants, &answers).await;
+                    let shown = if private {
+                        "■".repeat(letter_count(state.answer()))
+                    } else {
+                        answers
+                    };
+                    let tries_text = tries.to_string();
+                    let max_text = state.max_guesses.to_string();
+                    let args = [
+                        ("tries", tries_text.as_str()),
+                        ("max", &max_text),
+                        ("answer", &shown),
+                    ];
+                    let mut text = end_message(&cx, &LOSS_MESSAGE, MsgKey::Loss, &args);
+                    text += &hints_note(&cx, &state);
+                    if private {
+                        text += "\n";
+                        text += &tr(&cx, MsgKey::AnswerSentPrivately, &[]);
+                    }
+                    reveal_guess(&cx, &text, &played.guesses, &played.guessers, &state).await;
+                    store_share(cx.update.chat.id, &state, &played.guesses, tries, false);
+                    next(StartState)
+                }
+            }
+        }
+        "/guess" => {
+            cx.answer(tr(&cx, MsgKey::InvalidGuess, &[])).await?;
+            next(state)
+        }
+        _ if command.starts_with('/') => {
+            cx.answer(tr(&cx, MsgKey::UnknownCommand, &[])).await?;
+            next(state)
+        }
+        _ => {
+            // Not meant for us?
+            next(state)
+        }
+    }
+}
+
+/// Answer of the practice game played by /tutorial
+const TUTORIAL_ANSWER: &str = "crane";
+
+/// What's explained after each guess of the tutorial, the last one is repeated until it's solved
+const TUTORIAL_STEPS: [MsgKey; 3] = [
+    MsgKey::TutorialColors,
+    MsgKey::TutorialClose,
+    MsgKey::TutorialFinish,
+];
+
+/// A scripted practice game with a fixed answer, explaining the board as it goes
+#[derive(Clone, Default)]
+pub struct TutorialState {
+    // Emoji representation as well as word guessed
+    pub guesses: Vec<(String, String)>,
+}
+
+impl TutorialState {
+    /// Which of `TUTORIAL_STEPS` the player is at
+    pub fn step(&self) -> usize {
+        self.guesses.len().min(TUTORIAL_STEPS.len())
+    }
+
+    /// What the player should do next
+    pub fn prompt(&self) -> MsgKey {
+        match self.step() {
+            0 => MsgKey::TutorialStart,
+            step => TUTORIAL_STEPS[step - 1],
+        }
+    }
+}
+
+#[teloxide(subtransition)]
+async fn tutorial_state(
+    state: TutorialState,
+    cx: TransitionIn<AutoSend<Bot>>,
+    ans: String,
+) -> TransitionOut<Dialogue, BotError> {
+    let input: Vec<&str> = ans.split_whitespace().collect();
+    let command = input
+        .first()
+        .map(|cmd| strip_bot_mention(cmd))
+        .unwrap_or_default();
+
+    match command {
+        "/exit" | "/end" | "/stop" => {
+            cx.answer(tr(&cx, MsgKey::TutorialEnded, &[])).await?;
+            next(StartState)
+        }
+        "/guess" if input.len() == 2 => {
+            let attempt = normalize_guess(input[1]);
+            if letter_count(&attempt) != letter_count(TUTORIAL_ANSWER)
+                || !attempt.chars().all(|letter| letter.is_ascii_lowercase())
+            {
+                let length = letter_count(TUTORIAL_ANSWER).to_string();
+                let prompt = tr(&cx, state.prompt(), &[]);
+                let args = [("length", length.as_str()), ("prompt", &prompt)];
+                cx.answer(tr(&cx, MsgKey::TutorialWrongLength, &args))
+                    .await?;
+                return next(state);
+            }
+
+            // Not recorded in the stats, it's only practice
+            let placement = score_guess(&attempt, TUTORIAL_ANSWER);
+            let mut guesses = state.guesses.clone();
+            guesses.push((to_emoji(&placement), attempt));
+            let new_state = TutorialState { guesses };
+            // The tutorial answer is English, whatever the chat's language
+            let layout = i18n::keyboard_layout(i18n::DEFAULT_LOCALE);
+            if placement.iter().all(|p| *p == Placement::Correct) {
+                answer_with_board(
+                    &cx,
+                    &tr(&cx, MsgKey::TutorialDone, &[]),
+                    &new_state.guesses,
+                    &[],
+                    BoardStyle::Letters,
+                    layout,
+                )
+                .await?;
+                next(StartState)
+            } else {
+                answer_with_board(
+                    &cx,
+                    &tr(&cx, new_state.prompt(), &[]),
+                    &new_state.guesses,
+                    &[],
+                    BoardStyle::Letters,
+                    layout,
+                )
+                .await?;
+                next(new_state)
+            }
+        }
+        _ if command.starts_with('/') => {
+            let prompt = tr(&cx, state.prompt(), &[]);
+            cx.answer(tr(&cx, MsgKey::TutorialReminder, &[("prompt", &prompt)]))
+                .await?;
+            next(state)
+        }
+        _ => next(state),
+    }
+}
+
+/// Fills the `{name}` placeholders of a template in a single pass, so values
+/// are never expanded again. Unknown placeholders are kept as they are, so a typo shows up in the
+/// message instead of breaking it.
+fn render_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let value = rest.find('}').and_then(|end| {
+            let (_, value) = values.iter().find(|(name, _)| *name == &rest[1..end])?;
+            Some((value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                rendered.push_str(value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Line noting how many hints and clues the game used, empty if it didn't use any
+fn hints_note(cx: &TransitionIn<AutoSend<Bot>>, state: &GuessState) -> String {
+    match state.hints_used() {
+        0 => String::new(),
+        count => format!(
+            "\n{}",
+            tr(cx, MsgKey::HintsUsed, &[("count", &count.to_string())])
+        ),
+    }
+}
+
+/// Counts a win towards the user's session streak, or ends it, returning the streak
+fn update_session_streak(user_id: i64, won: bool) -> u32 {
+    let mut streaks = SESSION_STREAKS
+        .lock()
+        .expect("failed to lock SESSION_STREAKS");
+    if !won {
+        streaks.remove(&user_id);
+        return 0;
+    }
+
+    let streak = match streaks.get(&user_id) {
+        Some((streak, last_win)) if last_win.elapsed() < SESSION_STREAK_WINDOW => streak + 1,
+        _ => 1,
+    };
+    streaks.insert(user_id, (streak, Instant::now()));
+    streak
+}
+
+/// Whether the player is on track to win with `guesses_left` guesses, going by how many game
+/// words still fit their guesses
+fn coach_message(
+    cx: &TransitionIn<AutoSend<Bot>>,
+    guesses: &[(String, String)],
+    guesses_left: usize,
+) -> String {
+    let candidates = {
+        let game_words = GAME_WORDS.get().expect("GAME_WORDS is not initialized");
+        let game_words = game_words.read().expect("failed to lock GAME_WORDS");
+        let length = guesses.last().map_or(0, |(_, word)| letter_count(word));
+        let words: Vec<&str> = game_words
+            .iter()
+            .filter(|word| letter_count(word) == length)
+            .collect();
+        solver::candidates(guesses, &words).len()
+    };
+
+    let key = coach_verdict(candidates, guesses_left);
+    tr(cx, key, &[("count", &candidates.to_string())])
+}
+
+/// `MsgKey::OnTrack` when a win within `guesses_left` guesses is at least as likely as not with
+/// `candidates` words left, see `solver::win_chance`
+fn coach_verdict(candidates: usize, guesses_left: usize) -> MsgKey {
+    if solver::win_chance(candidates, guesses_left) >= 0.5 {
+        MsgKey::OnTrack
+    } else {
+        MsgKey::ToughSpot
+    }
+}
+
+/// Whether `word` fits the clues of every `(colors, word)` guess, as hard mode requires of the
+/// next guess
+fn fits_clues(guesses: &[(String, String)], word: &str) -> bool {
+    !solver::candidates(guesses, &[word]).is_empty()
+}
+
+/// Whether any word `state` takes as a guess fits the clues of `guesses`. A hard-mode game can't
+/// go on once none does. Games that take words outside the dictionary always can.
+fn any_guess_fits(state: &GuessState, guesses: &[(String, String)], chat_id: i64) -> bool {
+    if !state.require_dictionary && state.guess_pool == GuessPool::Dictionary {
+        return true;
+    }
+    let dict_words = DICT_WORDS.get().expect("DICT_WORDS is not initialized");
+    let dict_words = dict_words.read().expect("failed to lock DICT_WORDS");
+    let game_words = GAME_WORDS.get().expect("GAME_WORDS is not initialized");
+    let game_words = game_words.read().expect("failed to lock GAME_WORDS");
+    let chat_words = chat_words::words(chat_id);
+    let mut words: Vec<&str> = game_words.iter().collect();
+    if state.guess_pool == GuessPool::Dictionary {
+        words.extend(dict_words.iter());
+    }
+    words.extend(chat_words.iter().map(String::as_str));
+    words.extend(state.answers.iter().map(String::as_str));
+    words.retain(|word| letter_count(word) == state.word_length);
+    !solver::candidates(guesses, &words).is_empty()
+}
+
+/// Sends the answer to each of the game's players in a private chat, returning whether all of
+/// them got it. Players who never started a chat with the bot can't be messaged, in which case
+/// the answer should be shown in the group after all.
+async fn send_answer_privately(
+    cx: &TransitionIn<AutoSend<Bot>>,
+    participants: &BTreeSet<i64>,
+    answers: &str,
+) -> bool {
+    let chat = cx.update.chat.title().unwrap_or("your group");
+    let text = tr(
+        cx,
+        MsgKey::PrivateAnswer,
+        &[("chat", chat), ("answer", answers)],
+    );
+    let mut all_sent = !participants.is_empty();
+    for user_id in participants {
+        if let Err(err) = cx.requester.send_message(*user_id, &text).await {
+            log::warn!("Could not send the answer to {user_id} privately: {err}");
+            all_sent = false;
+        }
+    }
+    all_sent
+}
+
+/// Guesses `used` out of `max` as a bar, like `▰▰▰▱▱▱`
+fn progress_bar(used: usize, max: usize) -> String {
+    let used = used.min(max);
+    "▰".repeat(used) + &"▱".repeat(max - used)
+}
+
+/// Renders `key` in the chat's language
+fn tr(cx: &TransitionIn<AutoSend<Bot>>, key: MsgKey, args: &[(&str, &str)]) -> String {
+    i18n::msg(&chat_locale(cx), key, args)
+}
+
+/// Language the chat replies are in
+fn chat_locale(cx: &TransitionIn<AutoSend<Bot>>) -> String {
+    chat_config::get(cx.update.chat.id).locale
+}
+
+/// Renders the win or loss message, preferring the operator's template over the localized one
+fn end_message(
+    cx: &TransitionIn<AutoSend<Bot>>,
+    template: &Option<String>,
+    key: MsgKey,
+    args: &[(&str, &str)],
+) -> String {
+    match template {
+        Some(template) => render_template(template, args),
+        None => tr(cx, key, args),
+    }
+}
+
+/// Renders the guess history, one row per guess followed by the name in `guessers` of whoever
+/// made it, if any. The mono layout puts each guessed word next to its row, which only lines up
+/// in a monospace code block.
+fn render_board(guesses: &[(String, String)], guessers: &[String], style: BoardStyle) -> String {
+    if style == BoardStyle::Letters {
+        return render_board_with_letters(guesses, guessers);
+    }
+    guesses
+        .iter()
+        .enumerate()
+        .map(|(i, (emoji, word))| {
+            let row = if style == BoardStyle::Mono {
+                format!("{emoji} {word}")
+            } else {
+                emoji.clone()
+            };
+            with_guesser(row, guessers, i)
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// `row` followed by the name of whoever made the `i`th guess, when it's known
+fn with_guesser(row: String, guessers: &[String], i: usize) -> String {
+    match guessers.get(i) {
+        Some(name) if !name.is_empty() => format!("{row} {name}"),
+        _ => row,
+    }
+}
+
+/// Renders the result of a finished game as only its colored rows under a "Wordle N X/6" header,
+/// so it can be pasted elsewhere without giving the answer or any guessed word away. `tries`
+/// counts hints and clues as well as guesses, as the game did.
+fn render_share(
+    guesses: &[(String, String)],
+    tries: usize,
+    won: bool,
+    puzzle: Option<i64>,
+    max_guesses: usize,
+) -> String {
+    let tries = if won {
+        tries.to_string()
+    } else {
+        "X".to_string()
+    };
+    let header = match puzzle {
+        Some(number) => format!("Wordle {number} {tries}/{max_guesses}"),
+        None => format!("Wordle {tries}/{max_guesses}"),
+    };
+    let rows: Vec<&str> = guesses.iter().map(|(emoji, _)| emoji.as_str()).collect();
+    format!("{header}\n\n{}", rows.join("\n"))
+}
+
+/// Remembers the result of the game `state` ended with `guesses` after `tries`, for the chat's
+/// next `/share`
+fn store_share(
+    chat_id: i64,
+    state: &GuessState,
+    guesses: &[(String, String)],
+    tries: usize,
+    won: bool,
+) -> String {
+    let share = render_share(guesses, tries, won, state.daily, state.max_guesses);
+    LAST_SHARES
+        .lock()
+        .expect("failed to lock LAST_SHARES")
+        .insert(chat_id, share.clone());
+    share
+}
+
+/// Renders each guess as its colors with its letters right under them. Latin letters are shown
+/// in their fullwidth form, which is as wide as the emoji on most clients so each letter stays
+/// under its tile. Other letters can't be widened and are padded with a space instead.
+fn render_board_with_letters(guesses: &[(String, String)], guessers: &[String]) -> String {
+    guesses
+        .iter()
+        .enumerate()
+        .map(|(i, (emoji, word))| {
+            let letters: String = word
+                .graphemes(true)
+                .map(|letter| match letter.as_bytes() {
+                    [c @ b'a'..=b'z'] => char::from_u32(0xFF21 + u32::from(c - b'a'))
+                        .map(String::from)
+                        .unwrap_or_default(),
+                    _ => format!("{} ", letter.to_uppercase()),
+                })
+                .collect();
+            format!("{}\n{letters}", with_guesser(emoji.clone(), guessers, i))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// `text` followed by the board and the keyboard under it, as MarkdownV2. The keyboard and mono
+/// boards are wrapped in code blocks, so they line up on every client.
+fn board_message(
+    text: &str,
+    guesses: &[(String, String)],
+    guessers: &[String],
+    style: BoardStyle,
+    layout: &[&str],
+) -> String {
+    let board = render_board(guesses, guessers, style);
+    let board = if style == BoardStyle::Mono {
+        markdown::code_block(&board)
+    } else {
+        markdown::escape(&board)
+    };
+    let keyboard = keyboard::render_keyboard(guesses, layout);
+    format!(
+        "{}\n{board}\n{}",
+        markdown::escape(text),
+        markdown::code_block(&keyboard)
+    )
+}
+
+/// Replies with `text` followed by the board and the keyboard in `layout`
+async fn answer_with_board(
+    cx: &TransitionIn<AutoSend<Bot>>,
+    text: &str,
+    guesses: &[(String, String)],
+    guessers: &[String],
+    style: BoardStyle,
+    layout: &[&str],
+) -> Result<Message, RequestError> {
+    let message = board_message(text, guesses, guessers, style, layout);
+    // Boards carry the game's results, so they're worth waiting out a rate limit for
+    send_with_retry(|| cx.answer(&message).parse_mode(ParseMode::MarkdownV2)).await
+}
+
+/// Replies with the board of `state`'s game like `answer_with_board`, revealing the latest guess
+/// a tile at a time in animated games.
+///
+/// Every tile costs an extra edit of the message, so a chat only has one animation running at a
+/// time. Guesses made meanwhile, as in busy groups, are shown all at once instead.
+async fn reveal_guess(
+    cx: &TransitionIn<AutoSend<Bot>>,
+    text: &str,
+    guesses: &[(String, String)],
+    guessers: &[String],
+    state: &GuessState,
+) {
+    let chat_id = cx.update.chat.id;
+    let layout = state.keyboard_layout(play_locale(&chat_config::get(chat_id).locale));
+    let animate = state.animate
+        && ANIMATING_CHATS
+            .lock()
+            .expect("failed to lock ANIMATING_CHATS")
+            .insert(chat_id);
+    if !animate {
+        let layout: Vec<&str> = layout.iter().map(String::as_str).collect();
+        answer_with_board(cx, text, guesses, guessers, state.board, &layout)
+            .await
+            .ok();
+        return;
+    }
+
+    // Run apart from the dialogue, so the chat's next messages aren't held up by the delays
+    let bot = cx.requester.clone();
+    let text = text.to_string();
+    let guesses = guesses.to_vec();
+    let guessers = guessers.to_vec();
+    let style = state.board;
+    tokio::spawn(async move {
+        let layout: Vec<&str> = layout.iter().map(String::as_str).collect();
+        animate_guess(&bot, chat_id, &text, &guesses, &guessers, style, &layout).await;
+        ANIMATING_CHATS
+            .lock()
+            .expect("failed to lock ANIMATING_CHATS")
+            .remove(&chat_id);
+    });
+}
+
+/// Sends the board with the latest guess hidden, then edits in one of its tiles at a time. If an
+/// edit fails, the whole board is sent as a new message instead.
+async fn animate_guess(
+    bot: &AutoSend<Bot>,
+    chat_id: i64,
+    text: &str,
+    guesses: &[(String, String)],
+    guessers: &[String],
+    style: BoardStyle,
+    layout: &[&str],
+) {
+    let Some((tiles, word)) = guesses.last() else {
+        return;
+    };
+    let tile_count = tiles.chars().count();
+    // The keyboard only learns about the tiles shown so far, so it doesn't give the guess away
+    let frame = |shown: usize| {
+        let row: String = tiles
+            .chars()
+            .take(shown)
+            .chain(std::iter::repeat('⬜'))
+            .take(tile_count)
+            .collect();
+        let mut frame = guesses.to_vec();
+        frame.pop();
+        frame.push((row, word.clone()));
+        board_message(text, &frame, guessers, style, layout)
+    };
+
+    let first = frame(0);
+    let sent = send_with_retry(|| {
+        bot.send_message(chat_id, &first)
+            .parse_mode(ParseMode::MarkdownV2)
+    })
+    .await;
+    let Ok(message) = sent else {
+        return;
+    };
+
+    for shown in 1..=tile_count {
+        tokio::time::sleep(ANIMATION_FRAME_DELAY).await;
+        let edited = bot
+            .edit_message_text(chat_id, message.id, frame(shown))
+            .parse_mode(ParseMode::MarkdownV2)
+            .await;
+        if let Err(err) = edited {
+            log::warn!("Stopped animating a guess in chat {chat_id}: {err}");
+            let full = board_message(text, guesses, guessers, style, layout);
+            send_with_retry(|| {
+                bot.send_message(chat_id, &full)
+                    .parse_mode(ParseMode::MarkdownV2)
+            })
+            .await
+            .ok();
+            return;
+        }
+    }
+}
+
+/// Sends the request built by `send`, waiting out Telegram's flood control and retrying network
+/// errors with a backoff, up to `MAX_SEND_ATTEMPTS` times
+async fn send_with_retry<T, F, Fut>(mut send: F) -> Result<T, RequestError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, RequestError>>,
+{
+    let mut attempt = 1;
+    loop {
+        let err = match send().await {
+            Ok(sent) => return Ok(sent),
+            Err(err) => err,
+        };
+        match retry_delay(&err, attempt) {
+            Some(delay) if attempt < MAX_SEND_ATTEMPTS => {
+                log::warn!("Retrying send in {delay:?} after: {err}");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            _ => {
+                log::error!("Giving up sending after {attempt} attempts: {err}");
+                return Err(err);
+            }
+        }
+    }
+}
+
+/// How long to wait before retrying a request that failed with `err` on its `attempt`th try, or
+/// `None` if retrying wouldn't help
+fn retry_delay(err: &RequestError, attempt: u32) -> Option<Duration> {
+    match err {
+        RequestError::RetryAfter(secs) => Some(Duration::from_secs((*secs).max(1) as u64)),
+        RequestError::NetworkError(_) => Some(SEND_RETRY_BACKOFF * 2u32.pow(attempt - 1)),
+        _ => None,
+    }
+}
+
+/// Undoes what mobile keyboards do to a guess: surrounding punctuation like `crane.` or
+/// `"crane"` is trimmed, and the word is lowercased. Anything inside the word is kept, so
+/// guesses like `cr4ne` are still rejected.
+fn normalize_guess(guess: &str) -> String {
+    // Trimmed by grapheme so combining accents on the last letter aren't mistaken for punctuation
+    let is_letter = |grapheme: &&str| grapheme.chars().next().is_some_and(char::is_alphabetic);
+    let letters: Vec<&str> = guess.graphemes(true).collect();
+    let start = letters.iter().position(is_letter);
+    let end = letters.iter().rposition(is_letter);
+    match (start, end) {
+        (Some(start), Some(end)) => letters[start..=end].concat().to_lowercase(),
+        _ => String::new(),
+    }
+}
+
+/// Letters of `attempt` that aren't anywhere in `answer`. A letter guessed twice can score gray
+/// for its extra copy while being in the answer, so this goes by the answer rather than the colors.
+fn absent_letters<'a>(attempt: &'a str, answer: &str) -> BTreeSet<&'a str> {
+    let answer: BTreeSet<&str> = answer.graphemes(true).collect();
+    attempt
+        .graphemes(true)
+        .filter(|letter| !answer.contains(letter))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use dictionary_store::MemoryStore;
+    use fake_telegram::FakeTelegram;
+    use Placement::{Correct, Incorrect, Missing};
+
+    /// Answers the tests start with, each test edits words of its own so they can run together.
+    /// "sparkles" is longer than the lengths tests load.
+    const TEST_GAME_WORDS: &str =
+        "crane\nslate\tcategory=rocks\ntrace\nhello\nthere\tclue=not here\nsparkles\tclue=shines";
+    const TEST_DICT_WORDS: &str = "crane\nslate\ntrace\nhello\nthere\nbumpy\ncocoa\neerie";
+    /// The users the tests load as bot admins, one per test so the command cooldown of one
+    /// doesn't get in the way of another
+    const TEST_ADMIN_IDS: [i64; 2] = [154_001, 185_001];
+
+    /// The store the shared state was loaded from, set up on first use
+    fn test_store() -> &'static MemoryStore {
+        static STORE: OnceCell<MemoryStore> = OnceCell::new();
+        STORE.get_or_init(|| {
+            let assets = env::temp_dir().join(format!("telebot-tests-{}", std::process::id()));
+            fs::create_dir_all(&assets).expect("could not create test assets directory");
+            env::set_var("MAX_WORD_LEN", "7");
+            let admin_ids: Vec<String> = TEST_ADMIN_IDS.iter().map(i64::to_string).collect();
+            env::set_var("ADMIN_IDS", admin_ids.join(","));
+            let store = MemoryStore::new(HashMap::from([
+                ("words.txt".to_string(), TEST_GAME_WORDS.to_string()),
+                ("dictionary.txt".to_string(), TEST_DICT_WORDS.to_string()),
+                ("blocklist.txt".to_string(), "slurs\n".to_string()),
+            ]));
+            init(assets, Box::new(store.clone()));
+            store
+        })
+    }
+
+    #[test]
+    fn loads_word_sets_from_store() {
+        test_store();
+        assert!(is_dictionary_word("bumpy", None));
+        assert!(GAME_WORDS.get().unwrap().read().unwrap().contains("crane"));
+        assert_eq!(word_clue("there"), Some("not here"));
+        assert_eq!(word_length(), 5);
+    }
+
+    #[test]
+    fn saves_word_sets_to_store() {
+        let store = test_store();
+        let report = merge_words(&["vivid"], 5);
+        assert!(report.added().contains("vivid"));
+        mark_dictionary_dirty();
+        save_changes();
+
+        let saved = store.load("words_custom.txt").unwrap().unwrap();
+        assert!(saved.starts_with(WORD_FILE_HEADER));
+        assert!(saved.lines().any(|line| line == "vivid"));
+        // Other tests draw answers too, which adds their usage columns
+        assert!(saved
+            .lines()
+            .any(|line| line.starts_with("there\t") && line.contains("\tclue=not here")));
+        // Filtered out by MAX_WORD_LEN, but kept in the file
+        assert!(!GAME_WORDS
+            .get()
+            .unwrap()
+            .read()
+            .unwrap()
+            .contains("sparkles"));
+        assert!(saved.lines().any(|line| line == "sparkles\tclue=shines"));
+        let saved = store.load("dictionary_custom.txt").unwrap().unwrap();
+        assert!(saved.lines().any(|line| line == "bumpy"));
+    }
+
+    #[test]
+    fn concurrent_dictionary_edits_dont_deadlock() {
+        const THREADS: u8 = 8;
+        test_store();
+
+        let (done, finished) = std::sync::mpsc::channel();
+        for thread in 0..THREADS {
+            let done = done.clone();
+            thread::spawn(move || {
+                // Words of their own, so the threads only race on the locks
+                let words: Vec<String> = (b'a'..=b'z')
+                    .map(|letter| format!("qz{}{}x", (b'a' + thread) as char, letter as char))
+                    .collect();
+                for (i, word) in words.iter().enumerate() {
+                    merge_words(&[word], 5);
+                    assert!(is_dictionary_word(word, None));
+                    get_random_word(i64::from(thread), &(5..=5)).expect("no answer drawn");
+                    // Every other word is removed again, to check the sets end up consistent
+                    if i % 2 == 0 {
+                        assert!(remove_words(&[word]).contains(word.as_str()));
+                    }
+                }
+                done.send(words).unwrap();
+            });
+        }
+        drop(done);
+
+        for _ in 0..THREADS {
+            let words = finished
+                .recv_timeout(Duration::from_secs(30))
+                .expect("dictionary edits deadlocked or panicked");
+            for (i, word) in words.iter().enumerate() {
+                let kept = i % 2 == 1;
+                assert_eq!(is_dictionary_word(word, None), kept, "{word}");
+                assert_eq!(is_game_word(word, None), kept, "{word}");
+            }
+        }
+    }
+
+    #[test]
+    fn forced_answer_has_to_be_guessable() {
+        test_store();
+        assert!(check_forced_answer("crane", "en").is_ok());
+        assert_eq!(
+            check_forced_answer("slurs", "en"),
+            Err(MsgKey::AnswerBlocked)
+        );
+        assert_eq!(
+            check_forced_answer("c4ne5", "en"),
+            Err(MsgKey::AnswerNotSpellable)
+        );
+        assert!(check_forced_answer("crane", "ru").is_err());
+        assert!(check_forced_answer("ñandú", "es").is_ok());
+    }
+
+    #[test]
+    fn admin_replies_are_translated() {
+        test_store();
+        let breakdown = score_breakdown("es", Some("crane"), Some("trace"));
+        assert_eq!(
+            breakdown.lines().collect::<Vec<_>>(),
+            [
+                "⬛🟩🟩🟨🟩",
+                "1. t - no está en la respuesta, o no quedan copias",
+                "2. r - en su sitio",
+                "3. a - en su sitio",
+                "4. c - en otro sitio",
+                "5. e - en su sitio",
+            ]
+        );
+        assert_eq!(
+            score_breakdown("ru", None, None),
+            "Использование: /score <ОТВЕТ> <ПОПЫТКА>"
+        );
+        assert!(dictionary_stats("es").starts_with("Palabras del juego: "));
+    }
+
+    #[test]
+    fn locales_follow_word_alphabet() {
+        assert_eq!(locales_spelling(["crane", "slate"]), ["en", "es"]);
+        assert_eq!(locales_spelling(["crane", "ñandú"]), ["es"]);
+        assert_eq!(locales_spelling(["слово", "ёжик"]), ["ru"]);
+        assert!(locales_spelling(["crane", "слово"]).is_empty());
+    }
+
+    #[test]
+    fn cyrillic_guesses_use_russian_alphabet() {
+        assert!(in_alphabet("слово", "ru"));
+        assert!(!in_alphabet("слово", "en"));
+        assert!(!in_alphabet("crane", "ru"));
+        assert_eq!(score_guess("слово", "слово"), [Correct; 5]);
+        assert_eq!(
+            score_guess("полет", "слово"),
+            [Missing, Incorrect, Incorrect, Missing, Missing]
+        );
+    }
+
+    #[test]
+    fn play_locale_falls_back_to_word_lists() {
+        test_store();
+        // The test word lists are latin
+        assert_eq!(play_locale("es"), "es");
+        assert_eq!(play_locale("ru"), "en");
+    }
+
+    #[test]
+    fn daily_pick_is_stable() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let words = ["crane", "slate", "trace", "hello", "there"];
+        let pick = pick_daily_word(words, date);
+        // Pinned, so a change to the hash or the draw shows up here
+        assert_eq!(pick, Some("crane"));
+        // Words that don't beat the pick don't change it
+        let more = words.into_iter().chain(["zzzzz"]);
+        assert_eq!(pick_daily_word(more, date), pick);
+        assert_eq!(pick_daily_word([], date), None);
+    }
+
+    #[tokio::test]
+    async fn daily_word_is_kept_for_the_day() {
+        test_store();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        let word = get_daily_word(date).await.expect("no daily word");
+        let added: Vec<String> = (b'a'..=b'z')
+            .map(|letter| format!("dz{}yk", letter as char))
+            .collect();
+        let added: Vec<&str> = added.iter().map(String::as_str).collect();
+        merge_words(&added, 5);
+        assert_eq!(get_daily_word(date).await, Some(word));
+    }
+
+    #[test]
+    fn maintenance_holds_off_changes() {
+        assert!(!held_by_maintenance("/chatwords"));
+        MAINTENANCE.store(true, Ordering::Relaxed);
+        let held =
+            ["/chatwords", "/config", "/wordle", "/guess", "/addword"].map(held_by_maintenance);
+        let allowed = ["/help", "/stats", "hello"].map(held_by_maintenance);
+        MAINTENANCE.store(false, Ordering::Relaxed);
+        assert_eq!(held, [true; 5]);
+        assert_eq!(allowed, [false; 3]);
+    }
+
+    #[test]
+    fn migrates_word_lists_in_store() {
+        let store = MemoryStore::new(HashMap::from([
+            ("words_custom.txt".to_string(), "crane\nslate\n".to_string()),
+            (
+                "dictionary_custom.txt".to_string(),
+                format!("{WORD_FILE_HEADER}\ncrane\n"),
+            ),
+        ]));
+        let dir = env::temp_dir().join(format!("telebot-migrate-{}", std::process::id()));
+        migrate_assets(&store, &dir).unwrap();
+
+        let words = store.load("words_custom.txt").unwrap().unwrap();
+        assert_eq!(words, format!("{WORD_FILE_HEADER}\ncrane\nslate\n"));
+        let dict = store.load("dictionary_custom.txt").unwrap().unwrap();
+        assert_eq!(dict, format!("{WORD_FILE_HEADER}\ncrane\n"));
+    }
+
+    #[test]
+    fn share_counts_hints_and_hides_words() {
+        let guesses = [
+            (
+                to_emoji(&score_guess("slate", "crane")),
+                "slate".to_string(),
+            ),
+            (
+                to_emoji(&score_guess("crane", "crane")),
+                "crane".to_string(),
+            ),
+        ];
+        // Won on the second guess after a hint
+        let share = render_share(&guesses, 3, true, Some(12), 6);
+        assert_eq!(share, "Wordle 12 3/6\n\n⬛⬛🟩⬛🟩\n🟩🟩🟩🟩🟩");
+        let share = render_share(&guesses, 3, false, None, 6);
+        assert!(share.starts_with("Wordle X/6\n\n"));
+        assert!(!share.contains("slate") && !share.contains("crane"));
+    }
+
+    #[test]
+    fn random_word_draws_chat_words() {
+        test_store();
+        let chat_id = -181;
+        chat_words::add(chat_id, &["garden"]);
+        // The only 6 letter word is the chat's own
+        assert_eq!(
+            get_random_word(chat_id, &(6..=6)).as_deref(),
+            Some("garden")
+        );
+        for _ in 0..20 {
+            let word = get_random_word(chat_id, &(5..=5)).unwrap();
+            assert_eq!(letter_count(&word), 5, "{word}");
+        }
+    }
+
+    #[test]
+    fn bot_mentions_are_stripped_from_commands() {
+        fn command(text: &str) -> &str {
+            strip_bot_mention(text.split_whitespace().next().unwrap())
+        }
+        assert_eq!(command("/guess@somebot cat"), "/guess");
+        assert_eq!(command("/exit@bot"), "/exit");
+        assert_eq!(command("/guess cat"), "/guess");
+        assert_eq!(command("mail@example.com"), "mail@example.com");
+    }
+
+    #[test]
+    fn session_streak_counts_wins_until_a_loss() {
+        let user_id = 145;
+        assert_eq!(update_session_streak(user_id, true), 1);
+        assert_eq!(update_session_streak(user_id, true), 2);
+        assert_eq!(update_session_streak(user_id, true), 3);
+        assert_eq!(update_session_streak(user_id, false), 0);
+        assert_eq!(update_session_streak(user_id, true), 1);
+        assert_eq!(update_session_streak(user_id + 1, true), 1);
+    }
+
+    #[test]
+    fn aliases_expand_to_their_command() {
+        let aliases = HashMap::from([
+            ("/w".to_string(), "/wordle".to_string()),
+            ("/g".to_string(), "/guess".to_string()),
+        ]);
+        let expand = |text| expand_alias(text, '/', &aliases);
+        assert_eq!(expand("/w"), "/wordle");
+        assert_eq!(expand("/g crane"), "/guess crane");
+        assert_eq!(expand("/g@telebot crane"), "/guess crane");
+        assert_eq!(expand("  /w hard"), "/wordle hard");
+        // Only the command is looked up, not its arguments
+        assert_eq!(expand("/guess w"), "/guess w");
+        assert_eq!(expand("/wo"), "/wo");
+        assert_eq!(expand_alias("!g crane", '!', &aliases), "/guess crane");
+    }
+
+    #[test]
+    fn no_aliases_leave_commands_alone() {
+        let aliases = HashMap::new();
+        assert_eq!(expand_alias("/w", '/', &aliases), "/w");
+        assert_eq!(expand_alias("/guess crane", '/', &aliases), "/guess crane");
+        assert_eq!(expand_alias("nice game", '/', &aliases), "nice game");
+    }
+
+    #[test]
+    fn command_cooldown_holds_users_back_until_it_expires() {
+        let cooldown = Duration::from_secs(1);
+        let start = Instant::now();
+        let mut last_command = HashMap::new();
+        assert!(cooldown_passed(&mut last_command, 1, start, cooldown));
+        assert!(!cooldown_passed(
+            &mut last_command,
+            1,
+            start + cooldown / 2,
+            cooldown
+        ));
+        // Other users have cooldowns of their own
+        assert!(cooldown_passed(
+            &mut last_command,
+            2,
+            start + cooldown / 2,
+            cooldown
+        ));
+
+        // Held back commands don't extend the cooldown, and expired users are dropped
+        assert!(cooldown_passed(
+            &mut last_command,
+            1,
+            start + cooldown,
+            cooldown
+        ));
+        assert_eq!(last_command.len(), 2);
+        assert!(cooldown_passed(
+            &mut last_command,
+            3,
+            start + cooldown * 2,
+            cooldown
+        ));
+        assert_eq!(last_command.keys().collect::<Vec<_>>(), [&3]);
+    }
+
+    #[test]
+    fn assets_dir_resolution_order() {
+        let root = env::temp_dir().join(format!("telebot-assets-dirs-{}", std::process::id()));
+        let configured = root.join("configured");
+        let manifest = root.join("manifest");
+        let exe = root.join("exe");
+        fs::create_dir_all(&exe).unwrap();
+        let candidates = [manifest.clone(), exe.clone()];
+
+        // The env var wins, and its directory is created
+        let picked = pick_assets_dir(Some(configured.clone()), &candidates).unwrap();
+        assert_eq!(picked, configured);
+        assert!(configured.is_dir());
+        // Then the first candidate that exists
+        assert_eq!(pick_assets_dir(None, &candidates).unwrap(), exe);
+        fs::create_dir_all(&manifest).unwrap();
+        assert_eq!(pick_assets_dir(None, &candidates).unwrap(), manifest);
+
+        fs::remove_dir_all(&root).unwrap();
+        let err = pick_assets_dir(None, &candidates).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn quick_save_requests_are_saved_at_once() {
+        let requests = Arc::new(SaveRequests::default());
+        let exiting = Arc::new(AtomicBool::new(false));
+        let saves = Arc::new(Mutex::new(0));
+        let worker = {
+            let (requests, exiting, saves) = (requests.clone(), exiting.clone(), saves.clone());
+            thread::spawn(move || loop {
+                requests.wait(
+                    Duration::from_secs(60),
+                    Duration::from_millis(300),
+                    &exiting,
+                );
+                if exiting.load(Ordering::Relaxed) {
+                    break;
+                }
+                *saves.lock().unwrap() += 1;
+            })
+        };
+
+        for _ in 0..5 {
+            requests.request();
+            thread::sleep(Duration::from_millis(10));
+        }
+        thread::sleep(Duration::from_millis(600));
+        assert_eq!(*saves.lock().unwrap(), 1);
+
+        // Exiting wakes the worker up right away
+        exiting.store(true, Ordering::Relaxed);
+        requests.request();
+        worker.join().unwrap();
+        assert_eq!(*saves.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn prefix_only_rewrites_commands() {
+        let aliases = HashMap::from([("/w".to_string(), "/wordle".to_string())]);
+        let rewrite = |text| with_slash_prefix(text, '!', &aliases);
+        assert_eq!(rewrite("!wordle mono").as_deref(), Some("/wordle mono"));
+        assert_eq!(
+            rewrite("!guess@telebot crane").as_deref(),
+            Some("/guess@telebot crane")
+        );
+        assert_eq!(rewrite("!w").as_deref(), Some("/w"));
+        assert_eq!(rewrite("!!!"), None);
+        assert_eq!(rewrite("! wordle"), None);
+        assert_eq!(rewrite("!nice game"), None);
+        assert_eq!(rewrite("wordle"), None);
+        assert_eq!(with_slash_prefix("/wordle", '/', &aliases), None);
+    }
+
+    fn test_game(answers: &[&str], max_guesses: usize) -> GuessState {
+        GuessState {
+            answers: answers.iter().map(|answer| answer.to_string()).collect(),
+            word_length: letter_count(answers[0]),
+            guesses: Vec::new(),
+            guessers: Vec::new(),
+            last_input: Vec::new(),
+            last_input_by: None,
+            guess_pool: GuessPool::Dictionary,
+            hints: Vec::new(),
+            board: BoardStyle::Emoji,
+            keyboard_seed: None,
+            animate: false,
+            coach: false,
+            max_guesses,
+            hints_allowed: true,
+            hard_mode: false,
+            daily: None,
+            ranked: false,
+            require_dictionary: true,
+            private_answer: false,
+            participants: BTreeSet::new(),
+            absent: BTreeSet::new(),
+            clue_used: false,
+        }
+    }
+
+    #[test]
+    fn guess_wins_game() {
+        let game = test_game(&["crane"], 6);
+        let (game, outcome) = game.apply_guess("slate", String::new(), Some(1));
+        assert_eq!(
+            outcome,
+            GuessOutcome::Continue {
+                newly_absent: vec!["l".into(), "s".into(), "t".into()]
+            }
+        );
+        let (game, outcome) = game.apply_guess("crane", String::new(), Some(2));
+        assert_eq!(outcome, GuessOutcome::Won);
+        assert_eq!(game.tries(), 2);
+        assert_eq!(game.participants, BTreeSet::from([1, 2]));
+        assert_eq!(game.guesses[1], ("🟩🟩🟩🟩🟩".into(), "crane".into()));
+    }
+
+    #[test]
+    fn alternative_answer_wins_game() {
+        let game = test_game(&["crane", "crate"], 6);
+        let (_, outcome) = game.apply_guess("crate", String::new(), None);
+        assert_eq!(outcome, GuessOutcome::Won);
+    }
+
+    #[test]
+    fn last_wrong_guess_loses_game() {
+        let game = test_game(&["crane"], 3);
+        let (game, outcome) = game.apply_guess("slate", String::new(), None);
+        assert!(matches!(outcome, GuessOutcome::Continue { .. }));
+        // Letters that were already known absent aren't reported again
+        let (game, outcome) = game.apply_guess("hello", String::new(), None);
+        assert_eq!(
+            outcome,
+            GuessOutcome::Continue {
+                newly_absent: vec!["h".into(), "o".into()]
+            }
+        );
+        let (game, outcome) = game.apply_guess("trace", String::new(), None);
+        assert_eq!(outcome, GuessOutcome::Lost);
+        assert_eq!(game.tries(), 3);
+    }
+
+    #[test]
+    fn three_guess_game_reports_3_of_3() {
+        let mut game = test_game(&["crane"], 3);
+        for guess in ["slate", "hello", "trace"] {
+            let (played, outcome) = game.apply_guess(guess, String::new(), None);
+            assert_eq!(outcome == GuessOutcome::Lost, guess == "trace");
+            game = played;
+        }
+        let (tries, max) = (game.tries().to_string(), game.max_guesses.to_string());
+        let args = [
+            ("tries", tries.as_str()),
+            ("max", &max),
+            ("answer", "crane"),
+        ];
+        let text = i18n::msg("en", MsgKey::Loss, &args);
+        assert!(text.starts_with("You lost. 3/3."), "{text}");
+        assert!(
+            render_share(&game.guesses, game.tries(), false, None, game.max_guesses)
+                .starts_with("Wordle X/3\n")
+        );
+    }
+
+    #[test]
+    fn hints_use_up_guesses() {
+        let mut game = test_game(&["crane"], 3);
+        game.hints = vec![0];
+        game.clue_used = true;
+        let (_, outcome) = game.apply_guess("slate", String::new(), None);
+        assert_eq!(outcome, GuessOutcome::Lost);
+    }
+
+    /// A private chat with a single player, or a group of them, playing through the dialogue
+    /// handlers against a fake Telegram API
+    struct ChatHarness {
+        telegram: FakeTelegram,
+        bot: AutoSend<Bot>,
+        chat_id: i64,
+        /// Whether the chat is a group, rather than a private chat with its player
+        group: bool,
+        /// User id and name of whoever sends the next messages
+        from: (i64, &'static str),
+        dialogue: Dialogue,
+    }
+
+    impl ChatHarness {
+        /// A private chat, where the player's user id is the chat id
+        fn new(chat_id: i64) -> Self {
+            test_store();
+            let telegram = FakeTelegram::start();
+            let bot = telegram.bot();
+            Self {
+                telegram,
+                bot,
+                chat_id,
+                group: false,
+                from: (chat_id, "Player"),
+                dialogue: Dialogue::default(),
+            }
+        }
+
+        /// A group chat, with messages sent by `player` until another one is picked
+        fn group(chat_id: i64, player: (i64, &'static str)) -> Self {
+            Self {
+                group: true,
+                from: player,
+                ..Self::new(chat_id)
+            }
+        }
+
+        /// Sends the next messages as `player`
+        fn play_as(&mut self, player: (i64, &'static str)) {
+            self.from = player;
+        }
+
+        /// Sends `text` as the player and moves on to the dialogue it leads to, returning the
+        /// messages the bot replied with, without their markdown escapes
+        async fn send(&mut self, text: &str) -> Vec<String> {
+            self.react(text).await;
+            self.replies()
+        }
+
+        /// Like `send`, along with the chat each message was sent to
+        async fn send_anywhere(&mut self, text: &str) -> Vec<(i64, String)> {
+            self.react(text).await;
+            self.sent_messages()
+        }
+
+        async fn react(&mut self, text: &str) {
+            let dialogue = std::mem::take(&mut self.dialogue);
+            match dialogue.react(self.update(text), text.to_string()).await {
+                Ok(DialogueStage::Next(dialogue)) => self.dialogue = dialogue,
+                Ok(DialogueStage::Exit) => panic!("dialogue exited on {text:?}"),
+                Err(err) => panic!("{text:?} failed: {err}"),
+            }
+        }
+
+        /// Like `send`, but through `handle_message` like a real update, with the cooldown and
+        /// error handling that come with it
+        async fn handle(&mut self, text: &str) -> Vec<String> {
+            let dialogue = std::mem::take(&mut self.dialogue);
+            match handle_message(self.update(text), dialogue).await {
+                DialogueStage::Next(dialogue) => self.dialogue = dialogue,
+                DialogueStage::Exit => panic!("dialogue exited on {text:?}"),
+            }
+            self.replies()
+        }
+
+        fn update(&self, text: &str) -> UpdateWithCx<AutoSend<Bot>, Message> {
+            let message = serde_json::from_value(serde_json::json!({
+                "message_id": 1,
+                "date": 0,
+                "chat": if self.group {
+                    serde_json::json!({ "id": self.chat_id, "type": "group", "title": "Testers" })
+                } else {
+                    serde_json::json!({ "id": self.chat_id, "type": "private", "first_name": "Player" })
+                },
+                "from": { "id": self.from.0, "is_bot": false, "first_name": self.from.1 },
+                "text": text,
+            }))
+            .expect("invalid test message");
+            UpdateWithCx {
+                requester: self.bot.clone(),
+                update: message,
+            }
+        }
+
+        /// Messages sent since the last call, without their markdown escapes
+        fn replies(&self) -> Vec<String> {
+            self.sent_messages()
+                .into_iter()
+                .map(|(_, text)| text)
+                .collect()
+        }
+
+        /// Messages sent since the last call by the chat they were sent to, without their
+        /// markdown escapes
+        fn sent_messages(&self) -> Vec<(i64, String)> {
+            self.telegram
+                .take_requests()
+                .into_iter()
+                .filter(|request| request.method == "SendMessage")
+                .filter_map(|request| {
+                    let chat_id = request.params["chat_id"].as_i64()?;
+                    let text = request.params["text"].as_str()?;
+                    let text = match request.params["parse_mode"].as_str() {
+                        Some("MarkdownV2") => unescape_markdown(text),
+                        _ => text.to_string(),
+                    };
+                    Some((chat_id, text))
+                })
+                .collect()
+        }
+
+        fn game(&self) -> &GuessState {
+            match &self.dialogue {
+                Dialogue::Guess(game) => game,
+                _ => panic!("no game running"),
+            }
+        }
+
+        fn game_mut(&mut self) -> &mut GuessState {
+            match &mut self.dialogue {
+                Dialogue::Guess(game) => game,
+                _ => panic!("no game running"),
+            }
+        }
+
+        fn force_answer(&self, answer: &str) {
+            FORCED_ANSWERS
+                .lock()
+                .unwrap()
+                .insert(self.chat_id, answer.to_string());
+        }
+    }
+
+    fn unescape_markdown(text: &str) -> String {
+        let mut unescaped = String::with_capacity(text.len());
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            unescaped.extend(if c == '\\' { chars.next() } else { Some(c) });
+        }
+        unescaped
+    }
+
+    #[tokio::test]
+    async fn dialogue_plays_a_won_game() {
+        let mut chat = ChatHarness::new(173_001);
+        chat.force_answer("crane");
+
+        let replies = chat.send("/wordle").await;
+        assert_eq!(
+            replies,
+            [i18n::msg("en", MsgKey::GameStarted, &[("length", "5")])]
+        );
+        assert_eq!(chat.game().answers, ["crane"]);
+
+        let replies = chat.send("/guess slate").await;
+        assert_eq!(replies.len(), 1);
+        assert!(replies[0].contains("⬛⬛🟩⬛🟩"), "{replies:?}");
+        assert!(replies[0].contains("1/6"), "{replies:?}");
+        assert_eq!(chat.game().tries(), 1);
+
+        // Rejected guesses don't use up a try
+        let replies = chat.send("/guess bxyzw").await;
+        assert_eq!(
+            replies,
+            [i18n::msg(
+                "en",
+                MsgKey::NotInDictionary,
+                &[("word", "bxyzw")]
+            )]
+        );
+        assert_eq!(chat.game().tries(), 1);
+
+        let replies = chat.send("/guess crane").await;
+        assert!(replies[0].contains("You won. 2/6"), "{replies:?}");
+        assert!(replies[0].contains("🟩🟩🟩🟩🟩"), "{replies:?}");
+        assert!(replies[1].starts_with("Wordle 2/6\n"), "{replies:?}");
+        assert!(matches!(chat.dialogue, Dialogue::Start(_)));
+        stats::read(|stats| assert_eq!(stats.users[&173_001].wins, 1));
+    }
+
+    #[tokio::test]
+    async fn dialogue_plays_a_lost_game() {
+        let mut chat = ChatHarness::new(173_002);
+        chat.force_answer("crane");
+
+        chat.send("/wordle guesses=2").await;
+        assert_eq!(chat.game().max_guesses, 2);
+        let replies = chat.send("/guess slate").await;
+        assert!(replies[0].contains("1/2"), "{replies:?}");
+
+        let replies = chat.send("/guess hello").await;
+        assert!(
+            replies[0].contains("You lost. 2/2. Cringe.\nAnswer was crane"),
+            "{replies:?}"
+        );
+        assert!(matches!(chat.dialogue, Dialogue::Start(_)));
+        // A new game can be started right away
+        chat.force_answer("trace");
+        chat.send("/wordle").await;
+        assert_eq!(chat.game().answers, ["trace"]);
+        assert_eq!(chat.game().tries(), 0);
+    }
+
+    #[tokio::test]
+    async fn hard_mode_guesses_have_to_fit_the_clues() {
+        let mut chat = ChatHarness::new(167_001);
+        chat.force_answer("crane");
+        chat.send("/wordle hard-mode").await;
+        assert!(chat.game().hard_mode);
+        chat.send("/guess slate").await;
+
+        let must_fit = i18n::msg("en", MsgKey::MustFitClues, &[("word", "hello")]);
+        assert_eq!(chat.send("/guess hello").await, [must_fit.as_str()]);
+        assert_eq!(chat.game().tries(), 1);
+        let replies = chat.send("/guess crane").await;
+        assert!(replies[0].contains("2/5"), "{replies:?}");
+    }
+
+    #[tokio::test]
+    async fn hard_mode_reports_contradictory_clues() {
+        let mut chat = ChatHarness::new(167_002);
+        chat.force_answer("crane");
+        chat.send("/wordle hard-mode").await;
+        // A c first, then no c anywhere, which no word fits
+        chat.game_mut().guesses = vec![
+            ("🟩⬛⬛⬛⬛".to_string(), "crane".to_string()),
+            ("⬛⬛⬛⬛⬛".to_string(), "cocoa".to_string()),
+        ];
+        assert!(!any_guess_fits(chat.game(), &chat.game().guesses, 167_002));
+
+        let no_words = i18n::msg("en", MsgKey::NoValidWords, &[]);
+        assert_eq!(chat.send("/guess trace").await, [no_words.as_str()]);
+        assert_eq!(chat.game().tries(), 2);
+        // A particular word can fit while the whole set doesn't
+        assert!(fits_clues(&chat.game().guesses[..1], "chump"));
+    }
+
+    /// Error lines logged since the test logger was installed
+    static LOGGED_ERRORS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    struct TestLogger;
+
+    impl log::Log for TestLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::Level::Error
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                LOGGED_ERRORS
+                    .lock()
+                    .unwrap()
+                    .push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    /// Starts keeping logged errors in `LOGGED_ERRORS`
+    fn capture_errors() {
+        static INSTALL: std::sync::Once = std::sync::Once::new();
+        INSTALL.call_once(|| {
+            log::set_logger(&TestLogger).expect("a logger is already installed");
+            log::set_max_level(log::LevelFilter::Error);
+        });
+    }
+
+    #[tokio::test]
+    async fn failed_replies_are_logged_and_keep_the_game() {
+        capture_errors();
+        let mut chat = ChatHarness::new(113_001);
+        chat.force_answer("crane");
+        chat.send("/wordle").await;
+        chat.send("/guess slate").await;
+
+        chat.telegram.fail_requests(true);
+        let replies = chat.handle("/help").await;
+        // The help and then the error notice were tried, both failing
+        assert_eq!(replies.len(), 2, "{replies:?}");
+        assert_eq!(replies[1], i18n::msg("en", MsgKey::Error, &[]));
+        assert!(LOGGED_ERRORS.lock().unwrap().iter().any(|line| {
+            line.starts_with("Error handling message in chat 113001: failed to talk to Telegram")
+        }));
+
+        // The game is kept as it was and goes on once Telegram answers again
+        chat.telegram.fail_requests(false);
+        assert_eq!(chat.game().answers, ["crane"]);
+        assert_eq!(chat.game().tries(), 1);
+        let replies = chat.send("/guess crane").await;
+        assert!(replies[0].contains("2/6"), "{replies:?}");
+    }
+
+    #[tokio::test]
+    async fn helpers_are_rejected_in_daily_and_ranked_games() {
+        let daily_only = i18n::msg("en", MsgKey::HelpersDisabledDaily, &[]);
+        let mut chat = ChatHarness::new(182_001);
+        chat.force_answer("there");
+        chat.send("/wordle").await;
+        // Set by hand, as /daily would change the daily word kept for other tests
+        chat.game_mut().daily = Some(1);
+        assert_eq!(chat.send("/hint").await, [daily_only.as_str()]);
+        assert_eq!(chat.send("/clue").await, [daily_only.as_str()]);
+        assert!(chat.game().hints.is_empty() && !chat.game().clue_used);
+
+        let ranked_only = i18n::msg("en", MsgKey::HelpersDisabledRanked, &[]);
+        let mut chat = ChatHarness::new(182_002);
+        chat.force_answer("there");
+        chat.send("/wordle ranked").await;
+        assert_eq!(chat.send("/hint").await, [ranked_only.as_str()]);
+        assert_eq!(chat.send("/clue").await, [ranked_only.as_str()]);
+    }
+
+    #[tokio::test]
+    async fn helpers_are_allowed_in_practice_games() {
+        let mut chat = ChatHarness::new(182_003);
+        chat.force_answer("there");
+        chat.send("/wordle").await;
+
+        let replies = chat.send("/hint").await;
+        assert!(replies[0].starts_with("Position "), "{replies:?}");
+        assert_eq!(chat.game().hints.len(), 1);
+        let replies = chat.send("/clue").await;
+        assert!(replies[0].contains("not here"), "{replies:?}");
+        assert!(chat.game().clue_used);
+    }
+
+    #[test]
+    fn replies_follow_chat_locale() {
+        for locale in i18n::LOCALES {
+            let list = modes_list(locale);
+            assert!(list.contains("strict - ") && !list.contains('{'), "{list}");
+        }
+        assert!(modes_list("es").starts_with("Modos para /wordle"));
+        assert_eq!(
+            words_message("ru", MsgKey::WordsRemoved, ["crane", "slate"]),
+            r#"Удалено слов: 2 ["crane", "slate"]"#
+        );
+        assert_eq!(
+            i18n::msg("es", TutorialState::default().prompt(), &[]),
+            "Empieza con /guess slate"
+        );
+    }
+
+    #[test]
+    fn guesses_lose_surrounding_punctuation() {
+        assert_eq!(normalize_guess("crane."), "crane");
+        assert_eq!(normalize_guess("\"crane\""), "crane");
+        assert_eq!(normalize_guess("crane!"), "crane");
+        assert_eq!(normalize_guess("«Crane»,"), "crane");
+        // Only the ends are trimmed, so these are still rejected as guesses
+        assert_eq!(normalize_guess("cr4ne"), "cr4ne");
+        assert_eq!(normalize_guess("cr.ne!"), "cr.ne");
+        assert!(!in_alphabet(&normalize_guess("cr4ne."), "en"));
+        assert!(in_alphabet(&normalize_guess("crane."), "en"));
+        assert_eq!(normalize_guess("!!!"), "");
+    }
+
+    #[test]
+    fn templates_fill_known_placeholders() {
+        let values = [("tries", "3"), ("answer", "crane")];
+        assert_eq!(
+            render_template("Got {answer} in {tries}!", &values),
+            "Got crane in 3!"
+        );
+        // Values aren't expanded again, even if they look like placeholders
+        assert_eq!(
+            render_template("{answer}", &[("answer", "{tries}"), ("tries", "3")]),
+            "{tries}"
+        );
+    }
+
+    #[test]
+    fn templates_keep_missing_placeholders() {
+        let values = [("tries", "3")];
+        assert_eq!(
+            render_template("{answer} in {tries}", &values),
+            "{answer} in 3"
+        );
+        assert_eq!(render_template("{tries", &values), "{tries");
+        assert_eq!(render_template("{}{{tries}}", &values), "{}{3}");
+        assert_eq!(
+            render_template("no placeholders", &values),
+            "no placeholders"
+        );
+    }
+
+    #[test]
+    fn progress_bar_fills_with_guesses() {
+        assert_eq!(progress_bar(0, 6), "▱▱▱▱▱▱");
+        assert_eq!(progress_bar(2, 6), "▰▰▱▱▱▱");
+        assert_eq!(progress_bar(6, 6), "▰▰▰▰▰▰");
+        assert_eq!(progress_bar(3, 8), "▰▰▰▱▱▱▱▱");
+        // Hints on the last guess can't overflow the bar
+        assert_eq!(progress_bar(4, 3), "▰▰▰");
+    }
+
+    #[test]
+    fn retries_back_off_exponentially() {
+        let network_error = || {
+            let err = reqwest::Client::new().get("http://").build().unwrap_err();
+            RequestError::NetworkError(err)
+        };
+        assert_eq!(retry_delay(&network_error(), 1), Some(SEND_RETRY_BACKOFF));
+        assert_eq!(
+            retry_delay(&network_error(), 2),
+            Some(SEND_RETRY_BACKOFF * 2)
+        );
+        assert_eq!(
+            retry_delay(&network_error(), 3),
+            Some(SEND_RETRY_BACKOFF * 4)
+        );
+    }
+
+    #[test]
+    fn retries_follow_retry_after() {
+        let retry_after = |secs| retry_delay(&RequestError::RetryAfter(secs), 3);
+        assert_eq!(retry_after(7), Some(Duration::from_secs(7)));
+        assert_eq!(retry_after(0), Some(Duration::from_secs(1)));
+        // Retrying these would fail the same way
+        assert_eq!(retry_delay(&RequestError::MigrateToChatId(1), 1), None);
+        let io_error = io::Error::other("file is gone");
+        assert_eq!(retry_delay(&RequestError::Io(io_error), 1), None);
+    }
+
+    #[test]
+    fn emoji_all_correct() {
+        assert_eq!(to_emoji(&[Correct; 5]), "🟩🟩🟩🟩🟩");
+    }
+
+    #[test]
+    fn emoji_all_missing() {
+        assert_eq!(to_emoji(&[Missing; 5]), "⬛⬛⬛⬛⬛");
+    }
+
+    #[test]
+    fn emoji_all_incorrect() {
+        assert_eq!(to_emoji(&[Incorrect; 5]), "🟨🟨🟨🟨🟨");
+    }
+
+    #[test]
+    fn emoji_mixed() {
+        let placement = [Correct, Missing, Incorrect, Missing, Correct];
+        assert_eq!(to_emoji(&placement), "🟩⬛🟨⬛🟩");
+    }
+
+    #[test]
+    fn emoji_empty() {
+        assert_eq!(to_emoji(&[]), "");
+    }
+
+    #[test]
+    fn scored_guess_renders_as_emoji() {
+        assert_eq!(to_emoji(&score_guess("crane", "crane")), "🟩🟩🟩🟩🟩");
+        assert_eq!(to_emoji(&score_guess("bumpy", "crane")), "⬛⬛⬛⬛⬛");
+        assert_eq!(to_emoji(&score_guess("nacre", "crane")), "🟨🟨🟨🟨🟩");
+        assert_eq!(to_emoji(&score_guess("trace", "crane")), "⬛🟩🟩🟨🟩");
+    }
+
+    #[test]
+    fn doubled_letter_scores_once() {
+        // Both of the answer's l's are taken by greens, leaving none for the first l
+        assert_eq!(
+            score_guess("lolly", "hello"),
+            [Missing, Incorrect, Correct, Correct, Missing]
+        );
+        // The answer has a single o, so only the first guessed o is yellow
+        assert_eq!(
+            score_guess("oopsy", "hello"),
+            [Incorrect, Missing, Missing, Missing, Missing]
+        );
+    }
+
+    #[test]
+    fn tripled_letter_against_single_letter() {
+        // The green e takes the answer's only e, so the other two stay gray
+        assert_eq!(
+            score_guess("eerie", "crane"),
+            [Missing, Missing, Incorrect, Missing, Correct]
+        );
+    }
+
+    #[test]
+    fn tripled_letter_against_doubled_letter() {
+        assert_eq!(
+            score_guess("eerie", "there"),
+            [Incorrect, Missing, Incorrect, Missing, Correct]
+        );
+    }
+
+    #[test]
+    fn green_takes_priority_over_later_yellow() {
+        assert_eq!(
+            score_guess("cocoa", "crane"),
+            [Correct, Missing, Missing, Missing, Incorrect]
+        );
+    }
+
+    #[test]
+    fn winning_grid() {
+        let grid: Vec<String> = ["slate", "trace", "crane"]
+            .iter()
+            .map(|guess| to_emoji(&score_guess(guess, "crane")))
+            .collect();
+        assert_eq!(grid.join("\n"), "⬛⬛🟩⬛🟩\n⬛🟩🟩🟨🟩\n🟩🟩🟩🟩🟩");
+    }
+
+    #[tokio::test]
+    async fn guess_pools_check_their_word_set() {
+        let not_in_dictionary = i18n::msg("en", MsgKey::NotInDictionary, &[("word", "zzzzz")]);
+        let mut chat = ChatHarness::new(105_001);
+        chat.force_answer("crane");
+        chat.send("/wordle").await;
+        assert!(chat.game().guess_pool == GuessPool::Dictionary);
+        // Dictionary words that can't be answers are fine in the usual pool
+        let replies = chat.send("/guess bumpy").await;
+        assert!(replies[0].contains("1/6"), "{replies:?}");
+        assert_eq!(
+            chat.send("/guess zzzzz").await,
+            [not_in_dictionary.as_str()]
+        );
+
+        let mut chat = ChatHarness::new(105_002);
+        chat.force_answer("crane");
+        chat.send("/wordle strict").await;
+        assert!(chat.game().guess_pool == GuessPool::Game);
+        let not_an_answer = i18n::msg("en", MsgKey::NotAnAnswer, &[("word", "bumpy")]);
+        assert_eq!(chat.send("/guess bumpy").await, [not_an_answer.as_str()]);
+        assert_eq!(
+            chat.send("/guess zzzzz").await,
+            [not_in_dictionary.as_str()]
+        );
+        assert_eq!(chat.game().tries(), 0);
+        let replies = chat.send("/guess slate").await;
+        assert!(replies[0].contains("1/6"), "{replies:?}");
+    }
+
+    #[tokio::test]
+    async fn unknown_commands_are_answered_but_chatter_is_not() {
+        let unknown = i18n::msg("en", MsgKey::UnknownCommand, &[]);
+        let mut chat = ChatHarness::new(109_001);
+        chat.force_answer("crane");
+        chat.send("/wordle").await;
+        assert_eq!(chat.send("/xyz").await, [unknown.as_str()]);
+        assert_eq!(chat.send("/xyz@somebot").await, [unknown.as_str()]);
+        assert!(chat.send("xyz").await.is_empty());
+        assert!(chat.send("nice one, almost had it").await.is_empty());
+        assert_eq!(chat.game().tries(), 0);
+    }
+
+    #[test]
+    fn save_interval_is_parsed_or_defaulted() {
+        assert_eq!(save_interval(Some("30")), Duration::from_secs(30));
+        assert_eq!(save_interval(Some(" 600\n")), Duration::from_secs(600));
+        assert_eq!(save_interval(None), DEFAULT_SAVE_INTERVAL);
+        // Saving in a busy loop or never isn't an interval
+        assert_eq!(save_interval(Some("0")), DEFAULT_SAVE_INTERVAL);
+        assert_eq!(save_interval(Some("-5")), DEFAULT_SAVE_INTERVAL);
+        assert_eq!(save_interval(Some("2m")), DEFAULT_SAVE_INTERVAL);
+        assert_eq!(save_interval(Some("")), DEFAULT_SAVE_INTERVAL);
+    }
+
+    #[test]
+    fn word_lengths_filter_loaded_words() {
+        let lengths = word_lengths(Some("5"), Some("6"));
+        let loaded = |word: &str| lengths.contains(&letter_count(word));
+        assert!(loaded("crane") && loaded("cranes"));
+        assert!(!loaded("cran") && !loaded("sparkles"));
+        // Accented letters count once
+        assert!(loaded("ñandú"));
+
+        assert_eq!(word_lengths(None, None), 1..=usize::MAX);
+        assert_eq!(word_lengths(Some("0"), Some("five")), 1..=usize::MAX);
+
+        // The tests load words up to 7 letters
+        test_store();
+        assert!(!is_game_word("sparkles", None));
+        let filtered = FILTERED_WORD_LINES.lock().unwrap();
+        assert!(filtered
+            .values()
+            .flatten()
+            .any(|line| line.starts_with("sparkles")));
+    }
+
+    #[test]
+    fn blocked_words_are_never_added() {
+        test_store();
+        assert!(is_blocked_word("SLURS"));
+        let report = merge_words(&["slurs"], 5);
+        assert_eq!(report.blocked, BTreeSet::from(["slurs"]));
+        assert!(report.added().is_empty());
+        assert!(!is_game_word("slurs", None) && !is_dictionary_word("slurs", None));
+        let blocked = i18n::msg("en", MsgKey::WordBlocked, &[("word", "slurs")]);
+        assert!(report.message("en").contains(&blocked));
+    }
+
+    #[test]
+    fn mono_board_is_a_code_block() {
+        let guesses = [
+            ("⬛⬛🟩⬛🟩".to_string(), "slate".to_string()),
+            ("🟩🟩🟩🟩🟩".to_string(), "crane".to_string()),
+        ];
+        let guessers = ["Ann".to_string(), String::new()];
+        assert_eq!(
+            render_board(&guesses, &guessers, BoardStyle::Mono),
+            "⬛⬛🟩⬛🟩 slate Ann\n🟩🟩🟩🟩🟩 crane"
+        );
+        // The other boards only show the colors
+        assert_eq!(
+            render_board(&guesses, &guessers, BoardStyle::Emoji),
+            "⬛⬛🟩⬛🟩 Ann\n🟩🟩🟩🟩🟩"
+        );
+
+        let message = board_message("2/6", &guesses, &guessers, BoardStyle::Mono, &[]);
+        assert!(
+            message.starts_with("2/6\n```\n⬛⬛🟩⬛🟩 slate Ann\n🟩🟩🟩🟩🟩 crane\n```\n"),
+            "{message}"
+        );
+        // Backticks can't end the code block early
+        let guesses = [("⬛⬛⬛⬛⬛".to_string(), "a`b\\c".to_string())];
+        let message = board_message("", &guesses, &[], BoardStyle::Mono, &[]);
+        assert!(message.contains("⬛⬛⬛⬛⬛ a\\`b\\\\c\n```"), "{message}");
+    }
+
+    #[test]
+    fn overlapping_merges_report_what_was_new() {
+        test_store();
+        let first = merge_words(&["prism", "plumb"], 5);
+        assert_eq!(first.added(), BTreeSet::from(["plumb", "prism"]));
+        assert!(first.already_present.is_empty());
+
+        // "eerie" was only a dictionary word, so it joins the answers
+        let second = merge_words(
+            &["plumb", "prism", "gusto", "eerie", "slurs", "prisms", " "],
+            5,
+        );
+        assert_eq!(second.added(), BTreeSet::from(["eerie", "gusto"]));
+        assert_eq!(second.added_to_game, BTreeSet::from(["eerie", "gusto"]));
+        assert_eq!(second.added_to_dict, BTreeSet::from(["gusto"]));
+        assert_eq!(second.already_present, BTreeSet::from(["plumb", "prism"]));
+        assert_eq!(second.blocked, BTreeSet::from(["slurs"]));
+        assert_eq!(second.wrong_length, BTreeSet::from(["prisms"]));
+    }
+
+    #[test]
+    fn grapheme_words_are_colored_per_letter() {
+        // ñandú with its accents as combining marks, five letters of several code points
+        let answer = "n\u{303}andu\u{301}";
+        assert_eq!(letter_count(answer), 5);
+        assert_eq!(to_emoji(&score_guess("nandu", answer)), "⬛🟩🟩🟩⬛");
+        assert_eq!(to_emoji(&score_guess(answer, answer)), "🟩🟩🟩🟩🟩");
+        // The accented n is found out of place like any other letter
+        assert_eq!(
+            to_emoji(&score_guess("andu\u{301}n\u{303}", answer)),
+            "🟨🟨🟨🟨🟨"
+        );
+
+        let guesses = [(to_emoji(&score_guess("nandu", answer)), "nandu".to_string())];
+        assert_eq!(
+            render_board(&guesses, &[], BoardStyle::Letters),
+            "⬛🟩🟩🟩⬛\nＮＡＮＤＵ"
+        );
+        let guesses = [(to_emoji(&score_guess(answer, answer)), answer.to_string())];
+        assert_eq!(
+            render_board(&guesses, &[], BoardStyle::Letters),
+            "🟩🟩🟩🟩🟩\nN\u{303} ＡＮＤU\u{301} "
+        );
+    }
+
+    #[tokio::test]
+    async fn any_word_is_guessable_without_the_dictionary() {
+        let mut chat = ChatHarness::new(132_001);
+        let off = i18n::msg("en", MsgKey::DictionaryOff, &[]);
+        assert_eq!(chat.handle("/config dictionary off").await, [off.as_str()]);
+        chat.force_answer("crane");
+        chat.send("/wordle").await;
+        assert!(!chat.game().require_dictionary);
+
+        let replies = chat.send("/guess zzzzz").await;
+        assert!(replies[0].contains("1/6"), "{replies:?}");
+        // Guesses still have to be words of the right length
+        let letters_only = i18n::msg("en", MsgKey::LettersOnly, &[]);
+        assert_eq!(chat.send("/guess zz9zz").await, [letters_only.as_str()]);
+        chat.send("/guess zzz").await;
+        assert_eq!(chat.game().tries(), 1);
+    }
+
+    #[tokio::test]
+    async fn games_of_a_user_in_two_chats_are_independent() {
+        use teloxide::dispatching::DispatcherHandler;
+
+        test_store();
+        let telegram = FakeTelegram::start();
+        let bot = telegram.bot();
+        let storage = InMemStorage::new();
+        let user_id = 134_001;
+        let (private_chat, group_chat) = (user_id, -134_001);
+        for (chat_id, answer) in [(private_chat, "crane"), (group_chat, "slate")] {
+            let game = Dialogue::Guess(test_game(&[answer], 6));
+            storage
+                .clone()
+                .update_dialogue(chat_id, game)
+                .await
+                .unwrap();
+        }
+
+        let (updates, rx) = tokio::sync::mpsc::unbounded_channel();
+        let dispatcher = DialogueDispatcher::with_storage(handle_dialogue, storage.clone());
+        tokio::spawn(dispatcher.handle(rx));
+        let message = |chat: serde_json::Value, text: &str| {
+            let update = serde_json::from_value(serde_json::json!({
+                "message_id": 1,
+                "date": 0,
+                "chat": chat,
+                "from": { "id": user_id, "is_bot": false, "first_name": "Player" },
+                "text": text,
+            }))
+            .unwrap();
+            UpdateWithCx {
+                requester: bot.clone(),
+                update,
+            }
+        };
+        let private =
+            serde_json::json!({ "id": private_chat, "type": "private", "first_name": "Player" });
+        let group = serde_json::json!({ "id": group_chat, "type": "group", "title": "Group" });
+        updates.send(message(private, "/guess trace")).ok().unwrap();
+        // Past the cooldown, so the second command isn't dropped
+        tokio::time::sleep(*COMMAND_COOLDOWN).await;
+        updates.send(message(group, "/guess crane")).ok().unwrap();
+
+        let game = |chat_id| {
+            let storage = storage.clone();
+            async move {
+                for _ in 0..100 {
+                    if let Some(Dialogue::Guess(game)) =
+                        storage.clone().get_dialogue(chat_id).await.unwrap()
+                    {
+                        if game.tries() == 1 {
+                            return game;
+                        }
+                    }
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+                panic!("chat {chat_id} never got its guess");
+            }
+        };
+        let private_game = game(private_chat).await;
+        assert_eq!(private_game.answers, ["crane"]);
+        assert_eq!(private_game.guesses[0].1, "trace");
+        // Guessing the private game's answer in the group doesn't win there
+        let group_game = game(group_chat).await;
+        assert_eq!(group_game.answers, ["slate"]);
+        assert_eq!(group_game.guesses[0].1, "crane");
+    }
+
+    #[test]
+    fn word_files_skip_comments_and_read_weights() {
+        assert_eq!(
+            parse_word_line("gamma\t3"),
+            ("gamma", vec![("weight", "3")])
+        );
+        assert_eq!(
+            parse_word_line("delta\tweight=7\tclue=a river mouth"),
+            ("delta", vec![("weight", "7"), ("clue", "a river mouth")])
+        );
+        // Plain lines have no metadata, and columns that aren't any are dropped
+        assert_eq!(parse_word_line("omega"), ("omega", vec![]));
+        assert_eq!(parse_word_line("omega\tgreek"), ("omega", vec![]));
+
+        let store = test_store();
+        store
+            .save(
+                "weights-test.txt",
+                "# answers, one per line\ngamma\t3\n\ndelta\tweight=7\tclue=a river mouth\nomega\n",
+            )
+            .unwrap();
+        let mut seen = Vec::new();
+        let (words, _) = load_words("weights-test_custom.txt", "weights-test.txt", |word, _| {
+            seen.push(word.to_string());
+        });
+        assert_eq!(
+            words.iter().collect::<Vec<_>>(),
+            ["delta", "gamma", "omega"]
+        );
+        assert_eq!(seen, ["gamma", "delta", "omega"]);
+        let weights = WORD_WEIGHTS.read().unwrap();
+        assert_eq!(weights.get("gamma"), Some(&3));
+        assert_eq!(weights.get("delta"), Some(&7));
+        assert_eq!(weights.get("omega"), None);
+    }
+
+    #[tokio::test]
+    async fn clues_are_looked_up_and_cost_a_guess() {
+        test_store();
+        assert_eq!(word_clue("there"), Some("not here"));
+        assert_eq!(word_clue("crane"), None);
+
+        let mut chat = ChatHarness::new(139_001);
+        chat.force_answer("crane");
+        chat.send("/wordle").await;
+        assert_eq!(chat.send("/clue").await, ["No clue available"]);
+        assert_eq!(chat.game().tries(), 0);
+
+        let mut chat = ChatHarness::new(139_002);
+        chat.force_answer("there");
+        chat.send("/wordle").await;
+        assert_eq!(chat.send("/clue").await, ["Clue: not here. 1/6"]);
+        assert_eq!(chat.game().tries(), 1);
+        // Asking again repeats it for free
+        assert_eq!(chat.send("/clue").await, ["Clue: not here"]);
+        assert_eq!(chat.game().tries(), 1);
+        let replies = chat.send("/guess crane").await;
+        assert!(replies[0].contains("2/6"), "{replies:?}");
+
+        // A clue can't be what loses the game
+        let mut chat = ChatHarness::new(139_003);
+        chat.force_answer("there");
+        chat.send("/wordle").await;
+        chat.game_mut().max_guesses = 1;
+        assert_eq!(
+            chat.send("/clue").await,
+            ["A clue would use up your last guess"]
+        );
+        assert!(!chat.game().clue_used);
+    }
+
+    #[tokio::test]
+    async fn first_guess_can_come_with_wordle() {
+        let mut chat = ChatHarness::new(142_001);
+        chat.force_answer("there");
+        let replies = chat.send("/wordle crane").await;
+        assert!(
+            replies.iter().any(|reply| reply.contains("1/6")),
+            "{replies:?}"
+        );
+        assert_eq!(chat.game().answers, ["there"]);
+        assert_eq!(chat.game().tries(), 1);
+        assert_eq!(chat.game().guesses[0].1, "crane");
+
+        // The guess is checked like any other, and the game is still on when it's rejected
+        let mut chat = ChatHarness::new(142_002);
+        chat.force_answer("there");
+        let replies = chat.send("/wordle cranes").await;
+        assert_eq!(
+            replies.last().unwrap(),
+            "You sent 'cranes' (6), need 5 letter words"
+        );
+        assert_eq!(chat.game().answers, ["there"]);
+        assert_eq!(chat.game().tries(), 0);
+    }
+
+    #[tokio::test]
+    async fn second_wordle_keeps_the_running_game() {
+        let mut chat = ChatHarness::new(143_001);
+        chat.force_answer("there");
+        chat.send("/wordle").await;
+        chat.send("/guess crane").await;
+
+        chat.force_answer("slate");
+        assert_eq!(
+            chat.send("/wordle").await,
+            ["A game is already running — /guess to play or /exit to end"]
+        );
+        assert_eq!(chat.game().answers, ["there"]);
+        assert_eq!(chat.game().tries(), 1);
+        // Also when it's sent with a first guess
+        chat.send("/wordle slate").await;
+        assert_eq!(chat.game().answers, ["there"]);
+        assert_eq!(chat.game().tries(), 1);
+    }
+
+    #[tokio::test]
+    async fn guesses_left_is_counted_without_changing_the_game() {
+        let mut chat = ChatHarness::new(149_001);
+        chat.force_answer("there");
+        chat.send("/wordle").await;
+        assert_eq!(chat.send("/guesses").await, ["Guesses left: 6"]);
+        for guess in ["crane", "slate", "trace"] {
+            chat.send(&format!("/guess {guess}")).await;
+        }
+        let guesses = chat.game().guesses.clone();
+        let last_input = chat.game().last_input.clone();
+
+        assert_eq!(chat.send("/guesses").await, ["Guesses left: 3"]);
+        assert_eq!(chat.send("/left").await, ["Guesses left: 3"]);
+        assert_eq!(chat.game().guesses, guesses);
+        assert_eq!(chat.game().last_input, last_input);
+        assert_eq!(chat.game().tries(), 3);
+    }
+
+    #[tokio::test]
+    async fn letters_board_puts_each_letter_under_its_tile() {
+        let guesses = [
+            ("🟩⬛⬛⬛🟨".to_string(), "crane".to_string()),
+            ("⬛🟩⬛⬛⬛".to_string(), "ñandú".to_string()),
+        ];
+        let guessers = [String::new(), "Ana".to_string()];
+        assert_eq!(
+            render_board_with_letters(&guesses, &guessers),
+            "🟩⬛⬛⬛🟨\nＣＲＡＮＥ\n⬛🟩⬛⬛⬛ Ana\nÑ ＡＮＤÚ "
+        );
+        assert_eq!(render_board_with_letters(&[], &[]), "");
+
+        let mut chat = ChatHarness::new(152_001);
+        chat.force_answer("there");
+        chat.send("/wordle letters").await;
+        let replies = chat.send("/guess crane").await;
+        assert!(replies[0].contains("\nＣＲＡＮＥ"), "{replies:?}");
+        // Only when toggled on
+        let mut chat = ChatHarness::new(152_002);
+        chat.force_answer("there");
+        chat.send("/wordle").await;
+        let replies = chat.send("/guess crane").await;
+        assert!(!replies[0].contains('Ｃ'), "{replies:?}");
+    }
+
+    #[test]
+    fn words_in_one_set_only_are_added_to_the_other() {
+        test_store();
+        // An answer missing from the dictionary, as a hand edited answers file can leave it
+        GAME_WORDS.get().unwrap().write().unwrap().insert("quirk");
+
+        let report = merge_words(&["quirk", "cocoa"], 5);
+        assert_eq!(report.added_to_game, BTreeSet::from(["cocoa"]));
+        assert_eq!(report.added_to_dict, BTreeSet::from(["quirk"]));
+        assert_eq!(report.added(), BTreeSet::from(["cocoa", "quirk"]));
+        assert!(report.already_present.is_empty());
+        assert!(is_game_word("quirk", None) && is_dictionary_word("quirk", None));
+        assert!(is_game_word("cocoa", None) && is_dictionary_word("cocoa", None));
+
+        let message = report.message("en");
+        assert!(
+            message.contains("New answers {\"cocoa\"}\nNew dictionary words {\"quirk\"}"),
+            "{message}"
+        );
+        // Both are in both sets now
+        let again = merge_words(&["quirk", "cocoa"], 5);
+        assert!(again.added().is_empty());
+        assert_eq!(again.already_present, BTreeSet::from(["cocoa", "quirk"]));
+    }
+
+    #[tokio::test]
+    async fn score_command_scores_like_a_game() {
+        let emoji = |answer: &str, guess: &str| {
+            let breakdown = score_breakdown("en", Some(answer), Some(guess));
+            breakdown.lines().next().unwrap().to_string()
+        };
+        test_store();
+        assert_eq!(emoji("crane", "crane"), "🟩🟩🟩🟩🟩");
+        assert_eq!(emoji("crane", "bumpy"), "⬛⬛⬛⬛⬛");
+        assert_eq!(emoji("crane", "nacre"), "🟨🟨🟨🟨🟩");
+        assert_eq!(emoji("hello", "lolly"), "⬛🟨🟩🟩⬛");
+        assert_eq!(emoji("hello", "oopsy"), "🟨⬛⬛⬛⬛");
+        assert_eq!(emoji("crane", "eerie"), "⬛⬛🟨⬛🟩");
+        assert_eq!(emoji("there", "eerie"), "🟨⬛🟨⬛🟩");
+        assert_eq!(emoji("crane", "cocoa"), "🟩⬛⬛⬛🟨");
+        // Neither word has to be a known one, but both have to be words of the game's length
+        assert_eq!(emoji("Zzzzz", "ZZZZZ"), "🟩🟩🟩🟩🟩");
+        let length = "Both words have to be 5 letters";
+        assert_eq!(score_breakdown("en", Some("crane"), Some("cranes")), length);
+        assert_eq!(score_breakdown("en", Some("cr4ne"), Some("crane")), length);
+        assert_eq!(
+            score_breakdown("en", Some("crane"), None),
+            "Usage: /score <ANSWER> <GUESS>"
+        );
+
+        let mut admin = ChatHarness::new(TEST_ADMIN_IDS[0]);
+        let replies = admin.handle("/score hello lolly").await;
+        assert_eq!(
+            replies[0].lines().collect::<Vec<_>>(),
+            [
+                "⬛🟨🟩🟩⬛",
+                "1. l - not in the answer, or no copies left",
+                "2. o - wrong spot",
+                "3. l - right spot",
+                "4. l - right spot",
+                "5. y - not in the answer, or no copies left",
+            ]
+        );
+        // Scoring doesn't start a game
+        assert!(matches!(admin.dialogue, Dialogue::Start(_)));
+
+        let mut player = ChatHarness::new(154_002);
+        assert_eq!(
+            player.handle("/score hello lolly").await,
+            ["Only the bot's admins can use /score"]
+        );
+    }
+
+    #[tokio::test]
+    async fn chaos_keyboard_keeps_its_layout_for_the_game() {
+        let mut chat = ChatHarness::new(155_001);
+        chat.force_answer("there");
+        chat.send("/wordle chaos").await;
+        let seed = chat.game().keyboard_seed;
+        assert!(seed.is_some());
+        let layout = chat.game().keyboard_layout("en");
+        let qwerty: Vec<String> = i18n::keyboard_layout("en")
+            .iter()
+            .map(|row| row.to_string())
+            .collect();
+        assert_eq!(layout.concat().len(), qwerty.concat().len());
+
+        for guess in ["crane", "slate", "hello"] {
+            let replies = chat.send(&format!("/guess {guess}")).await;
+            assert_eq!(chat.game().keyboard_seed, seed);
+            assert_eq!(chat.game().keyboard_layout("en"), layout);
+            // Every turn shows the keyboard in the game's layout
+            let rows: Vec<&str> = layout.iter().map(String::as_str).collect();
+            let keyboard = keyboard::render_keyboard(&chat.game().guesses, &rows);
+            assert!(replies[0].contains(&keyboard), "{replies:?}");
+        }
+    }
+
+    #[test]
+    fn reported_size_is_the_set_size() {
+        let mut dict: WordSet = ["crane", "slate"].into_iter().collect();
+        let mut added = BTreeSet::new();
+        let size = insert_words(
+            &mut dict,
+            &BTreeSet::from(["crane", "vivid", "prism"]),
+            &mut added,
+        );
+        assert_eq!(size, 4);
+        assert_eq!(size, dict.len());
+        assert_eq!(added, BTreeSet::from(["prism", "vivid"]));
+
+        let report = MergeReport {
+            added_to_game: BTreeSet::from(["vivid"]),
+            added_to_dict: BTreeSet::from(["vivid"]),
+            length: 5,
+            game_size: 12_483,
+            dict_size: 1_204_000,
+            ..Default::default()
+        };
+        let message = report.message("en");
+        assert!(
+            message.ends_with("There are now 12,483 answers and 1,204,000 dictionary words"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn corrupt_custom_word_file_falls_back_to_the_base_one() {
+        test_store();
+        let dir = env::temp_dir().join(format!("telebot-corrupt-words-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        // Not UTF-8, as a binary file or one saved in another encoding would be
+        fs::write(dir.join("corrupt_custom.txt"), b"cr\xffne\n\x00\x9c").unwrap();
+        fs::write(dir.join("corrupt.txt"), "crane\nslate\n").unwrap();
+
+        let store = FileStore::new(dir.clone());
+        let mut seen = Vec::new();
+        let (words, source) =
+            load_words_from(&store, "corrupt_custom.txt", "corrupt.txt", |word, _| {
+                seen.push(word.to_string())
+            });
+        assert_eq!(seen, ["crane", "slate"]);
+        assert!(words.contains("crane") && words.contains("slate"));
+        assert!(source.ends_with("corrupt.txt"), "{source}");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn paused_saves_are_flushed_on_resume_and_exit() {
+        let requests = Arc::new(SaveRequests::default());
+        let paused = Arc::new(AtomicBool::new(true));
+        let exiting = Arc::new(AtomicBool::new(false));
+        let saves = Arc::new(Mutex::new(0));
+        let worker = {
+            let (requests, paused, exiting, saves) = (
+                requests.clone(),
+                paused.clone(),
+                exiting.clone(),
+                saves.clone(),
+            );
+            thread::spawn(move || {
+                let timing = (Duration::from_millis(50), Duration::ZERO);
+                run_saves(&requests, &paused, &exiting, timing, || {
+                    *saves.lock().unwrap() += 1
+                });
+            })
+        };
+
+        // Neither edits nor the interval save anything while paused
+        for _ in 0..5 {
+            requests.request();
+            thread::sleep(Duration::from_millis(10));
+        }
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(*saves.lock().unwrap(), 0);
+
+        // Resuming asks for a save, as /resumesave does, so it doesn't wait for the interval
+        paused.store(false, Ordering::Relaxed);
+        requests.request();
+        thread::sleep(Duration::from_millis(20));
+        assert!(*saves.lock().unwrap() >= 1);
+
+        // Exiting still saves when paused
+        paused.store(true, Ordering::Relaxed);
+        thread::sleep(Duration::from_millis(50));
+        let before_exit = *saves.lock().unwrap();
+        exiting.store(true, Ordering::Relaxed);
+        requests.request();
+        worker.join().unwrap();
+        assert_eq!(*saves.lock().unwrap(), before_exit + 1);
+    }
+
+    #[tokio::test]
+    async fn emoji_and_symbol_guesses_are_letters_only() {
+        let letters_only = "Letters only, please";
+        let mut chat = ChatHarness::new(161_001);
+        chat.force_answer("crane");
+        chat.send("/wordle").await;
+        for guess in [
+            "🍎",
+            "🍎🍎🍎🍎🍎",
+            "🟩🟩🟩🟩🟩",
+            "12345",
+            "$$$$$",
+            "cr@ne",
+            "c🍎ane",
+            "crαne",
+        ] {
+            assert_eq!(
+                chat.send(&format!("/guess {guess}")).await,
+                [letters_only],
+                "{guess}"
+            );
+        }
+        assert_eq!(chat.game().tries(), 0);
+        // Punctuation around a word is still trimmed off
+        let replies = chat.send("/guess crane!").await;
+        assert!(replies[0].contains("1/6"), "{replies:?}");
+    }
+
+    #[tokio::test]
+    async fn pool_switches_the_answers_of_later_games() {
+        let mut chat = ChatHarness::new(162_001);
+        assert_eq!(
+            chat.send("/pool").await,
+            ["Answers are drawn from all words. /pool <rocks, all> to change"]
+        );
+        assert_eq!(
+            chat.send("/pool Rocks").await,
+            ["Answers are now drawn from rocks"]
+        );
+        assert_eq!(chat_config::get(162_001).pool.as_deref(), Some("rocks"));
+        // Kept for every game until it's changed
+        for _ in 0..3 {
+            chat.send("/wordle").await;
+            assert_eq!(chat.game().answers, ["slate"]);
+            chat.send("/exit").await;
+        }
+
+        assert_eq!(
+            chat.send("/pool dinosaurs").await,
+            ["There's no dinosaurs pool, pick one of rocks, all"]
+        );
+        assert_eq!(chat_config::get(162_001).pool.as_deref(), Some("rocks"));
+        assert_eq!(
+            chat.send("/pool all").await,
+            ["Answers are now drawn from all words"]
+        );
+        assert_eq!(chat_config::get(162_001).pool, None);
+    }
+
+    #[tokio::test]
+    async fn wrong_length_guess_is_echoed_without_using_a_turn() {
+        let mut chat = ChatHarness::new(163_001);
+        chat.force_answer("there");
+        chat.send("/wordle").await;
+        chat.send("/guess crane").await;
+        let guesses = chat.game().guesses.clone();
+
+        assert_eq!(
+            chat.send("/guess cat").await,
+            ["You sent 'cat' (3), need 5 letter words"]
+        );
+        assert_eq!(
+            chat.send("/guess cranes").await,
+            ["You sent 'cranes' (6), need 5 letter words"]
+        );
+        assert_eq!(chat.game().guesses, guesses);
+        assert_eq!(chat.game().tries(), 1);
+        assert_eq!(chat.game().answers, ["there"]);
+        let replies = chat.send("/guess slate").await;
+        assert!(replies[0].contains("2/6"), "{replies:?}");
+    }
+
+    #[test]
+    fn missing_word_files_fall_back_to_the_embedded_words() {
+        test_store();
+        let embedded: Vec<&str> = FALLBACK_WORDS
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect();
+        assert!(embedded.len() >= 40);
+        assert!(embedded.iter().all(|word| letter_count(word) == 5));
+
+        let store = MemoryStore::default();
+        let (words, source) = load_words_from(
+            &store,
+            "fallback-test_custom.txt",
+            "fallback-test.txt",
+            |_, _| (),
+        );
+        assert_eq!(source, "the built-in words");
+        assert_eq!(words.len(), embedded.len());
+        assert!(embedded.iter().all(|word| words.contains(word)));
+
+        // The same with an assets directory that has no word files at all
+        let dir = env::temp_dir().join(format!("telebot-no-words-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let store = FileStore::new(dir.clone());
+        let (words, source) = load_words_from(
+            &store,
+            "fallback-test_custom.txt",
+            "fallback-test.txt",
+            |_, _| (),
+        );
+        assert_eq!(source, "the built-in words");
+        assert!(words.contains("apple"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn uniform_word_lengths_set_the_game_length() {
+        let length = |words: &[&str]| shared_length(words.iter().copied());
+        assert_eq!(length(&["crane", "slate", "trace"]), 5);
+        assert_eq!(length(&["cranes", "slates", "traces"]), 6);
+        assert_eq!(length(&["ñandú", "crane"]), 5);
+        // Mixed, or no words at all
+        assert_eq!(length(&["crane", "cranes"]), DEFAULT_WORD_LENGTH);
+        assert_eq!(length(&["cat", "cranes"]), DEFAULT_WORD_LENGTH);
+        assert_eq!(length(&[]), DEFAULT_WORD_LENGTH);
+
+        // The tests' game words are all 5 letters once "sparkles" is filtered out
+        test_store();
+        assert_eq!(word_length(), 5);
+    }
+
+    #[tokio::test]
+    async fn mixed_games_vary_in_length() {
+        let chat_id = 172_001;
+        test_store();
+        chat_words::add(chat_id, &["oboe", "oboist"]);
+        let mut chat = ChatHarness::new(chat_id);
+        let mut lengths = BTreeSet::new();
+        for _ in 0..40 {
+            let replies = chat.send("/wordle mixed").await;
+            let length = chat.game().word_length;
+            assert_eq!(length, letter_count(&chat.game().answers[0]));
+            // Each game announces its own length
+            let started = format!("Wordle game started - /guess any {length} letter word");
+            assert_eq!(replies, [started]);
+            lengths.insert(length);
+            chat.send("/exit").await;
+        }
+        assert_eq!(lengths, BTreeSet::from([4, 5, 6]));
+
+        // Games of a single length stay at it
+        assert_eq!(
+            get_random_word(chat_id, &(6..=6)).as_deref(),
+            Some("oboist")
+        );
+    }
+
+    #[tokio::test]
+    async fn coach_estimate_follows_the_candidates_left() {
+        assert_eq!(coach_verdict(1, 5), MsgKey::OnTrack);
+        assert_eq!(coach_verdict(10, 5), MsgKey::OnTrack);
+        assert_eq!(coach_verdict(11, 5), MsgKey::ToughSpot);
+        assert_eq!(coach_verdict(3, 1), MsgKey::ToughSpot);
+        assert_eq!(coach_verdict(0, 5), MsgKey::ToughSpot);
+
+        // Few words fit after "crane", leaving plenty of guesses to find the answer among them
+        let mut chat = ChatHarness::new(177_001);
+        chat.force_answer("there");
+        chat.send("/wordle coach").await;
+        let replies = chat.send("/guess crane").await;
+        assert!(replies[0].contains("On track 👍 "), "{replies:?}");
+        // Not shown unless asked for
+        let mut chat = ChatHarness::new(177_002);
+        chat.force_answer("there");
+        chat.send("/wordle").await;
+        let replies = chat.send("/guess crane").await;
+        assert!(!replies[0].contains("words left"), "{replies:?}");
+    }
+
+    #[test]
+    fn edits_racing_saves_are_never_lost() {
+        const EDITORS: u8 = 4;
+        let store = test_store();
+        let editing = Arc::new(AtomicBool::new(true));
+        let saver = {
+            let editing = editing.clone();
+            thread::spawn(move || {
+                let mut saves = 0;
+                while editing.load(Ordering::Relaxed) {
+                    save_changes();
+                    saves += 1;
+                }
+                saves
+            })
+        };
+
+        let editors: Vec<_> = (0..EDITORS)
+            .map(|editor| {
+                thread::spawn(move || {
+                    let words: Vec<String> = (b'a'..=b'z')
+                        .map(|letter| format!("zv{}{}q", (b'a' + editor) as char, letter as char))
+                        .collect();
+                    for word in &words {
+                        // As `edit_dictionary` does it: the flag is set once the word is in
+                        merge_words(&[word], 5);
+                        mark_dictionary_dirty();
+                    }
+                    words
+                })
+            })
+            .collect();
+        let words: Vec<String> = editors
+            .into_iter()
+            .flat_map(|editor| editor.join().unwrap())
+            .collect();
+        editing.store(false, Ordering::Relaxed);
+        assert!(saver.join().unwrap() > 0);
+
+        // Whatever the last save in the loop missed left the flag set for this one
+        save_changes();
+        for file_name in ["words_custom.txt", "dictionary_custom.txt"] {
+            let saved = store.load(file_name).unwrap().unwrap();
+            let saved: BTreeSet<&str> = saved
+                .lines()
+                .map(|line| line.split('\t').next().unwrap_or_default())
+                .collect();
+            for word in &words {
+                assert!(
+                    saved.contains(word.as_str()),
+                    "{word} missing from {file_name}"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn length_reminder_leaves_the_game_as_is() {
+        let chat_id = 179_001;
+        test_store();
+        chat_words::add(chat_id, &["oboist", "planet"]);
+        let mut chat = ChatHarness::new(chat_id);
+        chat.force_answer("oboist");
+        chat.send("/wordle mixed").await;
+        assert_eq!(chat.send("/length").await, ["The answer has 6 letters"]);
+        assert_eq!(chat.game().tries(), 0);
+
+        chat.send("/guess planet").await;
+        let guesses = chat.game().guesses.clone();
+        let last_input = chat.game().last_input.clone();
+        assert_eq!(chat.send("/length").await, ["The answer has 6 letters"]);
+        assert_eq!(chat.game().guesses, guesses);
+        assert_eq!(chat.game().last_input, last_input);
+        assert_eq!(chat.game().tries(), 1);
+        assert_eq!(chat.game().word_length, 6);
+    }
+
+    #[tokio::test]
+    async fn explicit_modes_win_over_the_default_ones() {
+        let mut chat = ChatHarness::new(180_001);
+        assert_eq!(
+            chat.handle("/setdefault mono hard-mode").await,
+            ["Your games now start in mono hard-mode mode"]
+        );
+        tokio::time::sleep(*COMMAND_COOLDOWN).await;
+        assert_eq!(
+            chat.handle("/setdefault mono sideways").await,
+            ["Unknown modes sideways, see /modes"]
+        );
+        assert_eq!(
+            user_config::get(180_001).default_modes,
+            ["mono", "hard-mode"]
+        );
+
+        chat.force_answer("there");
+        chat.send("/wordle").await;
+        assert!(chat.game().board == BoardStyle::Mono);
+        assert!(chat.game().hard_mode && chat.game().max_guesses == 5);
+        chat.send("/exit").await;
+
+        // Passed to /wordle, a preset replaces the default one and a board replaces the default
+        // board, while the other defaults still apply
+        chat.force_answer("there");
+        chat.send("/wordle easy").await;
+        assert!(chat.game().board == BoardStyle::Mono);
+        assert!(!chat.game().hard_mode && chat.game().max_guesses == 8);
+        chat.send("/exit").await;
+        chat.force_answer("there");
+        chat.send("/wordle letters guesses=3").await;
+        assert!(chat.game().board == BoardStyle::Letters);
+        assert!(chat.game().hard_mode && chat.game().max_guesses == 3);
+        chat.send("/exit").await;
+
+        // The defaults are the user's, other players don't get them
+        let mut other = ChatHarness::new(180_002);
+        other.force_answer("there");
+        other.send("/wordle").await;
+        assert!(other.game().board == BoardStyle::Emoji && !other.game().hard_mode);
+
+        tokio::time::sleep(*COMMAND_COOLDOWN).await;
+        chat.handle("/setdefault").await;
+        chat.force_answer("there");
+        chat.send("/wordle").await;
+        assert!(chat.game().board == BoardStyle::Emoji && !chat.game().hard_mode);
+    }
+
+    #[tokio::test]
+    async fn presets_configure_their_games() {
+        for (chat_id, preset, max_guesses, hints, hard) in [
+            (183_001, "easy", 8, true, false),
+            (183_002, "normal", 6, true, false),
+            (183_003, "hard-mode", 5, false, true),
+            (183_004, "HARD-MODE", 5, false, true),
+        ] {
+            let mut chat = ChatHarness::new(chat_id);
+            chat.force_answer("there");
+            chat.send(&format!("/wordle {preset}")).await;
+            let game = chat.game();
+            assert_eq!(game.max_guesses, max_guesses, "{preset}");
+            assert_eq!(game.hints_allowed, hints, "{preset}");
+            assert_eq!(game.hard_mode, hard, "{preset}");
+
+            let replies = chat.send("/guess crane").await;
+            let tries = format!("1/{max_guesses}");
+            assert!(replies[0].contains(&tries), "{preset}: {replies:?}");
+            let replies = chat.send("/hint").await;
+            assert_eq!(
+                replies[0].starts_with("Position "),
+                hints,
+                "{preset}: {replies:?}"
+            );
+        }
+
+        // Without a preset games are played as normal ones
+        let mut chat = ChatHarness::new(183_005);
+        chat.force_answer("there");
+        chat.send("/wordle").await;
+        assert_eq!(chat.game().max_guesses, DEFAULT_MAX_GUESSES);
+        assert!(chat.game().hints_allowed && !chat.game().hard_mode);
+    }
+
+    #[tokio::test]
+    async fn word_list_diff_compares_with_the_base_file() {
+        test_store();
+        let words: WordSet = ["slate", "crane", "vivid", "prism"].into_iter().collect();
+        let base = "#telebot-words v1\ncrane\tweight=3\nslate\ntrace\nsparkles\n\n";
+        let (added, removed) = diff_words(&words, base);
+        assert_eq!(added, ["prism", "vivid"]);
+        // "sparkles" is too long to be loaded, so it isn't missing
+        assert_eq!(removed, ["trace"]);
+        let (added, removed) = diff_words(&words, "crane\nprism\nslate\nvivid");
+        assert!(added.is_empty() && removed.is_empty());
+
+        let diff = word_list_diff("ru");
+        assert!(diff.starts_with("Слова игры: "), "{diff}");
+        assert!(diff.contains("\n\nСлова словаря: "), "{diff}");
+        let mut admin = ChatHarness::new(TEST_ADMIN_IDS[1]);
+        let replies = admin.handle("/diffwords").await;
+        assert!(replies[0].starts_with("Game words: "), "{replies:?}");
+    }
+
+    #[tokio::test]
+    async fn answer_of_a_lost_group_game_goes_to_its_participants() {
+        let (ana, ben, cy) = ((186_011, "Ana"), (186_012, "Ben"), (186_013, "Cy"));
+        let mut chat = ChatHarness::group(-186_001, ana);
+        chat_config::update(-186_001, |config| config.private_answer = true);
+        chat.force_answer("there");
+        chat.send("/wordle guesses=4").await;
+        chat.send("/guess crane").await;
+        // A player who only looks at the board isn't one
+        chat.play_as(cy);
+        chat.send("/guesses").await;
+        chat.play_as(ben);
+        chat.send("/guess slate").await;
+        chat.play_as(ana);
+        chat.send("/guess hello").await;
+        assert_eq!(chat.game().participants, BTreeSet::from([ana.0, ben.0]));
+
+        chat.play_as(ben);
+        let sent = chat.send_anywhere("/guess trace").await;
+        let private = "The answer of your game in Testers was there";
+        let to = |chat_id: i64| -> Vec<&str> {
+            sent.iter()
+                .filter(|(to, _)| *to == chat_id)
+                .map(|(_, text)| text.as_str())
+                .collect()
+        };
+        assert_eq!(to(ana.0), [private]);
+        assert_eq!(to(ben.0), [private]);
+        assert!(to(cy.0).is_empty());
+        let group = to(-186_001).concat();
+        assert!(group.contains("Answer was ■■■■■"), "{group}");
+        assert!(
+            group.contains("The answer was sent to the players privately"),
+            "{group}"
+        );
+        assert!(!group.contains("there"), "{group}");
+    }
+
+    #[test]
+    fn empty_custom_word_file_falls_back_to_the_base_one() {
+        test_store();
+        // Truncated outright, cut after the header, or left with lines that have no word
+        for custom in ["", "#telebot-words v1\n", "#telebot-words v1\n\n\t3\n  \n"] {
+            let store = MemoryStore::new(HashMap::from([
+                ("empty-test_custom.txt".to_string(), custom.to_string()),
+                ("empty-test.txt".to_string(), "crane\nslate\n".to_string()),
+            ]));
+            let (words, source) =
+                load_words_from(&store, "empty-test_custom.txt", "empty-test.txt", |_, _| ());
+            assert!(source.ends_with("empty-test.txt"), "{custom:?}: {source}");
+            assert_eq!(words.len(), 2, "{custom:?}");
+            assert!(words.contains("crane") && words.contains("slate"));
+        }
+
+        // A custom file with a single word is still used
+        let store = MemoryStore::new(HashMap::from([
+            ("empty-test_custom.txt".to_string(), "vivid\n".to_string()),
+            ("empty-test.txt".to_string(), "crane\nslate\n".to_string()),
+        ]));
+        let (words, source) =
+            load_words_from(&store, "empty-test_custom.txt", "empty-test.txt", |_, _| ());
+        assert!(source.ends_with("empty-test_custom.txt"), "{source}");
+        assert_eq!(words.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn tutorial_walks_through_its_steps() {
+        let step = |chat: &ChatHarness| match &chat.dialogue {
+            Dialogue::Tutorial(tutorial) => Some(tutorial.step()),
+            _ => None,
+        };
+        let mut chat = ChatHarness::new(188_001);
+        let replies = chat.send("/tutorial").await;
+        assert!(
+            replies[0].starts_with("Let's play a practice game!"),
+            "{replies:?}"
+        );
+        assert_eq!(step(&chat), Some(0));
+
+        // Anything but a guess of the right length keeps the player at the same step
+        assert_eq!(
+            chat.send("/guess cat").await,
+            ["Practice guesses have to be 5 letter words. Start with /guess slate"]
+        );
+        assert_eq!(
+            chat.send("/hint").await,
+            ["You're in the tutorial. Start with /guess slate"]
+        );
+        assert_eq!(step(&chat), Some(0));
+
+        for (guess, prompt, next_step) in [
+            ("slate", "🟩 means a letter is in the right spot", 1),
+            ("crate", "Close!", 2),
+            ("trace", "The answer is crane, /guess crane to finish", 3),
+        ] {
+            let replies = chat.send(&format!("/guess {guess}")).await;
+            assert!(replies[0].starts_with(prompt), "{guess}: {replies:?}");
+            assert_eq!(step(&chat), Some(next_step));
+        }
+        let replies = chat.send("/guess crane").await;
+        assert!(replies[0].starts_with("You solved it! 🎉"), "{replies:?}");
+        assert!(matches!(chat.dialogue, Dialogue::Start(_)));
+        // Only practice
+        assert!(stats::read(|stats| !stats.users.contains_key(&188_001)));
+
+        // Left at any step
+        chat.send("/tutorial").await;
+        chat.send("/guess slate").await;
+        assert_eq!(
+            chat.send("/exit").await,
+            ["Tutorial ended, start a real game with /wordle"]
+        );
+        assert!(matches!(chat.dialogue, Dialogue::Start(_)));
+    }
+
+    #[tokio::test]
+    async fn games_are_played_at_the_length_they_start_with() {
+        test_store();
+        assert_eq!(score_guess("ruby", "ruby").len(), 4);
+        assert_eq!(score_guess("quartz", "quarts").len(), 6);
+        assert_eq!(to_emoji(&score_guess("burn", "ruby")), "🟨🟩🟨⬛");
+        // Words are only added at the length asked for
+        let report = merge_words(&["burns", "slate"], 4);
+        assert_eq!(report.wrong_length, BTreeSet::from(["burns", "slate"]));
+
+        let chat_id = 253_001;
+        let mut chat = ChatHarness::new(chat_id);
+        assert_eq!(
+            chat.send("/wordle 4").await,
+            ["There are no 4 letter words to play with"]
+        );
+        // The chat's own words, so games of other tests don't draw them
+        chat_words::add(chat_id, &["ruby", "burn", "quartz", "quarts"]);
+        for (length, answer, guess, tiles) in [
+            (4, "ruby", "burn", "🟨🟩🟨⬛"),
+            (6, "quartz", "quarts", "🟩🟩🟩🟩🟩⬛"),
+        ] {
+            let replies = chat.send(&format!("/wordle {length}")).await;
+            let started = format!("Wordle game started - /guess any {length} letter word");
+            assert_eq!(replies, [started]);
+            assert_eq!(chat.game().word_length, length);
+            chat.game_mut().answers = vec![answer.to_string()];
+
+            let replies = chat.send(&format!("/guess {guess}")).await;
+            assert!(replies[0].contains(tiles), "{replies:?}");
+            assert_eq!(chat.game().guesses[0].0.chars().count(), length);
+            let expected = format!("You sent 'crane' (5), need {length} letter words");
+            assert_eq!(chat.send("/guess crane").await, [expected]);
+            chat.send(&format!("/guess {answer}")).await;
+            assert!(matches!(chat.dialogue, Dialogue::Start(_)));
+        }
+    }
+
+    #[tokio::test]
+    async fn hints_skip_known_letters_and_cost_a_guess() {
+        // Only the n of "crane" isn't green after "crate"
+        let mut chat = ChatHarness::new(255_001);
+        chat_words::add(255_001, &["crate"]);
+        chat.force_answer("crane");
+        chat.send("/wordle").await;
+        chat.send("/guess crate").await;
+        assert_eq!(chat.send("/guesses").await, ["Guesses left: 5"]);
+        assert_eq!(chat.send("/hint").await, ["Position 4 is N. 2/6"]);
+        assert_eq!(chat.game().hints, [3]);
+        assert_eq!(chat.send("/guesses").await, ["Guesses left: 4"]);
+        // Hints that can't be given don't cost anything
+        assert_eq!(chat.send("/hint").await, ["No hints left"]);
+        assert_eq!(chat.game().tries(), 2);
+
+        // With the r, a and e green after "trace", a hint is either the c or the n
+        for game in 0..20 {
+            let mut chat = ChatHarness::new(255_010 + game);
+            chat.force_answer("crane");
+            chat.send("/wordle").await;
+            chat.send("/guess trace").await;
+            let replies = chat.send("/hint").await;
+            assert!(
+                ["Position 1 is C. 2/6", "Position 4 is N. 2/6"].contains(&replies[0].as_str()),
+                "{replies:?}"
+            );
+            assert_eq!(chat.send("/guesses").await, ["Guesses left: 4"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn group_board_shows_who_guessed_each_row() {
+        let (ana, ben) = ((259_011, "Ana"), (259_012, "Ben"));
+        let mut chat = ChatHarness::group(-259_001, ana);
+        chat.force_answer("there");
+        chat.send("/wordle mono").await;
+        chat.send("/guess crane").await;
+        chat.play_as(ben);
+        let replies = chat.send("/guess slate").await;
+        assert_eq!(chat.game().guessers, ["Ana", "Ben"]);
+        let board = replies.concat();
+        assert!(board.contains("⬛🟨⬛⬛🟩 crane Ana\n"), "{board}");
+        assert!(board.contains("⬛⬛⬛🟨🟩 slate Ben\n"), "{board}");
+
+        // A bare /addword only adds the sender's own rejected guess
+        chat.play_as(ana);
+        chat.send("/guess zqvbk").await;
+        chat.play_as(ben);
+        let replies = chat.send("/addword").await;
+        assert!(replies.concat().starts_with("Added 0 words"), "{replies:?}");
+        assert!(!is_dictionary_word("zqvbk", None));
+    }
 }
@@ -0,0 +1,135 @@
+//! Writing the files the bot keeps its state in so a crash mid-save can't corrupt them, and
+//! reading them back without failing to start over one that is corrupt anyway
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Writes a temporary file next to `path` and renames it over `path` once it's fully on disk, so
+/// a crash mid-save leaves the old file intact instead of a partial one
+pub fn replace_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp_path = with_suffix(path, "tmp");
+    let written = write_synced(&temp_path, contents).and_then(|()| fs::rename(&temp_path, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    written
+}
+
+/// Writes `contents` to `path` and waits for it to reach the disk
+fn write_synced(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// Saves `value` as JSON to `path`, see `replace_file`
+pub fn save_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> io::Result<()> {
+    replace_file(path, &serde_json::to_vec(value)?)
+}
+
+/// Reads the JSON file at `path`, `None` if there's none yet. A file that can't be parsed is
+/// moved aside to a timestamped `.corrupt` file and reads as `None`, so the bot starts from
+/// scratch instead of not at all, and the old data can still be recovered by hand.
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> io::Result<Option<T>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    match serde_json::from_reader(BufReader::new(file)) {
+        Ok(value) => Ok(Some(value)),
+        Err(err) if err.is_io() => Err(err.into()),
+        Err(err) => {
+            let corrupt_path = with_suffix(path, &format!("{}.corrupt", crate::unix_now()));
+            // Starting over without moving it aside would overwrite it with the next save
+            fs::rename(path, &corrupt_path)?;
+            log::error!(
+                "Could not parse {}, moved it to {} and starting without it: {err}",
+                path.display(),
+                corrupt_path.display()
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// `path` with `.<suffix>` appended to its file name
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".{suffix}"));
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("telebot-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let dir = temp_dir("persist-round-trip");
+        let path = dir.join("state.json");
+        assert_eq!(load_json::<BTreeMap<String, u32>>(&path).unwrap(), None);
+
+        let state = BTreeMap::from([("wins".to_string(), 3)]);
+        save_json(&path, &state).unwrap();
+        assert_eq!(load_json(&path).unwrap(), Some(state));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn replaced_file_has_exactly_the_new_contents() {
+        let dir = temp_dir("persist-replace");
+        let path = dir.join("words.txt");
+        fs::write(&path, "crane\nslate\ntrace\n").unwrap();
+
+        replace_file(&path, b"crane\nvivid\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "crane\nvivid\n");
+        // The temporary file was renamed over the old one
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn failed_write_leaves_old_file_intact() {
+        let dir = temp_dir("persist-failed-write");
+        let path = dir.join("words.txt");
+        fs::write(&path, "crane\n").unwrap();
+        // A directory in the way of the temporary file makes creating it fail
+        fs::create_dir(with_suffix(&path, "tmp")).unwrap();
+
+        assert!(replace_file(&path, b"vivid\n").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "crane\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn corrupt_json_is_moved_aside() {
+        let dir = temp_dir("persist-corrupt");
+        let path = dir.join("state.json");
+        fs::write(&path, r#"{"wins": "#).unwrap();
+
+        assert_eq!(load_json::<BTreeMap<String, u32>>(&path).unwrap(), None);
+        assert!(!path.exists());
+        let moved: Vec<PathBuf> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(moved.len(), 1);
+        assert!(moved[0].to_string_lossy().ends_with(".corrupt"));
+        assert_eq!(fs::read_to_string(&moved[0]).unwrap(), r#"{"wins": "#);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
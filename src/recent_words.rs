@@ -0,0 +1,165 @@
+//! Log of the latest words added to the dictionary and who added them, persisted to
+//! `recent_words.json` in the assets directory
+
+use std::collections::VecDeque;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+use chrono::{TimeZone, Utc};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+use crate::persist;
+
+/// Latest additions, oldest first
+static RECENT_WORDS: OnceCell<RwLock<VecDeque<Addition>>> = OnceCell::new();
+/// Flag to indicate to our worker thread that words were added since the last save
+static DIRTY_RECENT_WORDS: AtomicBool = AtomicBool::new(false);
+
+const RECENT_WORDS_FILE: &str = "recent_words.json";
+/// How many additions are kept, older ones are dropped as new ones come in
+const MAX_RECENT_WORDS: usize = 200;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Addition {
+    pub word: String,
+    /// Telegram user id of whoever added the word, `None` if it wasn't sent by a user
+    pub user_id: Option<i64>,
+    pub user_name: String,
+    /// Unix timestamp in seconds
+    pub added: u64,
+}
+
+pub fn load(assets_dir: &Path) {
+    let recent = persist::load_json(&assets_dir.join(RECENT_WORDS_FILE))
+        .expect("could not read recent words file")
+        .unwrap_or_default();
+
+    RECENT_WORDS
+        .set(RwLock::new(recent))
+        .expect("RECENT_WORDS already initialized");
+}
+
+/// Logs that `user` added `words`
+pub fn record(words: &[&str], user: Option<&teloxide::types::User>) {
+    if words.is_empty() {
+        return;
+    }
+    let recent = RECENT_WORDS.get().expect("RECENT_WORDS is not initialized");
+    let mut recent = recent.write().expect("failed to lock RECENT_WORDS");
+
+    let added = crate::unix_now();
+    for word in words {
+        let addition = Addition {
+            word: word.to_string(),
+            user_id: user.map(|user| user.id),
+            user_name: user.map(|user| user.full_name()).unwrap_or_default(),
+            added,
+        };
+        push(&mut recent, addition);
+    }
+    DIRTY_RECENT_WORDS.store(true, Ordering::Relaxed);
+}
+
+/// Appends `addition` to `recent`, dropping the oldest one once there are `MAX_RECENT_WORDS`
+fn push(recent: &mut VecDeque<Addition>, addition: Addition) {
+    recent.push_back(addition);
+    while recent.len() > MAX_RECENT_WORDS {
+        recent.pop_front();
+    }
+}
+
+/// The `limit` latest additions, newest first, one per line
+pub fn list(limit: usize) -> String {
+    let recent = RECENT_WORDS.get().expect("RECENT_WORDS is not initialized");
+    let recent = recent.read().expect("failed to lock RECENT_WORDS");
+    render(&recent, limit)
+}
+
+/// The `limit` latest of `recent`, see `list`
+fn render(recent: &VecDeque<Addition>, limit: usize) -> String {
+    if recent.is_empty() {
+        return "No words were added yet".into();
+    }
+
+    recent
+        .iter()
+        .rev()
+        .take(limit)
+        .map(|addition| {
+            let added = Utc
+                .timestamp_opt(addition.added as i64, 0)
+                .single()
+                .map(|added| added.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_default();
+            match addition.user_id {
+                Some(id) => format!(
+                    "{} by {} ({id}), {added}",
+                    addition.word, addition.user_name
+                ),
+                None => format!("{}, {added}", addition.word),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Writes the log to disk if words were added since the last save
+pub fn save_if_dirty(assets_dir: &Path) -> io::Result<()> {
+    if !DIRTY_RECENT_WORDS.swap(false, Ordering::Relaxed) {
+        return Ok(());
+    }
+    // A failed save leaves the changes to the next one
+    save(assets_dir).inspect_err(|_| DIRTY_RECENT_WORDS.store(true, Ordering::Relaxed))
+}
+
+fn save(assets_dir: &Path) -> io::Result<()> {
+    let recent = RECENT_WORDS.get().expect("RECENT_WORDS is not initialized");
+    let recent = recent.read().expect("failed to lock RECENT_WORDS");
+    persist::save_json(&assets_dir.join(RECENT_WORDS_FILE), &*recent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addition(word: &str, user_id: Option<i64>, added: u64) -> Addition {
+        Addition {
+            word: word.to_string(),
+            user_id,
+            user_name: user_id.map(|_| "Ana".to_string()).unwrap_or_default(),
+            added,
+        }
+    }
+
+    #[test]
+    fn oldest_additions_are_dropped() {
+        let mut recent = VecDeque::new();
+        for i in 0..MAX_RECENT_WORDS + 5 {
+            push(&mut recent, addition(&format!("word{i}"), None, i as u64));
+        }
+        assert_eq!(recent.len(), MAX_RECENT_WORDS);
+        assert_eq!(recent.front().unwrap().word, "word5");
+        assert_eq!(
+            recent.back().unwrap().word,
+            format!("word{}", MAX_RECENT_WORDS + 4)
+        );
+    }
+
+    #[test]
+    fn latest_additions_are_listed_first() {
+        assert_eq!(render(&VecDeque::new(), 10), "No words were added yet");
+
+        let mut recent = VecDeque::new();
+        push(&mut recent, addition("crane", Some(7), 1_700_000_000));
+        push(&mut recent, addition("slate", None, 1_700_000_060));
+        push(&mut recent, addition("trace", Some(7), 1_700_000_120));
+        assert_eq!(
+            render(&recent, 2),
+            "trace by Ana (7), 2023-11-14 22:15 UTC\nslate, 2023-11-14 22:14 UTC"
+        );
+        assert_eq!(render(&recent, 10).lines().count(), 3);
+    }
+}
@@ -0,0 +1,64 @@
+//! How a guess is colored against the answer
+
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Placement {
+    Correct,
+    Incorrect,
+    Missing,
+}
+
+/// Number of letters in `word`, counting accented letters made of several code points as one
+pub fn letter_count(word: &str) -> usize {
+    word.graphemes(true).count()
+}
+
+/// Colors each letter of `attempt` by how it matches `answer`
+pub fn score_guess(attempt: &str, answer: &str) -> Vec<Placement> {
+    let mut placement = vec![Placement::Missing; letter_count(attempt)];
+    // Letters are compared as grapheme clusters, so an accented letter is a single position
+    let mut corrected_answer: Vec<&str> = answer.graphemes(true).collect();
+
+    // check for correct placement
+    attempt
+        .graphemes(true)
+        .zip(answer.graphemes(true))
+        .enumerate()
+        .for_each(|(i, (attempt_char, answer_char))| {
+            if attempt_char == answer_char {
+                placement[i] = Placement::Correct;
+                // remove the char from our corrected_answer so we can check for misplaced chars without dupes
+                corrected_answer[i] = " ";
+            }
+        });
+
+    // check for misplaced characters
+    attempt
+        .graphemes(true)
+        .enumerate()
+        .for_each(|(i, attempt_char)| {
+            if placement[i] == Placement::Correct {
+                return;
+            }
+            // each letter of the answer colors a single tile, so a doubled guess letter only
+            // scores yellow twice if the answer has it twice
+            if let Some(j) = corrected_answer.iter().position(|c| *c == attempt_char) {
+                placement[i] = Placement::Incorrect;
+                corrected_answer[j] = " ";
+            }
+        });
+
+    placement
+}
+
+pub fn to_emoji(placement: &[Placement]) -> String {
+    placement
+        .iter()
+        .map(|p| match p {
+            Placement::Correct => '🟩',
+            Placement::Incorrect => '🟨',
+            Placement::Missing => '⬛',
+        })
+        .collect()
+}
@@ -0,0 +1,309 @@
+//! A simple solver, guessing whichever remaining candidate covers the most common letters
+
+use std::collections::HashMap;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::scoring::{score_guess, Placement};
+
+/// Plays a game against `answer` using `words` as the possible answers, returning the guesses
+/// it took, the last one being the answer. `answer` has to be one of `words`, or `None` is
+/// returned once they run out.
+pub fn solve<'a>(answer: &str, words: &[&'a str]) -> Option<Vec<&'a str>> {
+    let mut candidates = words.to_vec();
+    let mut guesses = Vec::new();
+
+    while let Some(guess) = best_guess(&candidates) {
+        guesses.push(guess);
+        let placement = score_guess(guess, answer);
+        if placement.iter().all(|p| *p == Placement::Correct) {
+            return Some(guesses);
+        }
+        // A guess that isn't the answer can't score like it, so each round removes at least it
+        candidates.retain(|word| score_guess(guess, word) == placement);
+    }
+    None
+}
+
+/// Words that could still be the answer given the `(colors, word)` guesses made so far
+pub fn candidates<'a>(guesses: &[(String, String)], words: &[&'a str]) -> Vec<&'a str> {
+    let mut letters = LetterIds::default();
+    let Some(constraints) = guesses
+        .iter()
+        .map(|(colors, guess)| Constraint::new(colors, guess, &mut letters))
+        .collect::<Option<Vec<_>>>()
+    else {
+        // Colors no guess can score, which no word fits
+        return Vec::new();
+    };
+    words
+        .iter()
+        .copied()
+        .filter(|candidate| {
+            let word = letters.word(candidate);
+            constraints
+                .iter()
+                .all(|constraint| constraint.fits(candidate, &word))
+        })
+        .collect()
+}
+
+/// Rough chance of winning with `guesses_left` guesses when `candidates` words are left.
+///
+/// Playing one candidate after another always wins within `candidates` guesses, so this is
+/// the share of the candidates that can be tried before running out. Real players narrow down
+/// faster than that, which makes it a pessimistic but cheap estimate.
+pub fn win_chance(candidates: usize, guesses_left: usize) -> f64 {
+    if candidates == 0 {
+        return 0.0;
+    }
+    (guesses_left as f64 / candidates as f64).min(1.0)
+}
+
+/// Candidate whose distinct letters are the most common among all candidates
+pub fn best_guess<'a>(candidates: &[&'a str]) -> Option<&'a str> {
+    let mut letters = LetterIds::default();
+    let words: Vec<Vec<usize>> = candidates
+        .iter()
+        .map(|word| {
+            let mut distinct = letters.word(word).letters;
+            distinct.sort_unstable();
+            distinct.dedup();
+            distinct
+        })
+        .collect();
+    let mut frequencies = vec![0; letters.ids.len()];
+    for word in &words {
+        for letter in word {
+            frequencies[*letter] += 1;
+        }
+    }
+
+    candidates
+        .iter()
+        .copied()
+        .zip(&words)
+        .max_by_key(|(_, word)| {
+            word.iter()
+                .map(|letter| frequencies[*letter])
+                .sum::<usize>()
+        })
+        .map(|(candidate, _)| candidate)
+}
+
+/// Numbers each letter the first time it's seen, so words can be compared letter by letter and
+/// by bitmask instead of as strings
+#[derive(Default)]
+struct LetterIds<'a> {
+    ids: HashMap<&'a str, usize>,
+}
+
+/// A word as its letters' ids
+struct Word {
+    letters: Vec<usize>,
+    /// Bit `id` set for each letter with an id under 64. Alphabets have fewer letters than that,
+    /// the ones over it are only told apart by `letters`.
+    mask: u64,
+}
+
+impl<'a> LetterIds<'a> {
+    fn word(&mut self, word: &'a str) -> Word {
+        let mut mask = 0;
+        let mut id_of = |letter| {
+            let next = self.ids.len();
+            let id = *self.ids.entry(letter).or_insert(next);
+            mask |= letter_bit(id);
+            id
+        };
+        // Every ASCII letter is a grapheme of its own, so splitting them up is much cheaper than
+        // finding grapheme boundaries, which is most of the time spent on a large dictionary
+        let letters = if word.is_ascii() {
+            (0..word.len()).map(|i| id_of(&word[i..i + 1])).collect()
+        } else {
+            word.graphemes(true).map(id_of).collect()
+        };
+        Word { letters, mask }
+    }
+}
+
+fn letter_bit(id: usize) -> u64 {
+    1u64.checked_shl(id as u32).unwrap_or(0)
+}
+
+/// What a guess's colors say about the answer
+struct Constraint<'a> {
+    guess: &'a str,
+    placement: Vec<Placement>,
+    letters: Vec<usize>,
+    /// Letters colored green or yellow, which the answer has
+    present: u64,
+    /// Letters colored gray every time they were guessed, which the answer doesn't have
+    absent: u64,
+}
+
+impl<'a> Constraint<'a> {
+    /// `None` if `colors` aren't tiles scored for `guess`
+    fn new(colors: &str, guess: &'a str, letters: &mut LetterIds<'a>) -> Option<Self> {
+        let placement = colors
+            .chars()
+            .map(|tile| match tile {
+                '🟩' => Some(Placement::Correct),
+                '🟨' => Some(Placement::Incorrect),
+                '⬛' => Some(Placement::Missing),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let word = letters.word(guess);
+        if placement.len() != word.letters.len() {
+            return None;
+        }
+
+        let mut present = 0;
+        let mut guessed_gray = 0;
+        for (letter, tile) in word.letters.iter().zip(&placement) {
+            match tile {
+                Placement::Missing => guessed_gray |= letter_bit(*letter),
+                _ => present |= letter_bit(*letter),
+            }
+        }
+        Some(Self {
+            guess,
+            placement,
+            letters: word.letters,
+            present,
+            absent: guessed_gray & !present,
+        })
+    }
+
+    /// Whether `candidate` scores `placement` for the guess. The cheap checks go first, so most
+    /// words are ruled out before being scored.
+    fn fits(&self, candidate: &str, word: &Word) -> bool {
+        if word.mask & self.present != self.present || word.mask & self.absent != 0 {
+            return false;
+        }
+        // A letter is green exactly where the guess and the answer have the same one
+        let greens_match = self.placement.iter().enumerate().all(|(i, tile)| {
+            let same = word.letters.get(i) == Some(&self.letters[i]);
+            same == (*tile == Placement::Correct)
+        });
+        greens_match && score_guess(self.guess, candidate) == self.placement
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    use crate::scoring::to_emoji;
+
+    /// `candidates` as it was before the bitmasks, scoring every word against every guess
+    fn naive_candidates<'a>(guesses: &[(String, String)], words: &[&'a str]) -> Vec<&'a str> {
+        words
+            .iter()
+            .copied()
+            .filter(|candidate| {
+                guesses
+                    .iter()
+                    .all(|(colors, guess)| to_emoji(&score_guess(guess, candidate)) == *colors)
+            })
+            .collect()
+    }
+
+    /// `best_guess` as it was before the letter ids
+    fn naive_best_guess<'a>(candidates: &[&'a str]) -> Option<&'a str> {
+        let distinct_letters = |word: &'a str| {
+            let mut letters: Vec<&str> = word.graphemes(true).collect();
+            letters.sort_unstable();
+            letters.dedup();
+            letters
+        };
+        let mut frequencies: HashMap<&str, usize> = HashMap::new();
+        for word in candidates {
+            for letter in distinct_letters(word) {
+                *frequencies.entry(letter).or_default() += 1;
+            }
+        }
+        candidates.iter().copied().max_by_key(|word| {
+            distinct_letters(word)
+                .iter()
+                .map(|letter| frequencies[letter])
+                .sum::<usize>()
+        })
+    }
+
+    const WORDS: [&str; 16] = [
+        "crane",
+        "slate",
+        "trace",
+        "eerie",
+        "cocoa",
+        "llama",
+        "allay",
+        "yells",
+        "éclat",
+        "ñandú",
+        "cañón",
+        "песок",
+        "сосна",
+        "lever",
+        "revel",
+        "cranes",
+    ];
+
+    #[test]
+    fn optimized_candidates_match_naive() {
+        let mut rng = StdRng::seed_from_u64(121);
+        for _ in 0..500 {
+            let answer = WORDS.choose(&mut rng).unwrap();
+            let count = rng.gen_range(0..4);
+            let guesses: Vec<(String, String)> = WORDS
+                .choose_multiple(&mut rng, count)
+                .map(|guess| (to_emoji(&score_guess(guess, answer)), guess.to_string()))
+                .collect();
+            assert_eq!(
+                candidates(&guesses, &WORDS),
+                naive_candidates(&guesses, &WORDS),
+                "{guesses:?}"
+            );
+        }
+
+        // Colors that contradict each other or that no guess can score fit no word either way
+        for colors in ["🟩⬛⬛⬛⬛", "🟩🟩", "🟥🟩🟩🟩🟩"] {
+            let guesses = [
+                (colors.to_string(), "crane".to_string()),
+                ("⬛⬛⬛⬛⬛".to_string(), "cocoa".to_string()),
+            ];
+            assert_eq!(
+                candidates(&guesses, &WORDS),
+                naive_candidates(&guesses, &WORDS)
+            );
+            assert!(candidates(&guesses, &WORDS).is_empty());
+        }
+    }
+
+    #[test]
+    fn optimized_best_guess_matches_naive() {
+        let mut rng = StdRng::seed_from_u64(121);
+        assert_eq!(best_guess(&[]), None);
+        for _ in 0..200 {
+            let count = rng.gen_range(1..WORDS.len());
+            let words: Vec<&str> = WORDS.choose_multiple(&mut rng, count).copied().collect();
+            assert_eq!(best_guess(&words), naive_best_guess(&words), "{words:?}");
+        }
+    }
+
+    #[test]
+    fn win_chance_is_the_share_of_candidates_left_to_try() {
+        assert_eq!(win_chance(1, 1), 1.0);
+        assert_eq!(win_chance(3, 5), 1.0);
+        assert_eq!(win_chance(10, 5), 0.5);
+        assert_eq!(win_chance(20, 5), 0.25);
+        assert_eq!(win_chance(4, 0), 0.0);
+        // No word fits, as with a guess typed against a word removed since
+        assert_eq!(win_chance(0, 5), 0.0);
+    }
+}
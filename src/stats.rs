@@ -0,0 +1,771 @@
+//! Results of past games, persisted to `stats.json` in the assets directory
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+use chrono::{Datelike, NaiveDate, TimeZone, Utc};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use teloxide::types::User;
+
+use crate::persist;
+
+/// Results recorded so far
+static STATS: OnceCell<RwLock<Stats>> = OnceCell::new();
+/// Flag to indicate to our worker thread that the stats have been updated
+static DIRTY_STATS: AtomicBool = AtomicBool::new(false);
+
+const STATS_FILE: &str = "stats.json";
+/// Version of the stats file format, bumped whenever `migrate` needs to upgrade older files
+const STATS_VERSION: u32 = 1;
+/// How long a week of `Stats::week` lasts
+const WEEK_SECS: u64 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub version: u32,
+    /// Outcome of games, by answer word
+    pub answers: BTreeMap<String, AnswerStats>,
+    /// Results of each player, by Telegram user id
+    pub users: BTreeMap<i64, UserStats>,
+    /// Results of each player in a single chat, by chat id and then user id, for /leaderboard
+    pub chats: BTreeMap<i64, BTreeMap<i64, UserStats>>,
+    /// Players who asked for their games not to be recorded
+    pub opted_out: BTreeSet<i64>,
+    /// Games of the current week, which lasts `WEEK_SECS` from its start
+    pub week: Period,
+    /// The week before `week` if anyone played in it, until its summary is posted
+    pub last_week: Option<Period>,
+    /// Games of the current season, which lasts for a calendar month in UTC
+    pub season: Period,
+    /// Standings of the seasons that ended, oldest first
+    pub past_seasons: Vec<SeasonResult>,
+}
+
+/// Final standings of a season
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SeasonResult {
+    /// Unix timestamps in seconds of the start and end of the season
+    pub started: u64,
+    pub ended: u64,
+    /// Names of the players with their wins, best first
+    pub standings: Vec<(String, u32)>,
+}
+
+/// Results of games played within a period of time
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Period {
+    /// Unix timestamp in seconds the period started at
+    pub started: u64,
+    pub games: u32,
+    /// Games won by each player, by Telegram user id
+    pub wins: BTreeMap<i64, u32>,
+    /// Outcome of games, by answer word
+    pub answers: BTreeMap<String, AnswerStats>,
+}
+
+impl Default for Period {
+    fn default() -> Self {
+        Self {
+            started: crate::unix_now(),
+            games: 0,
+            wins: Default::default(),
+            answers: Default::default(),
+        }
+    }
+}
+
+/// A finished game to be recorded
+pub struct GameRecord<'a> {
+    /// Player who finished the game, if known
+    pub user: Option<&'a User>,
+    /// Chat the game was played in
+    pub chat_id: i64,
+    pub answer: &'a str,
+    pub won: bool,
+    /// How many hints were used during the game
+    pub hints: usize,
+    /// Guesses the game took, including the ones spent on hints
+    pub tries: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnswerStats {
+    pub plays: u32,
+    pub losses: u32,
+    /// Guesses taken over all plays, lost games included
+    pub total_tries: u32,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserStats {
+    /// Name the player had when they last played
+    pub name: String,
+    pub played: u32,
+    pub wins: u32,
+    /// Wins where at least one hint was used, so leaderboards can leave them out
+    pub hinted_wins: u32,
+    /// Wins in a row, ended by any loss. Wins with hints don't count towards it.
+    pub current_streak: u32,
+    pub max_streak: u32,
+    /// Wins by the number of guesses they took, starting at one guess. Grows with the longest win
+    /// so far rather than being a `[u32; 6]`, as presets allow up to 8 guesses and `/wordle`
+    /// takes a guess count, so wins past the 6th guess still need a bucket.
+    pub guess_distribution: Vec<u32>,
+}
+
+impl UserStats {
+    /// Percentage of games won
+    pub fn win_rate(&self) -> u32 {
+        (self.wins * 100)
+            .checked_div(self.played)
+            .unwrap_or_default()
+    }
+
+    fn record(&mut self, user: &User, game: &GameRecord) {
+        self.name = user.full_name();
+        self.played += 1;
+        if game.won {
+            self.wins += 1;
+            if game.hints > 0 {
+                self.hinted_wins += 1;
+            }
+            let tries = game.tries.max(1);
+            if self.guess_distribution.len() < tries {
+                self.guess_distribution.resize(tries, 0);
+            }
+            self.guess_distribution[tries - 1] += 1;
+        }
+        if !game.won {
+            self.current_streak = 0;
+        } else if game.hints == 0 {
+            self.current_streak += 1;
+            self.max_streak = self.max_streak.max(self.current_streak);
+        }
+    }
+}
+
+impl AnswerStats {
+    /// Average guesses a game with this answer took
+    pub fn average_tries(&self) -> f64 {
+        if self.plays == 0 {
+            0.0
+        } else {
+            f64::from(self.total_tries) / f64::from(self.plays)
+        }
+    }
+
+    pub fn loss_rate(&self) -> f64 {
+        if self.plays == 0 {
+            0.0
+        } else {
+            f64::from(self.losses) / f64::from(self.plays)
+        }
+    }
+}
+
+impl Stats {
+    /// Ranks answers by how often they result in a loss, ignoring answers played fewer than
+    /// `min_plays` times so a single unlucky game doesn't top the list
+    pub fn hardest_words(&self, min_plays: u32, limit: usize) -> Vec<(&str, AnswerStats)> {
+        rank_hardest(&self.answers, min_plays, limit)
+    }
+
+    /// Best players of `chat_id` by their longest streak, with their stats. Ties go to the better
+    /// win rate.
+    pub fn chat_leaderboard(&self, chat_id: i64, limit: usize) -> Vec<&UserStats> {
+        let Some(players) = self.chats.get(&chat_id) else {
+            return Vec::new();
+        };
+        let mut players: Vec<&UserStats> = players.values().collect();
+        players.sort_by_key(|player| Reverse((player.max_streak, player.win_rate())));
+        players.truncate(limit);
+        players
+    }
+
+    /// Players with the most wins in `period`, with their names
+    pub fn top_players(&self, period: &Period, limit: usize) -> Vec<(&str, u32)> {
+        let mut players: Vec<(&str, u32)> = period
+            .wins
+            .iter()
+            .map(|(user_id, wins)| {
+                let name = self.users.get(user_id).map_or("someone", |user| &user.name);
+                (name, *wins)
+            })
+            .collect();
+        players.sort_by_key(|(_, wins)| Reverse(*wins));
+        players.truncate(limit);
+        players
+    }
+}
+
+fn rank_hardest(
+    answers: &BTreeMap<String, AnswerStats>,
+    min_plays: u32,
+    limit: usize,
+) -> Vec<(&str, AnswerStats)> {
+    let mut words: Vec<(&str, AnswerStats)> = answers
+        .iter()
+        .filter(|(_, stats)| stats.plays >= min_plays)
+        .map(|(word, stats)| (word.as_str(), *stats))
+        .collect();
+    words.sort_by(|(_, a), (_, b)| b.loss_rate().total_cmp(&a.loss_rate()));
+    words.truncate(limit);
+    words
+}
+
+impl Period {
+    /// The answer lost to most often in this period
+    pub fn hardest_word(&self) -> Option<(&str, AnswerStats)> {
+        rank_hardest(&self.answers, 1, 1).into_iter().next()
+    }
+}
+
+/// Loads the stats saved in `assets_dir`, starting from scratch if there are none yet or they
+/// can't be parsed
+pub fn load(assets_dir: &Path) {
+    let stats = persist::load_json(&assets_dir.join(STATS_FILE))
+        .expect("could not read stats file")
+        .unwrap_or_else(|| Stats {
+            version: STATS_VERSION,
+            ..Default::default()
+        });
+
+    STATS
+        .set(RwLock::new(stats))
+        .expect("STATS already initialized");
+}
+
+/// Upgrades a stats file saved by an older version of the bot
+pub fn migrate(assets_dir: &Path) -> io::Result<()> {
+    let path = assets_dir.join(STATS_FILE);
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let file = File::open(&path)?;
+    // An unparseable file is left to `load`, which moves it aside
+    let Ok(mut stats) = serde_json::from_reader::<_, serde_json::Value>(BufReader::new(file))
+    else {
+        return Ok(());
+    };
+    let version = stats
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or_default();
+
+    // v0 files only lack the version, their fields are read the same way in v1
+    if version == 0 {
+        log::info!("Migrating {} to version {STATS_VERSION}", path.display());
+        stats["version"] = STATS_VERSION.into();
+        persist::save_json(&path, &stats)?;
+    }
+    Ok(())
+}
+
+/// Runs `f` with read access to the stats
+pub fn read<T>(f: impl FnOnce(&Stats) -> T) -> T {
+    let stats = STATS.get().expect("STATS is not initialized");
+    let stats = stats.read().expect("failed to lock STATS");
+    f(&stats)
+}
+
+/// Records a finished game, unless the player opted out of tracking
+pub fn record_game(game: GameRecord) {
+    let stats = STATS.get().expect("STATS is not initialized");
+    let mut stats = stats.write().expect("failed to lock STATS");
+    let stats = &mut *stats;
+    // Games are always recorded into the week and season they were played in
+    roll_week(stats, crate::unix_now());
+    roll_season(stats, crate::unix_now());
+
+    if let Some(user) = game.user {
+        if stats.opted_out.contains(&user.id) {
+            return;
+        }
+    }
+
+    for answers in [
+        &mut stats.answers,
+        &mut stats.week.answers,
+        &mut stats.season.answers,
+    ] {
+        let answer_stats = answers.entry(game.answer.to_string()).or_default();
+        answer_stats.plays += 1;
+        answer_stats.total_tries += game.tries as u32;
+        if !game.won {
+            answer_stats.losses += 1;
+        }
+    }
+    stats.week.games += 1;
+    stats.season.games += 1;
+
+    if let Some(user) = game.user {
+        if game.won {
+            *stats.week.wins.entry(user.id).or_default() += 1;
+            *stats.season.wins.entry(user.id).or_default() += 1;
+        }
+
+        stats.users.entry(user.id).or_default().record(user, &game);
+        stats
+            .chats
+            .entry(game.chat_id)
+            .or_default()
+            .entry(user.id)
+            .or_default()
+            .record(user, &game);
+    }
+    DIRTY_STATS.store(true, Ordering::Relaxed);
+}
+
+/// Stops recording a player's games and forgets the ones recorded so far
+pub fn opt_out(user_id: i64) {
+    let stats = STATS.get().expect("STATS is not initialized");
+    let mut stats = stats.write().expect("failed to lock STATS");
+
+    stats.opted_out.insert(user_id);
+    stats.users.remove(&user_id);
+    for players in stats.chats.values_mut() {
+        players.remove(&user_id);
+    }
+    stats.week.wins.remove(&user_id);
+    stats.season.wins.remove(&user_id);
+    DIRTY_STATS.store(true, Ordering::Relaxed);
+}
+
+pub fn opt_in(user_id: i64) {
+    let stats = STATS.get().expect("STATS is not initialized");
+    let mut stats = stats.write().expect("failed to lock STATS");
+
+    if stats.opted_out.remove(&user_id) {
+        DIRTY_STATS.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Deletes the stats of `user_id`, or everyone's when `None`, after backing them up to a
+/// timestamped file in `assets_dir`. Opt-outs are kept, they're a preference rather than stats.
+/// Returns the path of the backup.
+pub fn clear(assets_dir: &Path, user_id: Option<i64>) -> io::Result<PathBuf> {
+    let stats = STATS.get().expect("STATS is not initialized");
+    let mut stats = stats.write().expect("failed to lock STATS");
+    let backup = clear_stats(&mut stats, assets_dir, user_id)?;
+    DIRTY_STATS.store(true, Ordering::Relaxed);
+    Ok(backup)
+}
+
+fn clear_stats(stats: &mut Stats, assets_dir: &Path, user_id: Option<i64>) -> io::Result<PathBuf> {
+    // The backup is taken from memory rather than copied, so changes that weren't saved yet are kept
+    let backup = assets_dir.join(format!("stats-{}.json.bak", crate::unix_now()));
+    let file = File::create(&backup)?;
+    serde_json::to_writer(BufWriter::new(file), &*stats)?;
+
+    match user_id {
+        Some(user_id) => {
+            stats.users.remove(&user_id);
+            for players in stats.chats.values_mut() {
+                players.remove(&user_id);
+            }
+            stats.week.wins.remove(&user_id);
+            stats.season.wins.remove(&user_id);
+        }
+        None => {
+            let opted_out = std::mem::take(&mut stats.opted_out);
+            *stats = Stats {
+                version: STATS_VERSION,
+                opted_out,
+                ..Default::default()
+            };
+        }
+    }
+    Ok(backup)
+}
+
+/// Archives the current season if its month is over, so standings read afterwards are the
+/// new season's
+pub fn start_season_if_due() {
+    let stats = STATS.get().expect("STATS is not initialized");
+    let mut stats = stats.write().expect("failed to lock STATS");
+    roll_season(&mut stats, crate::unix_now());
+}
+
+fn roll_season(stats: &mut Stats, now: u64) {
+    if month_start(stats.season.started) == month_start(now) {
+        return;
+    }
+
+    // Months nobody played in aren't worth archiving
+    if stats.season.games > 0 {
+        let standings = stats
+            .top_players(&stats.season, usize::MAX)
+            .into_iter()
+            .map(|(name, wins)| (name.to_string(), wins))
+            .collect();
+        stats.past_seasons.push(SeasonResult {
+            started: stats.season.started,
+            ended: next_month_start(stats.season.started),
+            standings,
+        });
+    }
+    stats.season = Period {
+        started: month_start(now),
+        ..Default::default()
+    };
+    DIRTY_STATS.store(true, Ordering::Relaxed);
+}
+
+/// Unix timestamp of the first second of the UTC month `timestamp` is in
+pub fn month_start(timestamp: u64) -> u64 {
+    let date = utc_date(timestamp);
+    utc_timestamp(date.year(), date.month())
+}
+
+/// Unix timestamp of the first second of the UTC month after the one `timestamp` is in
+pub fn next_month_start(timestamp: u64) -> u64 {
+    let date = utc_date(timestamp);
+    match date.month() {
+        12 => utc_timestamp(date.year() + 1, 1),
+        month => utc_timestamp(date.year(), month + 1),
+    }
+}
+
+fn utc_date(timestamp: u64) -> NaiveDate {
+    Utc.timestamp_opt(timestamp as i64, 0)
+        .single()
+        .expect("timestamp out of range")
+        .naive_utc()
+        .date()
+}
+
+fn utc_timestamp(year: i32, month: u32) -> u64 {
+    let start = NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .expect("invalid month");
+    Utc.from_utc_datetime(&start).timestamp() as u64
+}
+
+/// Starts a new week if the current one is over, so this week's games read afterwards are the
+/// new week's
+pub fn start_week_if_due() {
+    let stats = STATS.get().expect("STATS is not initialized");
+    let mut stats = stats.write().expect("failed to lock STATS");
+    roll_week(&mut stats, crate::unix_now());
+}
+
+fn roll_week(stats: &mut Stats, now: u64) {
+    let elapsed = now.saturating_sub(stats.week.started);
+    if elapsed < WEEK_SECS {
+        return;
+    }
+
+    // Weeks keep starting on the same weekday and hour, even after weeks without games
+    let started = stats.week.started + elapsed / WEEK_SECS * WEEK_SECS;
+    let ended = std::mem::replace(
+        &mut stats.week,
+        Period {
+            started,
+            ..Default::default()
+        },
+    );
+    stats.last_week = (ended.games > 0).then_some(ended);
+    DIRTY_STATS.store(true, Ordering::Relaxed);
+}
+
+/// Forgets the week before the current one, after its summary was posted
+pub fn clear_last_week() {
+    let stats = STATS.get().expect("STATS is not initialized");
+    let mut stats = stats.write().expect("failed to lock STATS");
+
+    stats.last_week = None;
+    DIRTY_STATS.store(true, Ordering::Relaxed);
+}
+
+/// Writes the stats to `assets_dir` if they changed since the last save
+pub fn save_if_dirty(assets_dir: &Path) -> io::Result<()> {
+    if !DIRTY_STATS.swap(false, Ordering::Relaxed) {
+        return Ok(());
+    }
+    // A failed save leaves the changes to the next one
+    save(assets_dir).inspect_err(|_| DIRTY_STATS.store(true, Ordering::Relaxed))
+}
+
+fn save(assets_dir: &Path) -> io::Result<()> {
+    read(|stats| persist::save_json(&assets_dir.join(STATS_FILE), stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: u64 = 1_700_000_000;
+
+    fn stats_with_week(games: u32) -> Stats {
+        Stats {
+            week: Period {
+                started: START,
+                games,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn player(id: i64) -> User {
+        User {
+            id,
+            is_bot: false,
+            first_name: format!("Player {id}"),
+            last_name: None,
+            username: None,
+            language_code: None,
+        }
+    }
+
+    fn game(user: &User, won: bool, hints: usize, tries: usize) -> GameRecord<'_> {
+        GameRecord {
+            user: Some(user),
+            chat_id: 1,
+            answer: "crane",
+            won,
+            hints,
+            tries,
+        }
+    }
+
+    #[test]
+    fn wins_fill_the_guess_distribution() {
+        let user = player(1);
+        let mut stats = UserStats::default();
+        stats.record(&user, &game(&user, true, 0, 3));
+        stats.record(&user, &game(&user, true, 0, 3));
+        stats.record(&user, &game(&user, true, 0, 1));
+        assert_eq!(stats.guess_distribution, [1, 0, 2]);
+
+        // Losses aren't wins by any number of guesses
+        stats.record(&user, &game(&user, false, 0, 6));
+        assert_eq!(stats.guess_distribution, [1, 0, 2]);
+        // Longer presets grow the distribution
+        stats.record(&user, &game(&user, true, 0, 8));
+        assert_eq!(stats.guess_distribution, [1, 0, 2, 0, 0, 0, 0, 1]);
+        assert_eq!((stats.played, stats.wins, stats.win_rate()), (5, 4, 80));
+    }
+
+    #[test]
+    fn loss_resets_the_streak() {
+        let user = player(1);
+        let mut stats = UserStats::default();
+        for _ in 0..3 {
+            stats.record(&user, &game(&user, true, 0, 4));
+        }
+        assert_eq!((stats.current_streak, stats.max_streak), (3, 3));
+
+        stats.record(&user, &game(&user, false, 0, 6));
+        assert_eq!((stats.current_streak, stats.max_streak), (0, 3));
+        stats.record(&user, &game(&user, true, 0, 2));
+        // Hinted wins don't add to the streak, and hinted losses still end it
+        stats.record(&user, &game(&user, true, 1, 3));
+        assert_eq!((stats.current_streak, stats.max_streak), (1, 3));
+        stats.record(&user, &game(&user, false, 1, 6));
+        assert_eq!((stats.current_streak, stats.max_streak), (0, 3));
+    }
+
+    fn played_stats() -> Stats {
+        let mut stats = Stats::default();
+        for id in [1, 2] {
+            let user = player(id);
+            stats
+                .users
+                .entry(id)
+                .or_default()
+                .record(&user, &game(&user, true, 0, 3));
+            stats
+                .chats
+                .entry(1)
+                .or_default()
+                .entry(id)
+                .or_default()
+                .record(&user, &game(&user, true, 0, 3));
+            stats.season.wins.insert(id, 1);
+        }
+        stats.opted_out.insert(3);
+        stats
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("telebot-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn clearing_a_user_backs_up_and_removes_only_them() {
+        let dir = temp_dir("stats-clear-user");
+        let mut stats = played_stats();
+        let backup = clear_stats(&mut stats, &dir, Some(1)).unwrap();
+
+        let backed_up: Stats = serde_json::from_reader(File::open(&backup).unwrap()).unwrap();
+        assert!(backed_up.users.contains_key(&1));
+        assert_eq!(stats.users.keys().collect::<Vec<_>>(), [&2]);
+        assert_eq!(stats.chats[&1].keys().collect::<Vec<_>>(), [&2]);
+        assert_eq!(stats.season.wins.keys().collect::<Vec<_>>(), [&2]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn clearing_everyone_backs_up_and_keeps_opt_outs() {
+        let dir = temp_dir("stats-clear-all");
+        let mut stats = played_stats();
+        let backup = clear_stats(&mut stats, &dir, None).unwrap();
+
+        let backed_up: Stats = serde_json::from_reader(File::open(&backup).unwrap()).unwrap();
+        assert_eq!(backed_up.users.len(), 2);
+        assert!(stats.users.is_empty());
+        assert!(stats.chats.is_empty());
+        assert!(stats.season.wins.is_empty());
+        assert_eq!(stats.version, STATS_VERSION);
+        assert!(stats.opted_out.contains(&3));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn leaderboard_ranks_by_streak_then_win_rate() {
+        let mut stats = Stats::default();
+        let players = stats.chats.entry(1).or_default();
+        for (id, max_streak, played, wins) in
+            [(1, 2, 4, 2), (2, 5, 10, 5), (3, 2, 4, 4), (4, 2, 2, 1)]
+        {
+            players.insert(
+                id,
+                UserStats {
+                    name: format!("Player {id}"),
+                    played,
+                    wins,
+                    max_streak,
+                    ..Default::default()
+                },
+            );
+        }
+        stats
+            .chats
+            .entry(2)
+            .or_default()
+            .insert(5, UserStats::default());
+
+        let names = |limit| -> Vec<&str> {
+            stats
+                .chat_leaderboard(1, limit)
+                .into_iter()
+                .map(|player| player.name.as_str())
+                .collect()
+        };
+        // Players 1 and 4 tie on both, and keep their order by user id
+        assert_eq!(names(10), ["Player 2", "Player 3", "Player 1", "Player 4"]);
+        assert_eq!(names(2), ["Player 2", "Player 3"]);
+        assert!(stats.chat_leaderboard(3, 10).is_empty());
+    }
+
+    #[test]
+    fn season_is_kept_within_its_month() {
+        let mut stats = played_stats();
+        stats.season.started = month_start(START);
+        stats.season.games = 2;
+        roll_season(&mut stats, next_month_start(START) - 1);
+        assert_eq!(stats.season.games, 2);
+        assert!(stats.past_seasons.is_empty());
+    }
+
+    #[test]
+    fn ended_season_is_archived() {
+        let mut stats = played_stats();
+        stats.season.started = month_start(START);
+        stats.season.games = 3;
+        stats.season.wins.insert(2, 2);
+        let next_month = next_month_start(START);
+        roll_season(&mut stats, next_month + 60);
+
+        assert_eq!(stats.season.started, next_month);
+        assert_eq!(stats.season.games, 0);
+        assert!(stats.season.wins.is_empty());
+        let [season] = &stats.past_seasons[..] else {
+            panic!("expected one archived season");
+        };
+        assert_eq!(
+            (season.started, season.ended),
+            (month_start(START), next_month)
+        );
+        assert_eq!(
+            season.standings,
+            [("Player 2".to_string(), 2), ("Player 1".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn seasons_nobody_played_are_not_archived() {
+        let mut stats = Stats::default();
+        stats.season.started = utc_timestamp(2023, 12);
+        // December rolls over into January of the next year
+        roll_season(&mut stats, utc_timestamp(2024, 1) + 60);
+        assert_eq!(stats.season.started, utc_timestamp(2024, 1));
+        assert!(stats.past_seasons.is_empty());
+        assert_eq!(
+            next_month_start(utc_timestamp(2023, 12)),
+            utc_timestamp(2024, 1)
+        );
+    }
+
+    #[test]
+    fn week_is_kept_until_it_ends() {
+        let mut stats = stats_with_week(3);
+        roll_week(&mut stats, START + WEEK_SECS - 1);
+        assert_eq!(stats.week.games, 3);
+        assert!(stats.last_week.is_none());
+    }
+
+    #[test]
+    fn ended_week_moves_to_last_week() {
+        let mut stats = stats_with_week(3);
+        roll_week(&mut stats, START + WEEK_SECS);
+        assert_eq!(stats.week.games, 0);
+        assert_eq!(stats.week.started, START + WEEK_SECS);
+        assert_eq!(stats.last_week.map(|week| week.games), Some(3));
+    }
+
+    #[test]
+    fn weeks_stay_aligned_after_idle_weeks() {
+        let mut stats = stats_with_week(0);
+        roll_week(&mut stats, START + 3 * WEEK_SECS + 60);
+        assert_eq!(stats.week.started, START + 3 * WEEK_SECS);
+        // Nobody played, so there's nothing to summarize
+        assert!(stats.last_week.is_none());
+    }
+
+    #[test]
+    fn v0_stats_are_migrated_to_the_current_version() {
+        let dir = temp_dir("stats-migrate");
+        let path = dir.join(STATS_FILE);
+        // v0 files had no version
+        let v0 = r#"{"users": {"7": {"played": 3, "wins": 2}}, "opted_out": [9]}"#;
+        std::fs::write(&path, v0).unwrap();
+
+        migrate(&dir).unwrap();
+        let stats: Stats = persist::load_json(&path).unwrap().unwrap();
+        assert_eq!(stats.version, STATS_VERSION);
+        assert_eq!(stats.users[&7].played, 3);
+        assert_eq!(stats.users[&7].wins, 2);
+        assert_eq!(stats.opted_out, BTreeSet::from([9]));
+
+        // Migrating again leaves the file as it is
+        let migrated = std::fs::read_to_string(&path).unwrap();
+        migrate(&dir).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), migrated);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,80 @@
+//! Preferences picked by each user, persisted to `user_config.json` in the assets directory
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+use crate::persist;
+
+/// Preferences of every user that changed one from its default
+static USER_CONFIGS: OnceCell<RwLock<UserConfigs>> = OnceCell::new();
+/// Flag to indicate to our worker thread that a user's preferences have been updated
+static DIRTY_USER_CONFIGS: AtomicBool = AtomicBool::new(false);
+
+const USER_CONFIG_FILE: &str = "user_config.json";
+/// Version of the user config file format
+const USER_CONFIG_VERSION: u32 = 1;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct UserConfigs {
+    version: u32,
+    /// Preferences by Telegram user id
+    users: BTreeMap<i64, UserConfig>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserConfig {
+    /// Names of the modes a bare /wordle starts the user's games in
+    pub default_modes: Vec<String>,
+}
+
+pub fn load(assets_dir: &Path) {
+    let configs = persist::load_json(&assets_dir.join(USER_CONFIG_FILE))
+        .expect("could not read user config file")
+        .unwrap_or_else(|| UserConfigs {
+            version: USER_CONFIG_VERSION,
+            ..Default::default()
+        });
+
+    USER_CONFIGS
+        .set(RwLock::new(configs))
+        .expect("USER_CONFIGS already initialized");
+}
+
+/// Preferences of `user_id`, or the defaults if they never changed them
+pub fn get(user_id: i64) -> UserConfig {
+    let configs = USER_CONFIGS.get().expect("USER_CONFIGS is not initialized");
+    let configs = configs.read().expect("failed to lock USER_CONFIGS");
+    configs.users.get(&user_id).cloned().unwrap_or_default()
+}
+
+/// Changes the preferences of `user_id` with `f`
+pub fn update(user_id: i64, f: impl FnOnce(&mut UserConfig)) {
+    let configs = USER_CONFIGS.get().expect("USER_CONFIGS is not initialized");
+    let mut configs = configs.write().expect("failed to lock USER_CONFIGS");
+
+    f(configs.users.entry(user_id).or_default());
+    DIRTY_USER_CONFIGS.store(true, Ordering::Relaxed);
+}
+
+/// Writes the user preferences to disk if they changed since the last save
+pub fn save_if_dirty(assets_dir: &Path) -> io::Result<()> {
+    if !DIRTY_USER_CONFIGS.swap(false, Ordering::Relaxed) {
+        return Ok(());
+    }
+    // A failed save leaves the changes to the next one
+    save(assets_dir).inspect_err(|_| DIRTY_USER_CONFIGS.store(true, Ordering::Relaxed))
+}
+
+fn save(assets_dir: &Path) -> io::Result<()> {
+    let configs = USER_CONFIGS.get().expect("USER_CONFIGS is not initialized");
+    let configs = configs.read().expect("failed to lock USER_CONFIGS");
+    persist::save_json(&assets_dir.join(USER_CONFIG_FILE), &*configs)
+}
@@ -0,0 +1,159 @@
+//! Sorted set of words packed into one buffer. Big dictionaries take a fraction of the memory of a
+//! `BTreeSet<String>`, as a word costs its bytes and a 4 byte offset instead of its own allocation.
+
+use std::fmt;
+
+#[derive(Clone, Default)]
+pub struct WordSet {
+    /// Every word back to back, in sorted order
+    buffer: String,
+    /// Where each word starts in `buffer`, it ends where the next one starts
+    starts: Vec<u32>,
+}
+
+impl WordSet {
+    pub fn len(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Byte range of the `index`th word in `buffer`
+    fn span(&self, index: usize) -> (usize, usize) {
+        let start = self.starts[index] as usize;
+        let end = self
+            .starts
+            .get(index + 1)
+            .map_or(self.buffer.len(), |&end| end as usize);
+        (start, end)
+    }
+
+    fn get(&self, index: usize) -> &str {
+        let (start, end) = self.span(index);
+        &self.buffer[start..end]
+    }
+
+    /// Index of `word`, or where it would be inserted
+    fn position(&self, word: &str) -> Result<usize, usize> {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match self.get(middle).cmp(word) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => return Ok(middle),
+            }
+        }
+        Err(low)
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.position(word).is_ok()
+    }
+
+    /// Adds `word`, returning whether it wasn't in the set yet. The words after it are moved, so
+    /// building a big set from scratch should go through `collect` instead.
+    pub fn insert(&mut self, word: &str) -> bool {
+        let Err(index) = self.position(word) else {
+            return false;
+        };
+        let start = self
+            .starts
+            .get(index)
+            .map_or(self.buffer.len(), |&start| start as usize);
+        self.buffer.insert_str(start, word);
+        for later in &mut self.starts[index..] {
+            *later += word.len() as u32;
+        }
+        self.starts.insert(index, start as u32);
+        true
+    }
+
+    /// Removes `word`, returning whether it was in the set
+    pub fn remove(&mut self, word: &str) -> bool {
+        let Ok(index) = self.position(word) else {
+            return false;
+        };
+        let (start, end) = self.span(index);
+        self.buffer.replace_range(start..end, "");
+        self.starts.remove(index);
+        for later in &mut self.starts[index..] {
+            *later -= word.len() as u32;
+        }
+        true
+    }
+
+    /// Words in sorted order
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        (0..self.len()).map(|index| self.get(index))
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for WordSet {
+    fn from_iter<I: IntoIterator<Item = S>>(words: I) -> Self {
+        let mut words: Vec<S> = words.into_iter().collect();
+        words.sort_unstable_by(|a, b| a.as_ref().cmp(b.as_ref()));
+        words.dedup_by(|a, b| a.as_ref() == b.as_ref());
+
+        let mut set = WordSet {
+            buffer: String::with_capacity(words.iter().map(|word| word.as_ref().len()).sum()),
+            starts: Vec::with_capacity(words.len()),
+        };
+        for word in &words {
+            set.starts.push(set.buffer.len() as u32);
+            set.buffer.push_str(word.as_ref());
+        }
+        set
+    }
+}
+
+impl fmt::Debug for WordSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeSet;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// Short words over a small alphabet, so inserts and removes keep hitting the same words
+    fn random_word(rng: &mut StdRng) -> String {
+        let length = rng.gen_range(1..=4);
+        (0..length).map(|_| rng.gen_range('a'..='d')).collect()
+    }
+
+    #[test]
+    fn behaves_like_btree_set() {
+        let mut rng = StdRng::seed_from_u64(181);
+        let mut expected: BTreeSet<String> = (0..50).map(|_| random_word(&mut rng)).collect();
+        let mut set: WordSet = expected.iter().collect();
+
+        for _ in 0..2000 {
+            let word = random_word(&mut rng);
+            if rng.gen_bool(0.5) {
+                assert_eq!(set.insert(&word), expected.insert(word.clone()), "{word}");
+            } else {
+                assert_eq!(set.remove(&word), expected.remove(&word), "{word}");
+            }
+            assert_eq!(set.contains(&word), expected.contains(&word), "{word}");
+            assert_eq!(set.len(), expected.len());
+        }
+        assert!(set.iter().eq(expected.iter().map(String::as_str)));
+    }
+
+    #[test]
+    fn collect_sorts_and_dedups() {
+        let set: WordSet = ["slate", "crane", "slate", "", "crane", "trace"]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            ["", "crane", "slate", "trace"]
+        );
+        assert!(set.contains("") && !set.contains("cran"));
+    }
+}